@@ -6,14 +6,14 @@ use crate::emit_msg;
 use crate::list::ListItem;
 use crate::stream_formats::Formats;
 use crate::{
-    CONFIG, http, utils,
+    CONFIG, cookies, http, utils,
     video::{FetchedVideo, Video},
 };
 use anyhow::{Result, bail};
 use async_trait::async_trait;
 use futures_util::future::join_all;
 use regex_lite::Regex;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde_json::Value;
 use std::collections::HashSet;
 use std::sync::{LazyLock, OnceLock};
@@ -353,6 +353,24 @@ impl Local {
         })
     }
 
+    /// Signs innertube requests in when a cookies file is configured so that
+    /// members-only and age-restricted videos are accessible.
+    fn authorize(request: RequestBuilder) -> RequestBuilder {
+        const ORIGIN: &str = "https://www.youtube.com";
+
+        match cookies::get()
+            .ok()
+            .flatten()
+            .and_then(|cookies| cookies.authorization(ORIGIN))
+        {
+            Some(authorization) => request
+                .header("Authorization", authorization)
+                .header("X-Origin", ORIGIN)
+                .header("X-Goog-AuthUser", "0"),
+            None => request,
+        }
+    }
+
     async fn get_visitor_data(&self) -> Result<String> {
         static VISITOR_DATA: OnceLock<String> = OnceLock::new();
         static RE: LazyLock<Regex> =
@@ -386,7 +404,7 @@ impl Local {
         let map = data.as_object_mut().unwrap();
         map.insert(String::from("videoId"), Value::String(video_id.to_owned()));
 
-        let mut request = self.client.post(url);
+        let mut request = Self::authorize(self.client.post(url));
 
         if let Ok(visitor_data) = self.get_visitor_data().await {
             request = request.header("X-Goog-Visitor-Id", visitor_data);
@@ -407,7 +425,10 @@ impl Local {
             map.insert((*key).to_string(), Value::String((*value).to_string()));
         }
 
-        let response = self.client.post(url).json(&data).send().await?;
+        let response = Self::authorize(self.client.post(url))
+            .json(&data)
+            .send()
+            .await?;
         Ok(response.error_for_status()?.json().await?)
     }
 
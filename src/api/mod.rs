@@ -276,6 +276,28 @@ impl Format {
         }
     }
 
+    /// The file extension of the stream, derived from its MIME type.
+    pub fn get_extension(&self) -> &str {
+        let (Self::Video { r#type, .. } | Self::Audio { r#type, .. } | Self::Stream { r#type, .. }) =
+            self
+        else {
+            unreachable!()
+        };
+
+        let subtype = r#type
+            .split(';')
+            .next()
+            .and_then(|mime_type| mime_type.split_once('/'))
+            .map_or("", |(_, subtype)| subtype.trim());
+
+        match (self, subtype) {
+            (Self::Audio { .. }, "mp4") => "m4a",
+            (_, "3gpp") => "3gp",
+            (_, "") => "mp4",
+            (_, subtype) => subtype,
+        }
+    }
+
     pub fn get_codec(&self) -> VideoFormat {
         static RE: LazyLock<Regex> =
             LazyLock::new(|| Regex::new(r"(video|audio)\/(?<codec>webm|mp4);").unwrap());
@@ -298,7 +320,7 @@ impl Format {
     }
 }
 
-#[derive(Deserialize, Eq, PartialEq)]
+#[derive(Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
 #[serde(rename_all(deserialize = "lowercase"))]
 pub enum VideoFormat {
     WebM,
@@ -1,17 +1,28 @@
 use crate::api::{ApiBackend, ChannelFeed};
+use crate::autoplay::{self, PendingAutoplay};
 use crate::channel::{Channel, ChannelTab, HideVideos, RefreshState, tabs_to_be_loaded};
+use crate::channel_settings::{ChannelSettings, Setting, SettingEntry};
 use crate::client::FormatAction;
+use crate::config::AutoplayScope;
+use crate::download::{Download, DownloadState};
 use crate::emulator::Emulator;
+use crate::filter::{self, FilterRule, FilterScope, Filters};
 use crate::help::HelpWindowState;
 use crate::import::{self, ImportItem};
 use crate::input::InputMode;
 use crate::list::{ListItem, Selectable, SelectionItem, SelectionList, StatefulList};
 use crate::message::Message;
-use crate::mpv::{PlaybackPhase, PlaybackState, PlaybackUpdate};
+use crate::mpv::{
+    PlaybackChapter, PlaybackEndReason, PlaybackKind, PlaybackPhase, PlaybackState, PlaybackUpdate,
+    PlaybackUpdateCause, SleepTimer, TrackKind,
+};
 use crate::progress::{ProgressActions, ProgressTracker};
 use crate::search::{Search, SearchDirection, SearchState};
+use crate::stats::{ChannelStats, Stats};
 use crate::stream_formats::Formats;
 use crate::thumbnail::Thumbnail;
+use crate::tracks::Tracks;
+use crate::trash::{TrashEntry, TrashKind};
 use crate::video::{FetchedVideo, PlaybackSpec, Video, VideoListItem, VideoMetadata};
 use crate::{CLAP_ARGS, CONFIG, IoEvent, database, utils};
 use anyhow::{Context, Result};
@@ -37,7 +48,9 @@ pub struct App {
     pub thumbnail: Option<Thumbnail>,
     pub message: Message,
     pub playback_state: PlaybackState,
+    pub autoplay: Option<PendingAutoplay>,
     progress_tracker: ProgressTracker,
+    external_progress_tracker: ProgressTracker,
     pub input: String,
     pub input_mode: InputMode,
     pub input_idx: usize,
@@ -45,13 +58,29 @@ pub struct App {
     pub cursor_position: u16,
     pub help_window_state: HelpWindowState,
     pub import_state: SelectionList<ImportItem>,
+    import_tags: HashMap<String, Vec<String>>,
     new_video_ids: HashSet<String>,
     channels_with_new_videos: HashSet<String>,
     search: Search,
     pub hide_videos: HideVideos,
+    pub show_dismissed: bool,
+    pub show_filtered: bool,
     io_tx: UnboundedSender<IoEvent>,
     pub channel_selection: SelectionList<Channel>,
+    pub trash: StatefulList<TrashEntry, ListState>,
+    pub downloads: StatefulList<Download, ListState>,
+    pub filter_rules: StatefulList<FilterRule, ListState>,
+    filter_scope: FilterScope,
+    pub channel_settings: StatefulList<SettingEntry, ListState>,
+    pub settings_channel_name: String,
+    settings_channel_id: String,
+    settings: ChannelSettings,
+    pub stats: Option<Stats>,
+    pub stats_channels: StatefulList<ChannelStats, TableState>,
+    pub chapters: StatefulList<PlaybackChapter, ListState>,
+    pub tracks: Tracks,
     pub stream_formats: Formats,
+    pub offline: bool,
 }
 
 impl App {
@@ -73,7 +102,9 @@ impl App {
             thumbnail: None,
             message: Message::new(),
             playback_state: PlaybackState::default(),
+            autoplay: None,
             progress_tracker: ProgressTracker::default(),
+            external_progress_tracker: ProgressTracker::default(),
             input: String::default(),
             input_mode: InputMode::Normal,
             input_idx: 0,
@@ -83,11 +114,27 @@ impl App {
             new_video_ids: HashSet::default(),
             channels_with_new_videos: HashSet::default(),
             hide_videos,
+            show_dismissed: false,
+            show_filtered: false,
             io_tx,
             help_window_state: HelpWindowState::new(),
             import_state: SelectionList::default(),
+            import_tags: HashMap::default(),
             channel_selection: SelectionList::default(),
+            trash: StatefulList::default(),
+            downloads: StatefulList::default(),
+            filter_rules: StatefulList::default(),
+            filter_scope: FilterScope::Global,
+            channel_settings: StatefulList::default(),
+            settings_channel_name: String::default(),
+            settings_channel_id: String::default(),
+            settings: ChannelSettings::default(),
+            stats: None,
+            stats_channels: StatefulList::default(),
+            chapters: StatefulList::default(),
+            tracks: Tracks::default(),
             stream_formats: Formats::default(),
+            offline: false,
         };
 
         if CLAP_ARGS.contains_id("tick_rate")
@@ -119,6 +166,9 @@ impl App {
 
         app.tags = SelectionList::new(database::get_tags(&app.conn)?);
 
+        let retention = CONFIG.trash_retention_days * 24 * 60 * 60;
+        database::purge_trash(&app.conn, utils::now()?.saturating_sub(retention))?;
+
         Ok(app)
     }
 
@@ -133,6 +183,13 @@ impl App {
             self.set_error_message(&e.to_string());
             return;
         }
+
+        if let Some(tags) = self.import_tags.remove(&channel.channel_id)
+            && let Err(e) = self.add_channel_to_tags(&channel.channel_id, &tags)
+        {
+            self.set_error_message(&e.to_string());
+        }
+
         self.channels.items.push(channel);
         self.add_tabs(channel_feed);
     }
@@ -157,7 +214,7 @@ impl App {
         let channel_id = channel_feed.channel_id.as_ref().unwrap();
 
         let present_videos: Vec<VideoListItem> =
-            match database::get_videos(&self.conn, channel_id, tab) {
+            match database::get_videos(&self.conn, channel_id, tab, true, &Filters::default()) {
                 Ok(videos) => videos,
                 Err(e) => {
                     self.set_error_message(&e.to_string());
@@ -165,6 +222,14 @@ impl App {
                 }
             };
 
+        match database::get_dismissed_video_ids(&self.conn) {
+            Ok(dismissed) => videos.retain(|video| !dismissed.contains(&video.video_id)),
+            Err(e) => {
+                self.set_error_message(&e.to_string());
+                return;
+            }
+        }
+
         // Videos sharing the same published text has the same unix time. Because of this, to
         // preserve a new video's order relative to the other videos sharing the same published
         // text, they need to be replaced in the database.
@@ -226,24 +291,31 @@ impl App {
             && let Some(tab) = self.tabs.get_selected()
         {
             let channel_id = current_channel.channel_id.clone();
-            let present_videos = if self.hide_videos.is_empty() {
-                tab.videos
-                    .items
-                    .iter()
-                    .map(|video| video.video_id.clone())
-                    .collect()
-            } else {
-                match database::get_videos(&self.conn, &current_channel.channel_id, tab.variant) {
-                    Ok(videos) => videos
-                        .into_iter()
-                        .map(|VideoListItem { video, .. }| video.video_id)
-                        .collect(),
-                    Err(e) => {
-                        self.set_error_message(&e.to_string());
-                        return;
+            let present_videos =
+                if self.hide_videos.is_empty() && self.show_dismissed && self.show_filtered {
+                    tab.videos
+                        .items
+                        .iter()
+                        .map(|video| video.video_id.clone())
+                        .collect()
+                } else {
+                    match database::get_videos(
+                        &self.conn,
+                        &current_channel.channel_id,
+                        tab.variant,
+                        true,
+                        &Filters::default(),
+                    ) {
+                        Ok(videos) => videos
+                            .into_iter()
+                            .map(|VideoListItem { video, .. }| video.video_id)
+                            .collect(),
+                        Err(e) => {
+                            self.set_error_message(&e.to_string());
+                            return;
+                        }
                     }
-                }
-            };
+                };
 
             self.dispatch(IoEvent::LoadMoreVideos(
                 channel_id,
@@ -258,7 +330,7 @@ impl App {
         if let Some(videos) = self.tabs.get_videos_mut()
             && let Some(idx) = videos.state.selected()
         {
-            if let Err(e) = database::delete_video(&self.conn, &videos.items[idx].video_id) {
+            if let Err(e) = database::trash_video(&mut self.conn, &videos.items[idx].video_id) {
                 self.set_error_message(&e.to_string());
                 return;
             }
@@ -268,6 +340,49 @@ impl App {
         }
     }
 
+    pub fn dismiss_selected_video(&mut self) {
+        let show_dismissed = self.show_dismissed;
+
+        if let Some(videos) = self.tabs.get_videos_mut()
+            && let Some(idx) = videos.state.selected()
+        {
+            if let Err(e) = database::dismiss_video(&self.conn, &videos.items[idx].video_id) {
+                self.set_error_message(&e.to_string());
+                return;
+            }
+
+            if show_dismissed {
+                videos.items[idx].dismissed = true;
+            } else {
+                videos.items.remove(idx);
+                videos.check_bounds();
+                self.on_change_video();
+            }
+        }
+    }
+
+    pub fn undismiss_selected_video(&mut self) {
+        let Some(video) = self.get_current_video() else {
+            return;
+        };
+
+        if !video.dismissed {
+            self.set_warning_message("The selected video isn't dismissed");
+            return;
+        }
+
+        let video_id = video.video_id.clone();
+
+        if let Err(e) = database::undismiss_video(&self.conn, &video_id) {
+            self.set_error_message(&e.to_string());
+            return;
+        }
+
+        if let Some(video) = self.tabs.get_video_mut_by_id(&video_id) {
+            video.dismissed = false;
+        }
+    }
+
     fn move_channel_to_top(&mut self, channel_id: &str) {
         let id_of_current_channel = self
             .get_current_channel()
@@ -370,6 +485,14 @@ impl App {
             .and_then(|id| self.tabs.get_video_by_id(id))
             .and_then(|video| video.length.map(u64::from))
             .or(state.duration);
+
+        if matches!(cause, PlaybackUpdateCause::SpeedChanged)
+            && let Some(metadata) = &state.metadata
+        {
+            let speed = state.speed.filter(|speed| *speed != 1.0);
+            self.update_channel_settings(&metadata.channel_id, |settings| settings.speed = speed);
+        }
+
         let actions = video_id.as_deref().map(|video_id| {
             self.progress_tracker.handle_update(
                 video_id,
@@ -380,16 +503,188 @@ impl App {
             )
         });
 
+        // A new video or the loss of the session replaces the next video.
+        if matches!(cause, PlaybackUpdateCause::Loading) || state.kind.is_none() {
+            self.autoplay = None;
+        }
+
+        let ended_kind = state
+            .kind
+            .filter(|_| matches!(cause, PlaybackUpdateCause::Ended(PlaybackEndReason::Eof)));
+        // The sleep timer stops playback at the end of the video instead.
+        let sleep_at_end = matches!(state.sleep_timer, Some(SleepTimer::EndOfVideo));
+        let metadata = state.metadata.clone();
+
         self.playback_state = state;
 
         if let Some(video_id) = video_id
             && let Some(actions) = actions
+            && let Some(position) = self.apply_progress_actions(&video_id, actions)
         {
-            self.apply_progress_actions(&video_id, actions);
+            self.progress_tracker.mark_saved(position);
+        }
+
+        if let Some(kind) = ended_kind
+            && let Some(metadata) = metadata
+        {
+            self.queue_autoplay(kind, &metadata, sleep_at_end);
         }
     }
 
-    fn apply_progress_actions(&mut self, video_id: &str, actions: ProgressActions) {
+    /// Starts the countdown to the next video after a video reaches its end.
+    fn queue_autoplay(&mut self, kind: PlaybackKind, metadata: &VideoMetadata, sleep: bool) {
+        if CONFIG.autoplay == AutoplayScope::Off {
+            return;
+        }
+
+        let next = if sleep {
+            None
+        } else {
+            self.get_next_video_spec(metadata)
+        };
+
+        match next {
+            Some(spec) => {
+                self.autoplay = Some(PendingAutoplay::new(spec, kind, CONFIG.autoplay_countdown));
+                self.check_autoplay();
+            }
+            // The video session is kept idle for autoplay.
+            None if kind == PlaybackKind::Video => self.dispatch(IoEvent::StopPlayback),
+            None => (),
+        }
+    }
+
+    fn get_next_video_spec(&self, metadata: &VideoMetadata) -> Option<PlaybackSpec> {
+        let video_id = &metadata.video_id;
+        let get_spec = |video: &VideoListItem| {
+            let channel = video.channel_name.as_deref().unwrap_or(&metadata.channel);
+            self.get_video_spec(video, channel)
+        };
+
+        match CONFIG.autoplay {
+            AutoplayScope::Off => None,
+            AutoplayScope::List => self
+                .tabs
+                .items
+                .iter()
+                .find_map(|tab| autoplay::next_unwatched(&tab.videos.items, video_id, false))?
+                .map(get_spec),
+            AutoplayScope::Channel => {
+                let filters = database::get_filters(&self.conn).ok()?;
+                let settings =
+                    database::get_channel_settings(&self.conn, &metadata.channel_id).ok()?;
+
+                tabs_to_be_loaded()
+                    .filter(|tab| !settings.is_hidden(*tab))
+                    .find_map(|tab| {
+                        let videos = database::get_videos(
+                            &self.conn,
+                            &metadata.channel_id,
+                            tab,
+                            false,
+                            &filters,
+                        )
+                        .ok()?;
+
+                        autoplay::next_unwatched(&videos, video_id, true)
+                            .map(|video| video.map(get_spec))
+                    })?
+            }
+            AutoplayScope::Latest => self
+                .get_latest_videos()
+                .ok()?
+                .iter()
+                .find_map(|(videos, _)| autoplay::next_unwatched(videos, video_id, false))?
+                .map(get_spec),
+        }
+    }
+
+    /// Plays the next video once the countdown is over.
+    pub fn check_autoplay(&mut self) {
+        if !self.autoplay.as_ref().is_some_and(PendingAutoplay::is_due) {
+            return;
+        }
+
+        let Some(PendingAutoplay { spec, kind, .. }) = self.autoplay.take() else {
+            return;
+        };
+
+        match kind {
+            PlaybackKind::Audio => self.dispatch(IoEvent::PlayAudioUsingYtdlp(spec)),
+            PlaybackKind::Video => self.dispatch(IoEvent::AutoplayVideo(spec)),
+        }
+    }
+
+    /// Cancels the countdown and closes the video session kept idle for the
+    /// next video.
+    fn cancel_autoplay(&mut self) {
+        if let Some(autoplay) = self.autoplay.take() {
+            if autoplay.kind == PlaybackKind::Video {
+                self.dispatch(IoEvent::StopPlayback);
+            }
+
+            self.set_message_with_default_duration("Autoplay cancelled");
+        }
+    }
+
+    /// Tracks the progress of a video played by an external player, such as
+    /// VLC. It has its own tracker and doesn't replace the playback state of
+    /// mpv, which the playback commands control.
+    pub fn handle_external_playback_update(&mut self, update: PlaybackUpdate) {
+        let PlaybackUpdate { state, cause } = update;
+
+        let Some(video_id) = state.metadata.map(|metadata| metadata.video_id) else {
+            return;
+        };
+        let duration = self
+            .tabs
+            .get_video_by_id(&video_id)
+            .and_then(|video| video.length.map(u64::from))
+            .or(state.duration);
+
+        let actions = self.external_progress_tracker.handle_update(
+            &video_id,
+            state.elapsed,
+            duration,
+            &cause,
+            CONFIG.watched_threshold,
+        );
+
+        if let Some(position) = self.apply_progress_actions(&video_id, actions) {
+            self.external_progress_tracker.mark_saved(position);
+        }
+    }
+
+    /// Changes the stored settings of the channel, such as the speed or the
+    /// subtitle language chosen during playback, so its next video starts
+    /// with them.
+    fn update_channel_settings(
+        &mut self,
+        channel_id: &str,
+        update: impl FnOnce(&mut ChannelSettings),
+    ) {
+        let result =
+            database::get_channel_settings(&self.conn, channel_id).and_then(|mut settings| {
+                update(&mut settings);
+                database::set_channel_settings(&self.conn, channel_id, &settings)?;
+                Ok(settings)
+            });
+
+        match result {
+            Ok(settings) if self.settings_channel_id == channel_id => {
+                self.channel_settings.items = settings.entries();
+                self.settings = settings;
+            }
+            Ok(_) => (),
+            Err(e) => self.set_error_message(&e.to_string()),
+        }
+    }
+
+    /// Applies the actions of a progress tracker and returns the position
+    /// that was saved.
+    fn apply_progress_actions(&mut self, video_id: &str, actions: ProgressActions) -> Option<u64> {
+        let mut saved_position = None;
+
         if let Some(save) = actions.previous_save {
             self.persist_progress(&save.video_id, save.position);
         }
@@ -403,12 +698,14 @@ impl App {
         if let Some(position) = actions.save_position
             && self.persist_progress(video_id, position)
         {
-            self.progress_tracker.mark_saved(position);
+            saved_position = Some(position);
         }
 
         if actions.mark_watched {
             self.set_watched(video_id, true);
         }
+
+        saved_position
     }
 
     fn persist_progress(&mut self, video_id: &str, position: u64) -> bool {
@@ -437,9 +734,23 @@ impl App {
         self.reload_videos();
     }
 
+    pub fn toggle_dismissed(&mut self) {
+        self.show_dismissed = !self.show_dismissed;
+        self.reload_videos();
+    }
+
+    pub fn toggle_filtered(&mut self) {
+        self.show_filtered = !self.show_filtered;
+        self.reload_videos();
+    }
+
     pub fn play_video(&mut self) {
         if let Some(spec) = self.get_current_video_spec() {
-            self.dispatch(IoEvent::PlayUsingYtdlp(spec));
+            if spec.channel_settings.audio_only {
+                self.dispatch(IoEvent::PlayAudioUsingYtdlp(spec));
+            } else {
+                self.dispatch(IoEvent::PlayUsingYtdlp(spec));
+            }
         }
     }
 
@@ -451,9 +762,14 @@ impl App {
             None => &self.get_current_channel()?.channel_name,
         };
 
-        Some(PlaybackSpec {
+        Some(self.get_video_spec(video, channel))
+    }
+
+    fn get_video_spec(&self, video: &VideoListItem, channel: &str) -> PlaybackSpec {
+        PlaybackSpec {
             metadata: VideoMetadata {
                 video_id: video.video_id.clone(),
+                channel_id: video.channel_id.clone(),
                 title: video.title.clone(),
                 channel: channel.to_owned(),
             },
@@ -461,7 +777,10 @@ impl App {
                 .resume_playback
                 .then(|| video.resume_position())
                 .flatten(),
-        })
+            channel_settings: database::get_channel_settings(&self.conn, &video.channel_id)
+                .unwrap_or_default(),
+            local_file: video.local_file.clone().filter(|path| path.exists()),
+        }
     }
 
     pub fn play_audio(&mut self) {
@@ -476,6 +795,127 @@ impl App {
         }
     }
 
+    pub fn download_video(&mut self) {
+        let Some(spec) = self.get_current_video_spec() else {
+            return;
+        };
+
+        if spec.local_file.is_some() {
+            self.set_warning_message("The video is already downloaded");
+            return;
+        }
+
+        if self.queue_download(&spec.metadata) {
+            self.dispatch(IoEvent::Download(spec.metadata));
+        }
+    }
+
+    /// Downloads the streams selected in the format selection window without
+    /// yt-dlp.
+    pub fn download_selected_streams(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let formats = mem::take(&mut self.stream_formats);
+
+        if formats.spec.local_file.is_some() {
+            self.set_warning_message("The video is already downloaded");
+            return;
+        }
+
+        if self.queue_download(&formats.spec.metadata) {
+            self.dispatch(IoEvent::DownloadFormats(Box::new(formats)));
+        }
+    }
+
+    /// Adds the video to the downloads window. Returns `false` if it is
+    /// already being downloaded.
+    fn queue_download(&mut self, metadata: &VideoMetadata) -> bool {
+        match self.downloads.get_mut_by_id(&metadata.video_id) {
+            Some(download) if download.state.is_active() => {
+                self.set_warning_message("The video is already queued for download");
+                return false;
+            }
+            Some(download) => download.state = DownloadState::Queued,
+            None => self.downloads.items.push(Download::new(metadata.clone())),
+        }
+
+        if self.downloads.state.selected().is_none() {
+            self.downloads.select_first();
+        }
+
+        self.set_message_with_default_duration(&format!("Queued for download: {}", metadata.title));
+
+        true
+    }
+
+    pub fn toggle_downloads(&mut self) {
+        if matches!(self.input_mode, InputMode::Downloads) {
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        if self.downloads.items.is_empty() {
+            self.set_warning_message("No downloads");
+            return;
+        }
+
+        self.input_mode = InputMode::Downloads;
+    }
+
+    pub fn cancel_selected_download(&mut self) {
+        if let Some(download) = self.downloads.get_selected()
+            && download.state.is_active()
+        {
+            let video_id = download.metadata.video_id.clone();
+            self.dispatch(IoEvent::CancelDownload(video_id));
+        }
+    }
+
+    pub fn retry_selected_download(&mut self) {
+        let Some(index) = self.downloads.state.selected() else {
+            return;
+        };
+
+        let download = &mut self.downloads.items[index];
+
+        if download.state.can_retry() {
+            download.state = DownloadState::Queued;
+            let metadata = download.metadata.clone();
+            self.dispatch(IoEvent::Download(metadata));
+        }
+    }
+
+    /// Updates the state of a download and records the file of a finished
+    /// download so that the video is played from it.
+    pub fn set_download_state(&mut self, video_id: &str, state: DownloadState) {
+        let Some(download) = self.downloads.get_mut_by_id(video_id) else {
+            return;
+        };
+
+        let title = download.metadata.title.clone();
+
+        match &state {
+            DownloadState::Finished(path) => {
+                if let Err(e) = database::add_download(&self.conn, video_id, path) {
+                    self.set_error_message(&e.to_string());
+                } else {
+                    if let Some(video) = self.tabs.get_video_mut_by_id(video_id) {
+                        video.local_file = Some(path.clone());
+                    }
+
+                    self.set_message_with_default_duration(&format!("Downloaded: {title}"));
+                }
+            }
+            DownloadState::Failed(error) => {
+                self.set_error_message(&format!("Failed to download {title}: {error}"));
+            }
+            _ => (),
+        }
+
+        if let Some(download) = self.downloads.get_mut_by_id(video_id) {
+            download.state = state;
+        }
+    }
+
     pub fn toggle_playback(&mut self) {
         self.dispatch(IoEvent::TogglePlayback);
     }
@@ -492,14 +932,111 @@ impl App {
         self.dispatch(IoEvent::ToggleMute);
     }
 
+    pub fn adjust_speed(&mut self, value: f64) {
+        self.dispatch(IoEvent::AdjustSpeed(value));
+    }
+
+    pub fn reset_speed(&mut self) {
+        self.dispatch(IoEvent::ResetSpeed);
+    }
+
+    pub fn adjust_chapter(&mut self, value: i64) {
+        self.dispatch(IoEvent::AdjustChapter(value));
+    }
+
+    pub fn toggle_chapters(&mut self) {
+        if matches!(self.input_mode, InputMode::ChapterSelection) {
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        if self.playback_state.chapters.is_empty() {
+            self.set_warning_message("The playing video has no chapters");
+            return;
+        }
+
+        self.chapters = StatefulList::with_items(self.playback_state.chapters.clone());
+        if self.playback_state.chapter.is_some() {
+            self.chapters.state.select(self.playback_state.chapter);
+        }
+        self.input_mode = InputMode::ChapterSelection;
+    }
+
+    pub fn toggle_tracks(&mut self) {
+        if matches!(self.input_mode, InputMode::TrackSelection) {
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        if self.playback_state.tracks.is_empty() {
+            self.set_warning_message("The playing video has no audio or subtitle tracks");
+            return;
+        }
+
+        self.tracks = Tracks::new(&self.playback_state.tracks);
+        self.input_mode = InputMode::TrackSelection;
+    }
+
+    /// Switches to the selected track and remembers the language of a chosen
+    /// subtitle track for the channel.
+    pub fn select_track(&mut self) {
+        let list = self.tracks.get_mut_selected_tab();
+        list.select();
+
+        let Some(track) = list.get_selected_item().cloned() else {
+            return;
+        };
+
+        self.dispatch(IoEvent::SetTrack(track.kind, track.id.clone()));
+
+        if track.kind != TrackKind::Subtitle {
+            return;
+        }
+
+        let language = if track.id == "no" {
+            Some("off".to_owned())
+        } else {
+            track.language
+        };
+
+        if let Some(language) = language
+            && let Some(metadata) = &self.playback_state.metadata
+        {
+            let channel_id = metadata.channel_id.clone();
+            self.update_channel_settings(&channel_id, |settings| {
+                settings.subtitle_language = Some(language);
+            });
+        }
+    }
+
+    pub fn seek_to_selected_chapter(&mut self) {
+        if let Some(index) = self.chapters.state.selected() {
+            self.dispatch(IoEvent::SetChapter(index));
+        }
+
+        self.input_mode = InputMode::Normal;
+    }
+
     pub fn stop_playback(&mut self) {
-        self.dispatch(IoEvent::StopPlayback);
+        if self.autoplay.is_some() {
+            self.cancel_autoplay();
+        } else {
+            self.dispatch(IoEvent::StopPlayback);
+        }
     }
 
     pub fn release_video(&mut self) {
         self.dispatch(IoEvent::ReleaseVideo);
     }
 
+    pub fn cycle_sleep_timer(&mut self) {
+        if self.is_player_active() {
+            self.dispatch(IoEvent::CycleSleepTimer);
+        } else {
+            self.set_warning_message("No active playback to set a sleep timer for");
+        }
+    }
+
     pub fn enter_format_selection(&mut self) {
         if let Some(metadata) = self.get_current_video_spec() {
             self.dispatch(IoEvent::FetchFormats(metadata, FormatAction::Select));
@@ -508,7 +1045,13 @@ impl App {
 
     pub fn play_from_formats(&mut self) {
         if let Some(metadata) = self.get_current_video_spec() {
-            self.dispatch(IoEvent::FetchFormats(metadata, FormatAction::PlayVideo));
+            let action = if metadata.channel_settings.audio_only {
+                FormatAction::PlayAudio
+            } else {
+                FormatAction::PlayVideo
+            };
+
+            self.dispatch(IoEvent::FetchFormats(metadata, action));
         }
     }
 
@@ -572,9 +1115,18 @@ impl App {
         let mut tabs = Vec::with_capacity(3);
 
         if let Some(channel) = self.get_current_channel() {
-            for tab in tabs_to_be_loaded() {
+            let filters = database::get_filters(&self.conn)?;
+            let settings = database::get_channel_settings(&self.conn, &channel.channel_id)?;
+
+            for tab in tabs_to_be_loaded().filter(|tab| !settings.is_hidden(*tab)) {
                 tabs.push((
-                    database::get_videos(&self.conn, &channel.channel_id, tab)?,
+                    database::get_videos(
+                        &self.conn,
+                        &channel.channel_id,
+                        tab,
+                        self.show_dismissed,
+                        &filters,
+                    )?,
                     tab,
                 ));
             }
@@ -592,10 +1144,17 @@ impl App {
             .collect();
 
         let mut tabs = Vec::with_capacity(3);
+        let filters = database::get_filters(&self.conn)?;
 
         for tab in tabs_to_be_loaded() {
             tabs.push((
-                database::get_latest_videos(&self.conn, &selected_tags, tab)?,
+                database::get_latest_videos(
+                    &self.conn,
+                    &selected_tags,
+                    tab,
+                    self.show_dismissed,
+                    &filters,
+                )?,
                 tab,
             ));
         }
@@ -614,7 +1173,16 @@ impl App {
                 if preserve_tabs_state {
                     self.tabs.update_videos(tabs);
                 } else {
-                    self.tabs = Tabs::new(tabs);
+                    let default_tab = match self.mode {
+                        Mode::Subscriptions => self.get_current_channel().and_then(|channel| {
+                            database::get_channel_settings(&self.conn, &channel.channel_id)
+                                .ok()?
+                                .default_tab
+                        }),
+                        Mode::LatestVideos => None,
+                    };
+
+                    self.tabs = Tabs::new(tabs, default_tab);
                 }
 
                 for tab in &mut self.tabs.items {
@@ -635,6 +1203,12 @@ impl App {
                         tab.videos.items = tab.videos.items.drain(..).filter(f).collect();
                     }
 
+                    if !self.show_filtered {
+                        let count = tab.videos.items.len();
+                        tab.videos.items.retain(|video| !video.filtered);
+                        tab.filtered = count - tab.videos.items.len();
+                    }
+
                     let mut count = 0;
                     for video in &mut tab.videos.items {
                         if self.new_video_ids.contains(&video.video_id) {
@@ -810,6 +1384,8 @@ impl App {
                 | InputMode::Subscribe
                 | InputMode::TagCreation
                 | InputMode::TagRenaming
+                | InputMode::FilterCreation
+                | InputMode::ChannelSettingEditing
         ) || !self.message.is_empty()
     }
 
@@ -846,8 +1422,15 @@ impl App {
 
     pub fn unsubscribe(&mut self) {
         if let Some(idx) = self.channels.state.selected() {
-            database::delete_channel(&self.conn, &self.channels.items[idx].channel_id).unwrap();
             self.input_mode = InputMode::Normal;
+
+            if let Err(e) =
+                database::trash_channel(&mut self.conn, &self.channels.items[idx].channel_id)
+            {
+                self.set_error_message(&e.to_string());
+                return;
+            }
+
             self.channels.items.remove(idx);
             self.channels.check_bounds();
             self.on_change_channel();
@@ -892,6 +1475,16 @@ impl App {
             },
             InputMode::Import => self.search.search(&mut self.import_state, &self.input),
             InputMode::Tag => self.search.search(&mut self.tags, &self.input),
+            InputMode::Trash => self.search.search(&mut self.trash, &self.input),
+            InputMode::Downloads => self.search.search(&mut self.downloads, &self.input),
+            InputMode::Filter => self.search.search(&mut self.filter_rules, &self.input),
+            InputMode::ChannelSettings => {
+                self.search.search(&mut self.channel_settings, &self.input);
+            }
+            InputMode::ChapterSelection => self.search.search(&mut self.chapters, &self.input),
+            InputMode::TrackSelection => self
+                .search
+                .search(self.tracks.get_mut_selected_tab(), &self.input),
             InputMode::ChannelSelection => {
                 self.search.search(&mut self.channel_selection, &self.input);
             }
@@ -918,6 +1511,17 @@ impl App {
             },
             InputMode::Import => self.search.repeat_last(&mut self.import_state, opposite),
             InputMode::Tag => self.search.repeat_last(&mut self.tags, opposite),
+            InputMode::Trash => self.search.repeat_last(&mut self.trash, opposite),
+            InputMode::Downloads => self.search.repeat_last(&mut self.downloads, opposite),
+            InputMode::Filter => self.search.repeat_last(&mut self.filter_rules, opposite),
+            InputMode::ChannelSettings => {
+                self.search
+                    .repeat_last(&mut self.channel_settings, opposite);
+            }
+            InputMode::ChapterSelection => self.search.repeat_last(&mut self.chapters, opposite),
+            InputMode::TrackSelection => self
+                .search
+                .repeat_last(self.tracks.get_mut_selected_tab(), opposite),
             InputMode::ChannelSelection => self
                 .search
                 .repeat_last(&mut self.channel_selection, opposite),
@@ -1088,6 +1692,16 @@ impl App {
                 },
                 InputMode::Import => self.search.recover_item(&mut self.import_state),
                 InputMode::Tag => self.search.recover_item(&mut self.tags),
+                InputMode::Trash => self.search.recover_item(&mut self.trash),
+                InputMode::Downloads => self.search.recover_item(&mut self.downloads),
+                InputMode::Filter => self.search.recover_item(&mut self.filter_rules),
+                InputMode::ChannelSettings => {
+                    self.search.recover_item(&mut self.channel_settings);
+                }
+                InputMode::ChapterSelection => self.search.recover_item(&mut self.chapters),
+                InputMode::TrackSelection => {
+                    self.search.recover_item(self.tracks.get_mut_selected_tab())
+                }
                 InputMode::ChannelSelection => {
                     self.search.recover_item(&mut self.channel_selection);
                 }
@@ -1108,19 +1722,49 @@ impl App {
         let mut import_state = match format {
             import::Format::YoutubeCsv => import::YoutubeCsv::import(path),
             import::Format::NewPipe => import::NewPipe::import(path),
+            import::Format::Opml => import::Opml::import(path),
+            import::Format::FreeTube => import::FreeTube::import(path),
+            import::Format::LibreTube => import::LibreTube::import(path),
+            import::Format::Invidious => import::Invidious::import(path),
         }
         .with_context(|| "Failed to import")?;
 
-        import_state = import_state
+        let (subscribed, new): (Vec<ImportItem>, Vec<ImportItem>) = import_state
             .into_iter()
-            .filter(|entry| self.channels.find_by_id(&entry.channel_id).is_none())
-            .collect::<Vec<ImportItem>>();
+            .partition(|entry| self.channels.find_by_id(&entry.channel_id).is_some());
+        import_state = new;
+
+        // The folders and groups of the file are applied to the channels that
+        // are already subscribed, so re-importing copies the structure.
+        let mut tagged = 0;
+        for entry in subscribed.iter().filter(|entry| !entry.tags.is_empty()) {
+            self.add_channel_to_tags(&entry.channel_id, &entry.tags)?;
+            tagged += 1;
+        }
+
+        let tagged_message = match tagged {
+            0 => None,
+            1 => Some("Added tags to 1 subscribed channel".to_owned()),
+            count => Some(format!("Added tags to {count} subscribed channels")),
+        };
 
         if import_state.is_empty() {
-            self.set_warning_message("Already subscribed to all the channels in the file");
+            match tagged_message {
+                Some(message) => self.set_message_with_default_duration(&format!(
+                    "Already subscribed to all the channels in the file. {message}"
+                )),
+                None => {
+                    self.set_warning_message("Already subscribed to all the channels in the file");
+                }
+            }
+
             return Ok(());
         }
 
+        if let Some(message) = tagged_message {
+            self.set_message_with_default_duration(&message);
+        }
+
         self.import_state = SelectionList::new(import_state);
         self.import_state.select_all();
 
@@ -1129,6 +1773,19 @@ impl App {
         Ok(())
     }
 
+    pub fn import_watch_history(&mut self, path: &Path, format: import::Format) -> Result<()> {
+        let video_ids = match format {
+            import::Format::Invidious => import::Invidious::import_watch_history(path),
+            _ => anyhow::bail!("Watch history can only be imported from the invidious format"),
+        }
+        .with_context(|| "Failed to import watch history")?;
+
+        database::mark_watched(&mut self.conn, &video_ids)?;
+        self.load_videos(true);
+
+        Ok(())
+    }
+
     pub fn confirm_import(&mut self) {
         self.import_state.items = self
             .import_state
@@ -1146,9 +1803,25 @@ impl App {
     }
 
     pub fn export_subscriptions(&self, path: &Path, format: import::Format) -> Result<()> {
+        let channels = &self.channels.items;
+
         match format {
-            import::Format::YoutubeCsv => import::YoutubeCsv::export(&self.channels.items, path),
-            import::Format::NewPipe => import::NewPipe::export(&self.channels.items, path),
+            import::Format::YoutubeCsv => import::YoutubeCsv::export(channels, path),
+            import::Format::NewPipe => import::NewPipe::export(channels, path),
+            import::Format::Opml => {
+                import::Opml::export(channels, &database::get_tag_relations(&self.conn)?, path)
+            }
+            import::Format::FreeTube => {
+                import::FreeTube::export(channels, &database::get_tag_relations(&self.conn)?, path)
+            }
+            import::Format::LibreTube => {
+                import::LibreTube::export(channels, &database::get_tag_relations(&self.conn)?, path)
+            }
+            import::Format::Invidious => import::Invidious::export(
+                channels,
+                database::get_watched_video_ids(&self.conn)?,
+                path,
+            ),
         }
     }
 
@@ -1170,6 +1843,12 @@ impl App {
             .iter_mut()
             .map(|channel| {
                 channel.sub_state = RefreshState::ToBeRefreshed;
+
+                if !channel.tags.is_empty() {
+                    self.import_tags
+                        .insert(channel.channel_id.clone(), channel.tags.clone());
+                }
+
                 channel.channel_id.clone()
             })
             .collect();
@@ -1231,11 +1910,23 @@ impl App {
         }
     }
 
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+
+        if offline {
+            let ids = self.get_channels_for_refreshing(true);
+
+            if !ids.is_empty() {
+                self.dispatch(IoEvent::RefreshChannels(ids));
+            }
+        }
+    }
+
     pub fn set_message(&mut self, message: &str) {
         self.message.set_message(message);
     }
 
-    pub fn _set_message_with_default_duration(&mut self, message: &str) {
+    pub fn set_message_with_default_duration(&mut self, message: &str) {
         const DEFAULT_DURATION: u64 = 5;
         self.set_message(message);
         self.clear_message_after_duration(DEFAULT_DURATION);
@@ -1337,6 +2028,19 @@ impl App {
         self.input.clear();
     }
 
+    fn add_channel_to_tags(&mut self, channel_id: &str, tags: &[String]) -> Result<()> {
+        for tag in tags {
+            if !self.tags.items.iter().any(|item| item.item == *tag) {
+                database::create_tag(&self.conn, tag)?;
+                self.tags.items.push(SelectionItem::new(tag.clone()));
+            }
+
+            database::add_channel_to_tag(&self.conn, tag, channel_id)?;
+        }
+
+        Ok(())
+    }
+
     pub fn rename_selected_tag(&mut self) {
         if let Some(tag) = self.tags.get_mut_selected() {
             if let Err(e) = database::rename_tag(&self.conn, &tag.item, &self.input) {
@@ -1350,9 +2054,34 @@ impl App {
         self.input.clear();
     }
 
+    /// Reloads the tags from the database, keeping the selected ones and the
+    /// cursor position.
+    fn reload_tags(&mut self) -> Result<()> {
+        let mut tags = SelectionList::new(database::get_tags(&self.conn)?);
+
+        for tag in &mut tags.items {
+            tag.selected = self
+                .tags
+                .items
+                .iter()
+                .any(|item| item.selected && item.item == tag.item);
+        }
+
+        let index = self
+            .tags
+            .get_selected()
+            .and_then(|current| tags.items.iter().position(|tag| tag.item == current.item));
+
+        tags.state.select(index.or(self.tags.state.selected()));
+        tags.check_bounds();
+        self.tags = tags;
+
+        Ok(())
+    }
+
     pub fn delete_selected_tag(&mut self) {
         if let Some(idx) = self.tags.state.selected() {
-            if let Err(e) = database::delete_tag(&self.conn, &self.tags.items[idx].item) {
+            if let Err(e) = database::trash_tag(&mut self.conn, &self.tags.items[idx].item) {
                 self.set_error_message(&e.to_string());
                 return;
             }
@@ -1365,6 +2094,295 @@ impl App {
         }
     }
 
+    pub fn toggle_trash(&mut self) {
+        if matches!(self.input_mode, InputMode::Trash) {
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        match database::get_trash(&self.conn) {
+            Ok(entries) => {
+                self.trash = StatefulList::with_items(entries);
+                self.input_mode = InputMode::Trash;
+            }
+            Err(e) => self.set_error_message(&e.to_string()),
+        }
+    }
+
+    pub fn toggle_filters(&mut self) {
+        if matches!(self.input_mode, InputMode::Filter) {
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        match database::get_filter_rules(&self.conn) {
+            Ok(rules) => {
+                self.filter_rules = StatefulList::with_items(rules);
+                self.input_mode = InputMode::Filter;
+            }
+            Err(e) => self.set_error_message(&e.to_string()),
+        }
+    }
+
+    /// Returns the id and name of the selected channel, or of the channel of
+    /// the selected video in latest videos mode.
+    fn get_channel_of_selection(&self) -> Option<(String, String)> {
+        match self.mode {
+            Mode::Subscriptions => self
+                .get_current_channel()
+                .map(|channel| (channel.channel_id.clone(), channel.channel_name.clone())),
+            Mode::LatestVideos => self
+                .get_current_video()
+                .and_then(|video| Some((video.channel_id.clone(), video.channel_name.clone()?))),
+        }
+    }
+
+    pub fn enter_filter_creation(&mut self, for_channel: bool) {
+        self.filter_scope = if for_channel {
+            let Some((channel_id, channel_name)) = self.get_channel_of_selection() else {
+                self.set_warning_message("No channel is selected");
+                return;
+            };
+
+            FilterScope::Channel {
+                channel_id,
+                channel_name: Some(channel_name),
+            }
+        } else {
+            FilterScope::Global
+        };
+
+        self.prev_input_mode = self.input_mode.clone();
+        self.input_mode = InputMode::FilterCreation;
+        self.message.clear_message();
+        self.input_idx = 0;
+        self.cursor_position = 0;
+    }
+
+    pub fn create_filter_rule(&mut self) {
+        self.input_mode = InputMode::Filter;
+        let input = std::mem::take(&mut self.input);
+
+        let (tag, conditions) = match filter::parse_rule(&input) {
+            Ok(rule) => rule,
+            Err(e) => {
+                self.set_error_message(&format!("Invalid rule: {e}"));
+                return;
+            }
+        };
+
+        let scope = match (tag, &self.filter_scope) {
+            (Some(_), FilterScope::Channel { .. }) => {
+                self.set_error_message("A rule can't be limited to both a channel and a tag");
+                return;
+            }
+            (Some(tag), _) => FilterScope::Tag(tag),
+            (None, scope) => scope.clone(),
+        };
+
+        match database::create_filter_rule(&self.conn, &scope, &conditions) {
+            Ok(id) => {
+                self.filter_rules.items.push(FilterRule {
+                    id,
+                    scope,
+                    conditions,
+                });
+                self.filter_rules.select_last();
+                self.reload_videos();
+            }
+            Err(e) => self.set_error_message(&e.to_string()),
+        }
+    }
+
+    pub fn delete_selected_filter_rule(&mut self) {
+        if let Some(idx) = self.filter_rules.state.selected() {
+            if let Err(e) =
+                database::delete_filter_rule(&self.conn, self.filter_rules.items[idx].id)
+            {
+                self.set_error_message(&e.to_string());
+                return;
+            }
+
+            self.filter_rules.items.remove(idx);
+            self.filter_rules.check_bounds();
+            self.reload_videos();
+        }
+    }
+
+    pub fn toggle_channel_settings(&mut self) {
+        if matches!(self.input_mode, InputMode::ChannelSettings) {
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+
+        let Some((channel_id, channel_name)) = self.get_channel_of_selection() else {
+            self.set_warning_message("No channel is selected");
+            return;
+        };
+
+        match database::get_channel_settings(&self.conn, &channel_id) {
+            Ok(settings) => {
+                self.channel_settings = StatefulList::with_items(settings.entries());
+                self.settings = settings;
+                self.settings_channel_id = channel_id;
+                self.settings_channel_name = channel_name;
+                self.input_mode = InputMode::ChannelSettings;
+            }
+            Err(e) => self.set_error_message(&e.to_string()),
+        }
+    }
+
+    pub fn edit_selected_channel_setting(&mut self) {
+        let Some(setting) = self
+            .channel_settings
+            .get_selected()
+            .map(|entry| entry.setting)
+        else {
+            return;
+        };
+
+        if setting == Setting::AudioOnly {
+            let mut settings = self.settings.clone();
+            settings.audio_only = !settings.audio_only;
+            self.save_channel_settings(settings);
+            return;
+        }
+
+        self.prev_input_mode = self.input_mode.clone();
+        self.input_mode = InputMode::ChannelSettingEditing;
+        self.message.clear_message();
+        self.input = self.settings.value(setting).unwrap_or_default();
+        self.input_idx = self.input.len();
+        self.cursor_position = self.input.width() as u16;
+    }
+
+    pub fn complete_channel_setting_editing(&mut self) {
+        self.input_mode = InputMode::ChannelSettings;
+        let input = std::mem::take(&mut self.input);
+
+        let Some(setting) = self
+            .channel_settings
+            .get_selected()
+            .map(|entry| entry.setting)
+        else {
+            return;
+        };
+
+        let mut settings = self.settings.clone();
+
+        match settings.set(setting, &input) {
+            Ok(()) => self.save_channel_settings(settings),
+            Err(e) => self.set_error_message(&format!("Invalid setting: {e}")),
+        }
+    }
+
+    pub fn reset_selected_channel_setting(&mut self) {
+        if let Some(setting) = self
+            .channel_settings
+            .get_selected()
+            .map(|entry| entry.setting)
+        {
+            let mut settings = self.settings.clone();
+            settings.reset(setting);
+            self.save_channel_settings(settings);
+        }
+    }
+
+    fn save_channel_settings(&mut self, settings: ChannelSettings) {
+        if let Err(e) =
+            database::set_channel_settings(&self.conn, &self.settings_channel_id, &settings)
+        {
+            self.set_error_message(&e.to_string());
+            return;
+        }
+
+        self.channel_settings.items = settings.entries();
+        let tabs_changed = settings.hidden_tabs != self.settings.hidden_tabs
+            || settings.default_tab != self.settings.default_tab;
+        self.settings = settings;
+
+        if tabs_changed {
+            self.load_videos(false);
+        }
+    }
+
+    pub fn toggle_stats(&mut self) {
+        if matches!(self.input_mode, InputMode::Stats) {
+            self.input_mode = InputMode::Normal;
+            self.stats = None;
+            return;
+        }
+
+        match utils::now().and_then(|now| Stats::collect(&self.conn, now)) {
+            Ok(stats) => {
+                self.stats_channels = StatefulList::with_items(stats.channels.clone());
+                self.stats = Some(stats);
+                self.input_mode = InputMode::Stats;
+            }
+            Err(e) => self.set_error_message(&e.to_string()),
+        }
+    }
+
+    pub fn undo(&mut self) {
+        match database::get_trash(&self.conn) {
+            Ok(entries) => match entries.first() {
+                Some(entry) => {
+                    self.restore_from_trash(entry.id);
+                }
+                None => self.set_warning_message("Nothing to undo"),
+            },
+            Err(e) => self.set_error_message(&e.to_string()),
+        }
+    }
+
+    pub fn restore_selected_trash_entry(&mut self) {
+        if let Some(idx) = self.trash.state.selected() {
+            let id = self.trash.items[idx].id;
+
+            if self.restore_from_trash(id) {
+                self.trash.items.remove(idx);
+                self.trash.check_bounds();
+            }
+        }
+    }
+
+    pub fn delete_selected_trash_entry(&mut self) {
+        if let Some(idx) = self.trash.state.selected() {
+            if let Err(e) = database::delete_from_trash(&self.conn, self.trash.items[idx].id) {
+                self.set_error_message(&e.to_string());
+                return;
+            }
+
+            self.trash.items.remove(idx);
+            self.trash.check_bounds();
+        }
+    }
+
+    fn restore_from_trash(&mut self, id: i64) -> bool {
+        let entry = match database::restore_from_trash(&mut self.conn, id) {
+            Ok(entry) => entry,
+            Err(e) => {
+                self.set_error_message(&format!("Couldn't restore: {e}"));
+                return false;
+            }
+        };
+
+        match entry.kind {
+            TrashKind::Channel => self.reload_channels(),
+            TrashKind::Video => self.load_videos(true),
+            TrashKind::Tag => {
+                if let Err(e) = self.reload_tags() {
+                    self.set_error_message(&e.to_string());
+                    return true;
+                }
+            }
+        }
+
+        self.set_message_with_default_duration(&format!("Restored \"{}\"", entry.name));
+
+        true
+    }
+
     pub fn switch_api(&mut self) {
         self.dispatch(IoEvent::SwitchApi);
     }
@@ -1386,19 +2404,14 @@ pub enum Mode {
     LatestVideos,
 }
 
-#[derive(Deserialize)]
-#[serde(rename_all(deserialize = "lowercase"))]
-pub enum VideoPlayer {
-    Mpv,
-    Vlc,
-}
-
 type TabList = Vec<(Vec<VideoListItem>, ChannelTab)>;
 
 pub struct Tab {
     pub variant: ChannelTab,
     pub videos: StatefulList<VideoListItem, TableState>,
     pub has_new_video: bool,
+    /// Number of videos hidden by filter rules.
+    pub filtered: usize,
 }
 
 impl Tab {
@@ -1407,6 +2420,7 @@ impl Tab {
             variant,
             videos: StatefulList::with_items(videos),
             has_new_video: false,
+            filtered: 0,
         }
     }
 }
@@ -1415,13 +2429,19 @@ impl Tab {
 pub struct Tabs(StatefulList<Tab, ListState>);
 
 impl Tabs {
-    pub fn new(tabs: TabList) -> Self {
-        Self(StatefulList::with_items(
+    pub fn new(tabs: TabList, default_tab: Option<ChannelTab>) -> Self {
+        let mut tabs = Self(StatefulList::with_items(
             tabs.into_iter()
                 .filter(|(videos, _)| !videos.is_empty())
                 .map(|(videos, variant)| Tab::new(variant, videos))
                 .collect(),
-        ))
+        ));
+
+        if let Some(tab) = default_tab {
+            tabs.select_tab(tab);
+        }
+
+        tabs
     }
 
     pub fn update_videos(&mut self, tabs: TabList) {
@@ -0,0 +1,98 @@
+use crate::mpv::PlaybackKind;
+use crate::video::{PlaybackSpec, VideoListItem};
+use std::time::{Duration, Instant};
+
+/// The video waiting to be played after the countdown.
+pub struct PendingAutoplay {
+    pub spec: PlaybackSpec,
+    pub kind: PlaybackKind,
+    deadline: Instant,
+}
+
+impl PendingAutoplay {
+    pub fn new(spec: PlaybackSpec, kind: PlaybackKind, countdown: u64) -> Self {
+        Self {
+            spec,
+            kind,
+            deadline: Instant::now() + Duration::from_secs(countdown),
+        }
+    }
+
+    pub fn is_due(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    /// Returns the seconds left, rounded up.
+    pub fn remaining_seconds(&self) -> u64 {
+        self.deadline
+            .saturating_duration_since(Instant::now())
+            .as_millis()
+            .div_ceil(1000) as u64
+    }
+}
+
+/// Returns the first unwatched video after the given one, or `None` if the
+/// video isn't in the list. Lists are ordered from newest to oldest, so the
+/// videos before it are searched instead when `chronological` is set.
+pub fn next_unwatched<'a>(
+    videos: &'a [VideoListItem],
+    video_id: &str,
+    chronological: bool,
+) -> Option<Option<&'a VideoListItem>> {
+    let index = videos.iter().position(|video| video.video_id == video_id)?;
+    let is_unwatched =
+        |video: &&VideoListItem| !video.watched && !video.dismissed && !video.filtered;
+
+    Some(if chronological {
+        videos[..index].iter().rev().find(is_unwatched)
+    } else {
+        videos[index + 1..].iter().find(is_unwatched)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::next_unwatched;
+    use crate::video::{Video, VideoListItem};
+
+    fn video(video_id: &str, watched: bool) -> VideoListItem {
+        VideoListItem {
+            video: Video {
+                video_id: video_id.to_owned(),
+                title: String::new(),
+                published: 0,
+                length: None,
+                members_only: false,
+            },
+            channel_id: String::new(),
+            channel_name: None,
+            published_text: String::new(),
+            watched,
+            position: None,
+            dismissed: false,
+            local_file: None,
+            filtered: false,
+            is_new: false,
+        }
+    }
+
+    #[test]
+    fn next_unwatched_video_is_found() {
+        let videos = [
+            video("newest", false),
+            video("newer", true),
+            video("current", true),
+            video("older", true),
+            video("oldest", false),
+        ];
+        let next = |video_id, chronological| {
+            next_unwatched(&videos, video_id, chronological)
+                .map(|video| video.map(|video| video.video_id.as_str()))
+        };
+
+        assert_eq!(next("current", false), Some(Some("oldest")));
+        assert_eq!(next("current", true), Some(Some("newest")));
+        assert_eq!(next("oldest", false), Some(None));
+        assert_eq!(next("missing", false), None);
+    }
+}
@@ -12,6 +12,20 @@ pub enum ChannelTab {
     Streams,
 }
 
+impl ChannelTab {
+    /// Inverse of `tab as u8`, which is how tabs are stored in the database.
+    pub fn from_stored(value: u8) -> anyhow::Result<Self> {
+        let tab = match value {
+            0 => Self::Videos,
+            1 => Self::Shorts,
+            2 => Self::Streams,
+            _ => anyhow::bail!("{value} is not a stored tab"),
+        };
+
+        Ok(tab)
+    }
+}
+
 impl From<u8> for ChannelTab {
     fn from(value: u8) -> Self {
         match value {
@@ -23,6 +37,27 @@ impl From<u8> for ChannelTab {
     }
 }
 
+impl From<ChannelTab> for EnabledTabs {
+    fn from(tab: ChannelTab) -> Self {
+        Self::from_bits_truncate(1 << tab as u8)
+    }
+}
+
+impl TryFrom<&str> for ChannelTab {
+    type Error = anyhow::Error;
+
+    fn try_from(tab: &str) -> Result<Self, Self::Error> {
+        let tab = match tab {
+            "videos" => Self::Videos,
+            "shorts" => Self::Shorts,
+            "streams" | "live" => Self::Streams,
+            _ => anyhow::bail!("\"{tab}\" is not a tab. Use videos, shorts or streams"),
+        };
+
+        Ok(tab)
+    }
+}
+
 impl Display for ChannelTab {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
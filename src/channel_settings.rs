@@ -0,0 +1,262 @@
+use crate::{
+    api::VideoFormat,
+    channel::ChannelTab,
+    config,
+    config::EnabledTabs,
+    mpv::{MAX_SPEED, MIN_SPEED},
+};
+use anyhow::{Context, Result, bail, ensure};
+use std::fmt::Display;
+
+/// Settings of a channel that take precedence over the configuration. Unset
+/// settings fall back to the configuration.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChannelSettings {
+    pub default_tab: Option<ChannelTab>,
+    pub speed: Option<f64>,
+    pub audio_only: bool,
+    pub video_quality: Option<u16>,
+    pub video_codec: Option<VideoFormat>,
+    pub hidden_tabs: EnabledTabs,
+    /// Language of the subtitles selected when playing in mpv, or `"off"` to
+    /// play without subtitles.
+    pub subtitle_language: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    DefaultTab,
+    Speed,
+    AudioOnly,
+    VideoQuality,
+    VideoCodec,
+    HiddenTabs,
+    SubtitleLanguage,
+}
+
+impl Setting {
+    pub const ALL: [Self; 7] = [
+        Self::DefaultTab,
+        Self::Speed,
+        Self::AudioOnly,
+        Self::VideoQuality,
+        Self::VideoCodec,
+        Self::HiddenTabs,
+        Self::SubtitleLanguage,
+    ];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::DefaultTab => "Default tab",
+            Self::Speed => "Speed",
+            Self::AudioOnly => "Audio only",
+            Self::VideoQuality => "Video quality",
+            Self::VideoCodec => "Video codec",
+            Self::HiddenTabs => "Hidden tabs",
+            Self::SubtitleLanguage => "Subtitles",
+        }
+    }
+}
+
+impl ChannelSettings {
+    pub fn is_hidden(&self, tab: ChannelTab) -> bool {
+        self.hidden_tabs.contains(tab.into())
+    }
+
+    /// Returns the value of the setting as it is entered in the editor, or
+    /// `None` if it falls back to the configuration.
+    pub fn value(&self, setting: Setting) -> Option<String> {
+        match setting {
+            Setting::DefaultTab => self.default_tab.map(|tab| tab.to_string()),
+            Setting::Speed => self.speed.map(|speed| speed.to_string()),
+            Setting::AudioOnly => Some(if self.audio_only { "yes" } else { "no" }.to_owned()),
+            Setting::VideoQuality => self.video_quality.map(|quality| match quality {
+                u16::MAX => "best".to_owned(),
+                quality => format!("{quality}p"),
+            }),
+            Setting::VideoCodec => self.video_codec.map(|codec| codec.to_string()),
+            Setting::HiddenTabs => (!self.hidden_tabs.is_empty()).then(|| {
+                self.hidden_tabs
+                    .iter()
+                    .map(|tab| ChannelTab::from(tab.bits()).to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }),
+            Setting::SubtitleLanguage => self.subtitle_language.clone(),
+        }
+    }
+
+    /// Sets the setting from the text entered in the editor. Empty text unsets
+    /// it.
+    pub fn set(&mut self, setting: Setting, text: &str) -> Result<()> {
+        let text = text.trim();
+
+        if text.is_empty() {
+            self.reset(setting);
+            return Ok(());
+        }
+
+        match setting {
+            Setting::DefaultTab => self.default_tab = Some(ChannelTab::try_from(text)?),
+            Setting::Speed => {
+                let speed = text
+                    .strip_suffix('x')
+                    .unwrap_or(text)
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|speed| (MIN_SPEED..=MAX_SPEED).contains(speed))
+                    .with_context(|| {
+                        format!("\"{text}\" is not a speed between {MIN_SPEED} and {MAX_SPEED}")
+                    })?;
+
+                self.speed = Some(speed);
+            }
+            Setting::AudioOnly => {
+                self.audio_only = match text {
+                    "yes" | "true" => true,
+                    "no" | "false" => false,
+                    _ => bail!("\"{text}\" is not yes or no"),
+                }
+            }
+            Setting::VideoQuality => {
+                self.video_quality = Some(
+                    config::parse_video_quality(text)
+                        .with_context(|| format!("\"{text}\" is not a valid quality"))?,
+                );
+            }
+            Setting::VideoCodec => {
+                self.video_codec = Some(match text {
+                    "mp4" => VideoFormat::Mp4,
+                    "webm" => VideoFormat::WebM,
+                    _ => bail!("\"{text}\" is not a codec. Use mp4 or webm"),
+                });
+            }
+            Setting::HiddenTabs => {
+                let mut hidden_tabs = EnabledTabs::empty();
+
+                for tab in text.split([' ', ',']).filter(|tab| !tab.is_empty()) {
+                    hidden_tabs.insert(ChannelTab::try_from(tab)?.into());
+                }
+
+                ensure!(
+                    hidden_tabs != EnabledTabs::all(),
+                    "at least one tab has to be shown"
+                );
+
+                self.hidden_tabs = hidden_tabs;
+            }
+            Setting::SubtitleLanguage => {
+                ensure!(
+                    !text.contains(char::is_whitespace),
+                    "\"{text}\" is not a language code"
+                );
+
+                self.subtitle_language = Some(match text {
+                    "no" | "none" => "off".to_owned(),
+                    language => language.to_owned(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn reset(&mut self, setting: Setting) {
+        match setting {
+            Setting::DefaultTab => self.default_tab = None,
+            Setting::Speed => self.speed = None,
+            Setting::AudioOnly => self.audio_only = false,
+            Setting::VideoQuality => self.video_quality = None,
+            Setting::VideoCodec => self.video_codec = None,
+            Setting::HiddenTabs => self.hidden_tabs = EnabledTabs::empty(),
+            Setting::SubtitleLanguage => self.subtitle_language = None,
+        }
+    }
+
+    pub fn entries(&self) -> Vec<SettingEntry> {
+        Setting::ALL
+            .into_iter()
+            .map(|setting| SettingEntry {
+                setting,
+                value: self.value(setting),
+            })
+            .collect()
+    }
+}
+
+/// A row of the channel settings editor.
+pub struct SettingEntry {
+    pub setting: Setting,
+    value: Option<String>,
+}
+
+impl Display for SettingEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:13} {}",
+            self.setting.name(),
+            self.value.as_deref().unwrap_or("default")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChannelSettings, Setting};
+    use crate::{api::VideoFormat, channel::ChannelTab};
+    use anyhow::Result;
+
+    #[test]
+    fn settings_are_parsed_and_displayed() -> Result<()> {
+        let mut settings = ChannelSettings::default();
+
+        settings.set(Setting::DefaultTab, "streams")?;
+        settings.set(Setting::Speed, "1.5x")?;
+        settings.set(Setting::VideoQuality, "720p")?;
+        settings.set(Setting::VideoCodec, "webm")?;
+        settings.set(Setting::HiddenTabs, "shorts, live")?;
+        settings.set(Setting::SubtitleLanguage, "none")?;
+
+        assert_eq!(settings.default_tab, Some(ChannelTab::Streams));
+        assert_eq!(settings.speed, Some(1.5));
+        assert_eq!(settings.video_quality, Some(720));
+        assert_eq!(settings.video_codec, Some(VideoFormat::WebM));
+        assert!(settings.is_hidden(ChannelTab::Shorts));
+        assert!(!settings.is_hidden(ChannelTab::Videos));
+        assert_eq!(
+            settings
+                .entries()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "Default tab   streams",
+                "Speed         1.5",
+                "Audio only    no",
+                "Video quality 720p",
+                "Video codec   webm",
+                "Hidden tabs   shorts streams",
+                "Subtitles     off",
+            ]
+        );
+
+        assert!(settings.set(Setting::Speed, "fast").is_err());
+        assert!(settings.set(Setting::Speed, "0").is_err());
+        assert!(settings.set(Setting::Speed, "8").is_err());
+        assert!(settings.set(Setting::VideoCodec, "av1").is_err());
+        assert!(settings.set(Setting::SubtitleLanguage, "en us").is_err());
+        assert!(
+            settings
+                .set(Setting::HiddenTabs, "videos shorts streams")
+                .is_err()
+        );
+
+        for setting in Setting::ALL {
+            settings.set(setting, "")?;
+        }
+        assert_eq!(settings, ChannelSettings::default());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
-use clap::{Arg, ArgAction, ArgMatches, Command, builder::ValueParser};
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command, builder::ValueParser};
 
 pub fn get_matches() -> ArgMatches {
+    create_command().get_matches()
+}
+
+fn create_command() -> Command {
     Command::new(env!("CARGO_PKG_NAME"))
         .version(env!("CARGO_PKG_VERSION"))
         .about(env!("CARGO_PKG_DESCRIPTION"))
@@ -43,6 +47,13 @@ pub fn get_matches() -> ArgMatches {
                 .help("Generate Invidious instances file")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("offline")
+                .short('o')
+                .long("offline")
+                .help("Start without making network requests")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("tick_rate")
                 .hide(true)
@@ -71,7 +82,7 @@ pub fn get_matches() -> ArgMatches {
         .subcommand(create_import_subcommand())
         .subcommand(create_export_subcommand())
         .subcommand(create_database_subcommand())
-        .get_matches()
+        .subcommand(create_stats_subcommand())
 }
 
 fn create_import_subcommand() -> Command {
@@ -84,7 +95,20 @@ fn create_import_subcommand() -> Command {
                 .help("Format of the import file")
                 .value_name("FORMAT")
                 .default_value("youtube_csv")
-                .value_parser(["youtube_csv", "newpipe"]),
+                .value_parser([
+                    "youtube_csv",
+                    "newpipe",
+                    "opml",
+                    "freetube",
+                    "libretube",
+                    "invidious",
+                ]),
+        )
+        .arg(
+            Arg::new("watch_history")
+                .long("watch-history")
+                .help("Mark videos in the watch history as watched (invidious format only)")
+                .action(ArgAction::SetTrue),
         )
         .arg(
             Arg::new("source")
@@ -105,7 +129,14 @@ fn create_export_subcommand() -> Command {
                 .help("Format of the export file")
                 .value_name("FORMAT")
                 .default_value("youtube_csv")
-                .value_parser(["youtube_csv", "newpipe"]),
+                .value_parser([
+                    "youtube_csv",
+                    "newpipe",
+                    "opml",
+                    "freetube",
+                    "libretube",
+                    "invidious",
+                ]),
         )
         .arg(
             Arg::new("target")
@@ -116,6 +147,26 @@ fn create_export_subcommand() -> Command {
         )
 }
 
+fn create_stats_subcommand() -> Command {
+    Command::new("stats")
+        .about("Show watch statistics")
+        .arg(
+            Arg::new("inactive_months")
+                .long("inactive-months")
+                .help("List channels without a watched video in the last MONTHS months")
+                .value_name("MONTHS")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("top")
+                .long("top")
+                .help("Number of most-watched channels to list")
+                .value_name("N")
+                .default_value("10")
+                .value_parser(clap::value_parser!(usize)),
+        )
+}
+
 fn create_database_subcommand() -> Command {
     Command::new("database")
         .about("Manage the database")
@@ -134,4 +185,154 @@ fn create_database_subcommand() -> Command {
                         .value_parser(clap::value_parser!(u8)),
                 ),
         )
+        .subcommand(
+            Command::new("import-history")
+                .about("Mark videos from a Google Takeout or NewPipe watch history as watched")
+                .arg(
+                    Arg::new("source")
+                        .help("Path to watch-history.json, watch-history.html or a NewPipe backup zip")
+                        .value_parser(ValueParser::path_buf())
+                        .value_name("FILE")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("export-state")
+                .about("Export watched state, playback positions and tags to a JSON file")
+                .arg(
+                    Arg::new("target")
+                        .help("Path to the export file")
+                        .value_parser(ValueParser::path_buf())
+                        .value_name("FILE")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("import-state")
+                .about("Import watched state, playback positions and tags from a JSON file")
+                .arg(
+                    Arg::new("strategy")
+                        .short('s')
+                        .long("strategy")
+                        .help("How to resolve conflicts with the local state")
+                        .value_name("STRATEGY")
+                        .default_value("watched-wins")
+                        .value_parser(["watched-wins", "newest", "overwrite"]),
+                )
+                .arg(
+                    Arg::new("source")
+                        .help("Path to the import file")
+                        .value_parser(ValueParser::path_buf())
+                        .value_name("FILE")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("merge")
+                .about("Merge subscriptions, videos, tags and watch state from another database")
+                .after_help(
+                    "Deletions are not merged: channels and tags deleted on only one side are \
+                     added again. Deleted videos stay hidden because they are dismissed.",
+                )
+                .arg(
+                    Arg::new("source")
+                        .help("Path to the other database")
+                        .value_parser(ValueParser::path_buf())
+                        .value_name("FILE")
+                        .required(true),
+                ),
+        )
+        .subcommand(Command::new("backups").about("List the database backups"))
+        .subcommand(
+            Command::new("restore")
+                .about("Replace the database with a backup")
+                .arg(
+                    Arg::new("backup")
+                        .help("Path or file name of the backup")
+                        .value_parser(ValueParser::path_buf())
+                        .value_name("BACKUP")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("prune")
+                .about("Delete old videos from the database")
+                .arg(
+                    Arg::new("older_than")
+                        .long("older-than")
+                        .help("Delete videos published more than DAYS days ago")
+                        .value_name("DAYS")
+                        .value_parser(clap::value_parser!(u64)),
+                )
+                .arg(
+                    Arg::new("keep_per_channel")
+                        .long("keep-per-channel")
+                        .help("Keep the N most recent videos of every channel")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("keep_watched")
+                        .long("keep-watched")
+                        .help("Keep watched videos")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("keep_in_progress")
+                        .long("keep-in-progress")
+                        .help("Keep partially watched videos")
+                        .action(ArgAction::SetTrue),
+                )
+                .group(
+                    ArgGroup::new("criteria")
+                        .args(["older_than", "keep_per_channel"])
+                        .multiple(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(Command::new("vacuum").about("Rebuild the database to reclaim unused space"))
+        .subcommand(
+            Command::new("check").about("Check the integrity and consistency of the database"),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::create_command;
+    use std::path::PathBuf;
+
+    #[test]
+    fn import_invidious_watch_history() {
+        let matches = create_command()
+            .try_get_matches_from([
+                "ytsub",
+                "import",
+                "--format",
+                "invidious",
+                "--watch-history",
+                "export.json",
+            ])
+            .unwrap();
+
+        let (name, matches) = matches.subcommand().unwrap();
+
+        assert_eq!(name, "import");
+        assert_eq!(matches.get_one::<String>("format").unwrap(), "invidious");
+        assert!(matches.get_flag("watch_history"));
+        assert_eq!(
+            matches.get_one::<PathBuf>("source").unwrap(),
+            &PathBuf::from("export.json")
+        );
+    }
+
+    #[test]
+    fn export_invidious() {
+        let matches = create_command()
+            .try_get_matches_from(["ytsub", "export", "-f", "invidious", "export.json"])
+            .unwrap();
+
+        let (_, matches) = matches.subcommand().unwrap();
+
+        assert_eq!(matches.get_one::<String>("format").unwrap(), "invidious");
+    }
 }
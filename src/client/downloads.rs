@@ -0,0 +1,254 @@
+use super::{
+    ClientRequest, TX,
+    streams::{StreamSelection, download_streams},
+};
+use crate::{
+    CONFIG,
+    download::{DownloadProgress, DownloadState, FILE_PREFIX, progress_template},
+    player::youtube_watch_url,
+    utils,
+    video::VideoMetadata,
+};
+use anyhow::{Context, Result, bail};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    process::Stdio,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+    sync::mpsc::UnboundedReceiver,
+    task::JoinHandle,
+    time::Instant,
+};
+use tokio_util::sync::CancellationToken;
+
+pub(super) const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+pub enum DownloadCommand {
+    Queue(VideoMetadata),
+    /// Queues the streams selected in the format selection window.
+    QueueStreams(VideoMetadata, Box<StreamSelection>),
+    Cancel(String),
+}
+
+struct RunningDownload {
+    video_id: String,
+    token: CancellationToken,
+    task: JoinHandle<Result<PathBuf>>,
+}
+
+pub(super) fn set_state(video_id: String, state: DownloadState) -> Result<()> {
+    TX.send(ClientRequest::SetDownloadState(video_id, state))?;
+    Ok(())
+}
+
+pub(super) fn download_dir() -> Result<PathBuf> {
+    if CONFIG.download_dir.as_os_str().is_empty() {
+        utils::get_default_download_dir()
+    } else {
+        Ok(CONFIG.download_dir.clone())
+    }
+}
+
+/// Downloads the queued videos one at a time. The formats selected for a
+/// video are kept until it is downloaded so that a retry downloads them again
+/// instead of falling back to yt-dlp.
+pub async fn download_worker(mut rx: UnboundedReceiver<DownloadCommand>) -> Result<()> {
+    let mut queue = VecDeque::<VideoMetadata>::new();
+    let mut selections = HashMap::<String, Box<StreamSelection>>::new();
+    let mut running = None::<RunningDownload>;
+
+    loop {
+        if running.is_none()
+            && let Some(metadata) = queue.pop_front()
+        {
+            let token = CancellationToken::new();
+            let video_id = metadata.video_id.clone();
+            let task = match selections.get(&video_id) {
+                Some(streams) => tokio::spawn(download_streams(
+                    metadata,
+                    streams.as_ref().clone(),
+                    token.clone(),
+                )),
+                None => tokio::spawn(download(metadata, token.clone())),
+            };
+
+            running = Some(RunningDownload {
+                video_id,
+                token,
+                task,
+            });
+        }
+
+        tokio::select! {
+            command = rx.recv() => match command {
+                Some(DownloadCommand::QueueStreams(metadata, streams)) => {
+                    let is_running = running
+                        .as_ref()
+                        .is_some_and(|download| download.video_id == metadata.video_id);
+
+                    if !is_running {
+                        selections.insert(metadata.video_id.clone(), streams);
+                    }
+
+                    if !is_running && !queue.iter().any(|queued| queued.video_id == metadata.video_id) {
+                        queue.push_back(metadata);
+                    }
+                }
+                Some(DownloadCommand::Queue(metadata)) => {
+                    let is_known = running
+                        .as_ref()
+                        .is_some_and(|download| download.video_id == metadata.video_id)
+                        || queue.iter().any(|queued| queued.video_id == metadata.video_id);
+
+                    if !is_known {
+                        queue.push_back(metadata);
+                    }
+                }
+                Some(DownloadCommand::Cancel(video_id)) => {
+                    if let Some(download) = running
+                        .as_ref()
+                        .filter(|download| download.video_id == video_id)
+                    {
+                        download.token.cancel();
+                    } else if let Some(index) =
+                        queue.iter().position(|queued| queued.video_id == video_id)
+                    {
+                        queue.remove(index);
+                        set_state(video_id, DownloadState::Cancelled)?;
+                    }
+                }
+                None => return Ok(()),
+            },
+            result = async { (&mut running.as_mut().unwrap().task).await }, if running.is_some() => {
+                let download = running.take().unwrap();
+
+                let state = match result {
+                    _ if download.token.is_cancelled() => DownloadState::Cancelled,
+                    Ok(Ok(path)) => {
+                        selections.remove(&download.video_id);
+                        DownloadState::Finished(path)
+                    }
+                    Ok(Err(error)) => DownloadState::Failed(error.to_string()),
+                    Err(error) => DownloadState::Failed(error.to_string()),
+                };
+
+                set_state(download.video_id, state)?;
+            }
+        }
+    }
+}
+
+/// The output of yt-dlp collected while downloading.
+struct Output {
+    video_id: String,
+    path: Option<PathBuf>,
+    error: Option<String>,
+    last_progress: Option<Instant>,
+}
+
+impl Output {
+    fn handle_line(&mut self, line: &str) -> Result<()> {
+        if let Some(progress) = DownloadProgress::parse(line) {
+            if self
+                .last_progress
+                .is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL)
+            {
+                self.last_progress = Some(Instant::now());
+                set_state(self.video_id.clone(), DownloadState::Downloading(progress))?;
+            }
+        } else if let Some(path) = line.strip_prefix(FILE_PREFIX) {
+            self.path = Some(PathBuf::from(path));
+        } else if let Some(error) = line.strip_prefix("ERROR: ") {
+            self.error = Some(error.to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+fn download_command(metadata: &VideoMetadata) -> Result<Command> {
+    let directory = download_dir()?;
+
+    let mut command = Command::new(&CONFIG.ytdlp_path);
+    command
+        .args(["--newline", "--progress", "--no-simulate", "--no-playlist"])
+        .arg("--progress-template")
+        .arg(progress_template())
+        .arg("--print")
+        .arg(format!("after_move:{FILE_PREFIX}%(filepath)s"))
+        .arg("--paths")
+        .arg(directory)
+        .arg("--output")
+        .arg(&CONFIG.download_template);
+
+    if let Some(format) = &CONFIG.download_format {
+        command.arg("--format").arg(format);
+    }
+
+    if let Some(proxy) = &CONFIG.mpv_proxy {
+        command.arg("--proxy").arg(proxy);
+    }
+
+    if let Some(cookies) = &CONFIG.cookies {
+        command.arg("--cookies").arg(cookies);
+    }
+
+    command
+        .arg(youtube_watch_url(&metadata.video_id))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    Ok(command)
+}
+
+/// Downloads the video with yt-dlp and returns the path of the file.
+async fn download(metadata: VideoMetadata, token: CancellationToken) -> Result<PathBuf> {
+    let mut child = download_command(&metadata)?
+        .spawn()
+        .with_context(|| format!("Failed to run {}", CONFIG.ytdlp_path.display()))?;
+
+    let mut stdout = BufReader::new(child.stdout.take().context("stdout is not piped")?).lines();
+    let mut stderr = BufReader::new(child.stderr.take().context("stderr is not piped")?).lines();
+    let (mut stdout_open, mut stderr_open) = (true, true);
+
+    let mut output = Output {
+        video_id: metadata.video_id,
+        path: None,
+        error: None,
+        last_progress: None,
+    };
+
+    while stdout_open || stderr_open {
+        tokio::select! {
+            () = token.cancelled() => {
+                child.kill().await?;
+                bail!("Download was cancelled");
+            }
+            line = stdout.next_line(), if stdout_open => match line? {
+                Some(line) => output.handle_line(&line)?,
+                None => stdout_open = false,
+            },
+            line = stderr.next_line(), if stderr_open => match line? {
+                Some(line) => output.handle_line(&line)?,
+                None => stderr_open = false,
+            },
+        }
+    }
+
+    let status = child.wait().await?;
+
+    match output.path {
+        Some(path) if status.success() => Ok(path),
+        _ => bail!(
+            output
+                .error
+                .unwrap_or_else(|| format!("yt-dlp exited with {status}"))
+        ),
+    }
+}
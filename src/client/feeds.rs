@@ -3,7 +3,7 @@ use crate::{
     CONFIG,
     api::{Api, local::Local},
     channel::{ChannelTab, RefreshState},
-    emit_msg,
+    emit_msg, http,
 };
 use anyhow::Result;
 use futures_util::StreamExt;
@@ -146,6 +146,12 @@ pub async fn refresh_channels(instance: Box<dyn Api>, channel_ids: Vec<String>)
         }
     }
 
+    if count == 0 && !http::is_online().await {
+        super::set_offline(true)?;
+        emit_msg!(warning, "Connection lost. Switched to offline mode");
+        return Ok(());
+    }
+
     let elapsed = start.elapsed().as_secs_f64();
 
     match (count, total) {
@@ -9,7 +9,7 @@ use crate::{
     utils,
     video::PlaybackSpec,
 };
-use anyhow::Result;
+use anyhow::{Result, bail};
 use std::{
     fs::File,
     io::{Read, Write},
@@ -32,6 +32,8 @@ pub async fn get_thumbnail(
     {
         sleep(Duration::from_millis(10)).await;
         file.read_to_end(&mut bytes)?;
+    } else if super::is_offline() {
+        bail!("Thumbnail is not cached");
     } else {
         sleep(Duration::from_millis(69)).await;
         bytes = instance.get_thumbnail(video_id).await?;
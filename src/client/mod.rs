@@ -1,38 +1,49 @@
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    mem,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 
 use crate::{
     CONFIG,
     api::{Api, ApiBackend, ChannelFeed, invidious::Instance, local::Local},
     channel::{ChannelTab, RefreshState},
+    download::DownloadState,
+    http,
     message::MessageType,
-    mpv::{PlayerHandle, VideoRequest, VideoSource},
+    mpv::{PlaybackUpdate, PlayerHandle, TrackKind},
     player::{
-        copy_link, open_in_invidious, open_in_youtube, play_from_formats, play_mpv_without_ipc,
-        youtube_watch_url,
+        autoplay_video, copy_link, open_in_invidious, open_in_youtube, play_from_formats,
+        play_using_ytdlp, youtube_watch_url,
     },
     ro_cell::RoCell,
     stream_formats::Formats,
     thumbnail::{Thumbnail, protocols::GraphicsProtocol},
     utils,
-    video::PlaybackSpec,
+    video::{PlaybackSpec, VideoMetadata},
 };
 use anyhow::{Result, bail};
+use downloads::{DownloadCommand, download_worker};
 use feeds::{
     get_more_videos, get_video_title, import_channels, refresh_channels, subscribe_to_channel,
 };
 use media::{fetch_formats, get_thumbnail};
+use streams::queue_streams;
 use tokio::{
     sync::{
-        mpsc::{UnboundedReceiver, UnboundedSender},
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
         oneshot::Sender,
         watch,
     },
-    time::sleep,
+    time::{Instant, interval_at, sleep},
 };
 use tokio_util::sync::CancellationToken;
 
+mod downloads;
 mod feeds;
 mod media;
+mod streams;
 
 pub enum FormatAction {
     Select,
@@ -67,12 +78,22 @@ pub enum IoEvent {
     PlayFromFormats(Box<Formats>),
     PlayUsingYtdlp(PlaybackSpec),
     PlayAudioUsingYtdlp(PlaybackSpec),
+    AutoplayVideo(PlaybackSpec),
     TogglePlayback,
     SeekPlayback(i32),
     AdjustVolume(i8),
     ToggleMute,
+    AdjustSpeed(f64),
+    ResetSpeed,
+    AdjustChapter(i64),
+    SetChapter(usize),
+    SetTrack(TrackKind, String),
+    CycleSleepTimer,
     StopPlayback,
     ReleaseVideo,
+    Download(VideoMetadata),
+    DownloadFormats(Box<Formats>),
+    CancelDownload(String),
     CopyLink(String, ApiBackend),
     OpenInBrowser(String, ApiBackend),
     ClearMessage(CancellationToken, u64),
@@ -90,8 +111,12 @@ pub enum ClientRequest {
     SetThumbnail(String, Option<Thumbnail>),
     EnterFormatSelection(Box<Formats>),
     SetWatched(String, bool),
+    SetDownloadState(String, DownloadState),
+    /// Playback state of a video played by an external player.
+    ExternalPlaybackUpdate(PlaybackUpdate),
     SetMessage(String, MessageType, Option<u64>),
     ClearMessage,
+    SetOffline(bool),
 }
 
 #[macro_export]
@@ -125,6 +150,21 @@ macro_rules! emit_msg {
 }
 
 pub static TX: RoCell<UnboundedSender<ClientRequest>> = RoCell::new();
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+const CONNECTIVITY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+pub fn set_offline(offline: bool) -> Result<()> {
+    if OFFLINE.swap(offline, Ordering::Relaxed) != offline {
+        TX.send(ClientRequest::SetOffline(offline))?;
+    }
+
+    Ok(())
+}
 
 pub struct Client {
     rx: UnboundedReceiver<IoEvent>,
@@ -133,6 +173,8 @@ pub struct Client {
     pub invidious_instance: Option<Instance>,
     local_api: Local,
     pub selected_api: ApiBackend,
+    pending_subscriptions: Vec<String>,
+    pending_refreshes: Vec<String>,
 }
 
 impl Client {
@@ -144,10 +186,20 @@ impl Client {
             invidious_instance: None,
             local_api: Local::new()?,
             selected_api: CONFIG.api,
+            pending_subscriptions: Vec::new(),
+            pending_refreshes: Vec::new(),
         };
 
-        if matches!(client.selected_api, ApiBackend::Invidious) {
-            client.set_instance().await?;
+        if CONFIG.offline {
+            set_offline(true)?;
+        } else if matches!(client.selected_api, ApiBackend::Invidious)
+            && let Err(e) = client.set_instance().await
+        {
+            if http::is_online().await {
+                return Err(e);
+            }
+
+            set_offline(true)?;
         }
 
         Ok(client)
@@ -158,7 +210,44 @@ impl Client {
 
         tokio::spawn(thumbnail_worker(thumbnail_rx));
 
-        while let Some(event) = self.rx.recv().await {
+        let (download_tx, download_rx) = mpsc::unbounded_channel();
+        tokio::spawn(download_worker(download_rx));
+
+        // Requests aren't held back by a connectivity check at startup. A
+        // failing refresh switches to offline mode instead.
+        if is_offline() && !CONFIG.offline {
+            emit_msg!(warning, "No connection. Running in offline mode");
+        }
+
+        let mut connectivity_check = interval_at(
+            Instant::now() + CONNECTIVITY_CHECK_INTERVAL,
+            CONNECTIVITY_CHECK_INTERVAL,
+        );
+
+        loop {
+            let event = tokio::select! {
+                event = self.rx.recv() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                _ = connectivity_check.tick(), if is_offline() && !CONFIG.offline => {
+                    if http::is_online().await {
+                        self.go_online().await?;
+                    }
+
+                    continue;
+                }
+            };
+
+            let event = if is_offline() {
+                match self.defer_while_offline(event)? {
+                    Some(event) => event,
+                    None => continue,
+                }
+            } else {
+                event
+            };
+
             match event {
                 IoEvent::SubscribeToChannel(id) => {
                     let instance = self.instance();
@@ -206,31 +295,21 @@ impl Client {
                     );
                 }
                 IoEvent::PlayUsingYtdlp(spec) => {
-                    let source = youtube_watch_url(&spec.metadata.video_id);
-                    let request = VideoRequest {
-                        spec,
-                        source: VideoSource::YtDlp(source),
-                    };
-
-                    if CONFIG.mpv_video_ipc {
-                        if let Err(error) = self.player.play_video(request) {
-                            emit_msg!(error, error.to_string());
-                        }
-                    } else {
-                        tokio::spawn(async move {
-                            emit_msg!("Launching video player");
+                    let player = self.player.clone();
 
-                            play_mpv_without_ipc(request).await
-                        });
-                    }
+                    tokio::spawn(async move { play_using_ytdlp(player, spec).await });
                 }
                 IoEvent::PlayAudioUsingYtdlp(spec) => {
-                    let source = youtube_watch_url(&spec.metadata.video_id);
+                    let source = match &spec.local_file {
+                        Some(path) => path.to_string_lossy().into_owned(),
+                        None => youtube_watch_url(&spec.metadata.video_id),
+                    };
 
                     if let Err(error) = self.player.play_audio(spec, source) {
                         emit_msg!(error, error.to_string());
                     }
                 }
+                IoEvent::AutoplayVideo(spec) => autoplay_video(&self.player, spec)?,
                 IoEvent::TogglePlayback => {
                     if let Err(error) = self.player.toggle() {
                         emit_msg!(error, error.to_string());
@@ -251,6 +330,36 @@ impl Client {
                         emit_msg!(error, error.to_string());
                     }
                 }
+                IoEvent::AdjustSpeed(value) => {
+                    if let Err(error) = self.player.adjust_speed(value) {
+                        emit_msg!(error, error.to_string());
+                    }
+                }
+                IoEvent::ResetSpeed => {
+                    if let Err(error) = self.player.reset_speed() {
+                        emit_msg!(error, error.to_string());
+                    }
+                }
+                IoEvent::AdjustChapter(value) => {
+                    if let Err(error) = self.player.adjust_chapter(value) {
+                        emit_msg!(error, error.to_string());
+                    }
+                }
+                IoEvent::SetChapter(index) => {
+                    if let Err(error) = self.player.set_chapter(index) {
+                        emit_msg!(error, error.to_string());
+                    }
+                }
+                IoEvent::SetTrack(kind, id) => {
+                    if let Err(error) = self.player.set_track(kind, id) {
+                        emit_msg!(error, error.to_string());
+                    }
+                }
+                IoEvent::CycleSleepTimer => {
+                    if let Err(error) = self.player.cycle_sleep_timer() {
+                        emit_msg!(error, error.to_string());
+                    }
+                }
                 IoEvent::StopPlayback => {
                     if let Err(error) = self.player.stop() {
                         emit_msg!(error, error.to_string());
@@ -261,6 +370,15 @@ impl Client {
                         emit_msg!(error, error.to_string());
                     }
                 }
+                IoEvent::Download(metadata) => {
+                    download_tx.send(DownloadCommand::Queue(metadata))?;
+                }
+                IoEvent::DownloadFormats(formats) => {
+                    queue_streams(self.instance(), &formats, &download_tx)?;
+                }
+                IoEvent::CancelDownload(video_id) => {
+                    download_tx.send(DownloadCommand::Cancel(video_id))?;
+                }
                 IoEvent::CopyLink(url_component, api) => {
                     copy_link(self, &url_component, api).await?;
                 }
@@ -279,12 +397,90 @@ impl Client {
     }
 
     fn instance(&self) -> Box<dyn Api> {
-        match self.selected_api {
-            ApiBackend::Invidious => Box::new(self.invidious_instance.as_ref().unwrap().clone()),
-            ApiBackend::Local => Box::new(self.local_api.clone()),
+        match (self.selected_api, &self.invidious_instance) {
+            (ApiBackend::Invidious, Some(instance)) => Box::new(instance.clone()),
+            _ => Box::new(self.local_api.clone()),
         }
     }
 
+    /// Queues refresh and subscription requests until the connection is
+    /// restored and rejects the rest of the events that need the network.
+    /// Returns the event back if it can be handled offline.
+    fn defer_while_offline(&mut self, event: IoEvent) -> Result<Option<IoEvent>> {
+        const UNAVAILABLE: &str = "Not available in offline mode";
+
+        match event {
+            IoEvent::SubscribeToChannel(input) => {
+                self.pending_subscriptions.push(input);
+                emit_msg!(warning, "Offline: subscription queued until reconnected");
+            }
+            IoEvent::RefreshChannels(ids) => {
+                for id in ids {
+                    if !self.pending_refreshes.contains(&id) {
+                        self.pending_refreshes.push(id);
+                    }
+                }
+                emit_msg!(warning, "Offline: refresh queued until reconnected");
+            }
+            IoEvent::ImportChannels(_) => {
+                emit_msg!(error, UNAVAILABLE);
+                TX.send(ClientRequest::FinalizeImport(false))?;
+            }
+            IoEvent::GetVideoTitle(_) => (),
+            // Downloaded videos can be played offline.
+            IoEvent::PlayUsingYtdlp(spec) if spec.local_file.is_some() => {
+                return Ok(Some(IoEvent::PlayUsingYtdlp(spec)));
+            }
+            IoEvent::PlayAudioUsingYtdlp(spec) if spec.local_file.is_some() => {
+                return Ok(Some(IoEvent::PlayAudioUsingYtdlp(spec)));
+            }
+            IoEvent::AutoplayVideo(spec) if spec.local_file.is_some() => {
+                return Ok(Some(IoEvent::AutoplayVideo(spec)));
+            }
+            IoEvent::LoadMoreVideos(..)
+            | IoEvent::FetchFormats(..)
+            | IoEvent::PlayFromFormats(_)
+            | IoEvent::PlayUsingYtdlp(_)
+            | IoEvent::PlayAudioUsingYtdlp(_)
+            | IoEvent::AutoplayVideo(_)
+            | IoEvent::Download(_)
+            | IoEvent::DownloadFormats(_)
+            | IoEvent::SwitchApi
+            | IoEvent::CopyLink(_, ApiBackend::Invidious)
+            | IoEvent::OpenInBrowser(_, ApiBackend::Invidious) => emit_msg!(error, UNAVAILABLE),
+            event => return Ok(Some(event)),
+        }
+
+        Ok(None)
+    }
+
+    async fn go_online(&mut self) -> Result<()> {
+        set_offline(false)?;
+        emit_msg!("Connection restored");
+
+        if matches!(self.selected_api, ApiBackend::Invidious)
+            && self.invidious_instance.is_none()
+            && let Err(e) = self.set_instance().await
+        {
+            self.selected_api = ApiBackend::Local;
+            emit_msg!(error, format!("{e} Falling back to the local API."));
+        }
+
+        let ids = mem::take(&mut self.pending_refreshes);
+
+        if !ids.is_empty() {
+            let instance = self.instance();
+            tokio::spawn(async move { refresh_channels(instance, ids).await });
+        }
+
+        for input in mem::take(&mut self.pending_subscriptions) {
+            let instance = self.instance();
+            tokio::spawn(async move { subscribe_to_channel(instance, input).await });
+        }
+
+        Ok(())
+    }
+
     async fn switch_api(&mut self) -> Result<()> {
         self.selected_api = match self.selected_api {
             ApiBackend::Local => ApiBackend::Invidious,
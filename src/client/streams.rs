@@ -0,0 +1,643 @@
+use super::downloads::{DownloadCommand, PROGRESS_INTERVAL, download_dir, set_state};
+use crate::{
+    CONFIG,
+    api::{Api, Format},
+    download::{DownloadProgress, DownloadState},
+    http,
+    list::{ListItem, SelectionList},
+    stream_formats::Formats,
+    video::{PlaybackSpec, VideoMetadata},
+};
+use anyhow::{Context, Result, bail, ensure};
+use reqwest::{Client, StatusCode, header::RANGE};
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+use tokio::{
+    fs::{self, OpenOptions},
+    io::AsyncWriteExt,
+    process::Command,
+    sync::mpsc::UnboundedSender,
+    time::Instant,
+};
+use tokio_util::sync::CancellationToken;
+
+/// Size of the ranged requests. Requesting the streams in chunks avoids the
+/// throttling of long responses and lets interrupted downloads resume.
+const CHUNK_SIZE: u64 = 10 * 1024 * 1024;
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(300);
+
+struct Stream {
+    url: String,
+    extension: String,
+}
+
+impl Stream {
+    fn new(format: &Format) -> Self {
+        Self {
+            url: format.get_url().to_owned(),
+            extension: format.get_extension().to_owned(),
+        }
+    }
+}
+
+/// The streams of a download, resolved right before it starts.
+struct Streams {
+    video: Stream,
+    audio: Option<Stream>,
+    /// Language codes and the paths or URLs of the selected captions.
+    captions: Vec<(String, String)>,
+    chapters: Option<PathBuf>,
+}
+
+/// The formats selected in the format selection window. The URLs of the
+/// streams expire, so the formats are fetched again whenever the download
+/// starts and the same ones are selected.
+pub struct StreamSelection {
+    instance: Box<dyn Api>,
+    spec: PlaybackSpec,
+    use_adaptive_streams: bool,
+    /// Descriptions of the selected video and audio formats.
+    video: String,
+    audio: Option<String>,
+    /// Language codes of the selected captions.
+    captions: Vec<String>,
+}
+
+impl Clone for StreamSelection {
+    fn clone(&self) -> Self {
+        Self {
+            instance: dyn_clone::clone_box(self.instance.as_ref()),
+            spec: self.spec.clone(),
+            use_adaptive_streams: self.use_adaptive_streams,
+            video: self.video.clone(),
+            audio: self.audio.clone(),
+            captions: self.captions.clone(),
+        }
+    }
+}
+
+impl StreamSelection {
+    fn new(instance: Box<dyn Api>, formats: &Formats) -> Option<Self> {
+        let (video, audio) = formats.get_selected_video_formats()?;
+
+        Some(Self {
+            instance,
+            spec: formats.spec.clone(),
+            use_adaptive_streams: formats.use_adaptive_streams,
+            video: video.to_string(),
+            audio: audio.map(ToString::to_string),
+            captions: formats
+                .captions
+                .get_selected_items()
+                .iter()
+                .map(|caption| caption.id().to_owned())
+                .collect(),
+        })
+    }
+
+    async fn resolve(&self) -> Result<Streams> {
+        let video_info = self
+            .instance
+            .get_video_formats(&self.spec.metadata.video_id)
+            .await?;
+        let mut formats = Formats::new(self.spec.clone(), video_info);
+        formats.use_adaptive_streams = self.use_adaptive_streams;
+
+        let video_formats = if self.use_adaptive_streams {
+            &mut formats.video_formats
+        } else {
+            &mut formats.formats
+        };
+        select_format(video_formats, &self.video)
+            .context("The selected video format is no longer available")?;
+
+        if let Some(audio) = &self.audio {
+            select_format(&mut formats.audio_formats, audio)
+                .context("The selected audio format is no longer available")?;
+        }
+
+        for caption in &mut formats.captions.items {
+            caption.selected = self.captions.iter().any(|id| id == caption.item.id());
+        }
+
+        // The caption paths are resolved in the order of the selected captions.
+        let captions = formats
+            .captions
+            .get_selected_items()
+            .iter()
+            .map(|caption| caption.id().to_owned())
+            .zip(self.instance.get_caption_paths(&formats).await)
+            .collect();
+
+        let chapters = match formats.chapters.take() {
+            Some(chapters) => {
+                let video_id = self.spec.metadata.video_id.clone();
+                tokio::task::spawn_blocking(move || chapters.write_to_file(&video_id))
+                    .await?
+                    .ok()
+            }
+            None => None,
+        };
+
+        let (video, audio) = formats
+            .get_selected_video_formats()
+            .context("No downloadable stream available")?;
+
+        Ok(Streams {
+            video: Stream::new(video),
+            audio: audio.map(Stream::new),
+            captions,
+            chapters,
+        })
+    }
+}
+
+/// Selects the only format with the description.
+fn select_format(formats: &mut SelectionList<Format>, description: &str) -> Option<()> {
+    let index = formats
+        .items
+        .iter()
+        .position(|format| format.item.to_string() == description)?;
+
+    formats.deselect_all();
+    formats.items[index].selected = true;
+
+    Some(())
+}
+
+/// Queues the selected streams for download.
+pub fn queue_streams(
+    instance: Box<dyn Api>,
+    formats: &Formats,
+    download_tx: &UnboundedSender<DownloadCommand>,
+) -> Result<()> {
+    let metadata = formats.spec.metadata.clone();
+
+    match StreamSelection::new(instance, formats) {
+        Some(streams) => {
+            download_tx.send(DownloadCommand::QueueStreams(metadata, Box::new(streams)))?;
+        }
+        None => set_state(
+            metadata.video_id,
+            DownloadState::Failed("No downloadable stream available".to_owned()),
+        )?,
+    }
+
+    Ok(())
+}
+
+/// Downloads the selected streams and returns the path of the video.
+pub async fn download_streams(
+    metadata: VideoMetadata,
+    selection: StreamSelection,
+    token: CancellationToken,
+) -> Result<PathBuf> {
+    tokio::select! {
+        () = token.cancelled() => bail!("Download was cancelled"),
+        result = download(metadata, selection) => result,
+    }
+}
+
+async fn download(metadata: VideoMetadata, selection: StreamSelection) -> Result<PathBuf> {
+    let streams = selection.resolve().await?;
+    let client = http::client()?;
+    let stem = output_stem(&download_dir()?, &metadata);
+
+    if let Some(directory) = stem.parent() {
+        fs::create_dir_all(directory).await?;
+    }
+
+    let video_path = with_suffix(&stem, &streams.video.extension);
+    let audio_path = streams
+        .audio
+        .as_ref()
+        .map(|audio| with_suffix(&stem, &format!("audio.{}", audio.extension)));
+
+    let targets = std::iter::once((&streams.video, &video_path))
+        .chain(streams.audio.as_ref().zip(audio_path.as_ref()))
+        .collect::<Vec<_>>();
+
+    let mut progress = Progress::new(metadata.video_id);
+
+    for (stream, path) in &targets {
+        progress.add_stream(&client, &stream.url, path).await?;
+    }
+
+    for (stream, path) in targets {
+        fetch_stream(&client, &stream.url, path, &mut progress).await?;
+    }
+
+    for (language, source) in &streams.captions {
+        save_caption(&client, source, &stem, language).await?;
+    }
+
+    let chapters = match &streams.chapters {
+        Some(source) => {
+            let path = with_suffix(&stem, "ffmetadata");
+            fs::copy(source, &path).await?;
+            Some(path)
+        }
+        None => None,
+    };
+
+    let Some((audio, audio_path)) = streams.audio.zip(audio_path) else {
+        return Ok(video_path);
+    };
+
+    let container = mux_container(&streams.video.extension, &audio.extension);
+    let muxed_path = with_suffix(&stem, &format!("muxing.{container}"));
+
+    // Without ffmpeg, mpv loads the separate audio file next to the video.
+    if !mux(&video_path, &audio_path, chapters.as_deref(), &muxed_path).await? {
+        return Ok(video_path);
+    }
+
+    let output = with_suffix(&stem, container);
+    fs::remove_file(&video_path).await?;
+    fs::remove_file(&audio_path).await?;
+    fs::rename(&muxed_path, &output).await?;
+
+    Ok(output)
+}
+
+/// Tracks the progress of all the streams of a download.
+struct Progress {
+    video_id: String,
+    downloaded: u64,
+    total: Option<u64>,
+    /// Bytes that were already on disk when the download started.
+    resumed: u64,
+    started: Instant,
+    last_report: Option<Instant>,
+}
+
+impl Progress {
+    fn new(video_id: String) -> Self {
+        Self {
+            video_id,
+            downloaded: 0,
+            total: Some(0),
+            resumed: 0,
+            started: Instant::now(),
+            last_report: None,
+        }
+    }
+
+    async fn add_stream(&mut self, client: &Client, url: &str, path: &Path) -> Result<()> {
+        let (present, length) = if let Ok(metadata) = fs::metadata(path).await {
+            (metadata.len(), Some(metadata.len()))
+        } else {
+            let present = fs::metadata(part_path(path))
+                .await
+                .map_or(0, |metadata| metadata.len());
+            (present, stream_length(client, url).await?)
+        };
+
+        self.downloaded += present;
+        self.resumed += present;
+        self.total = self.total.zip(length).map(|(total, length)| total + length);
+
+        Ok(())
+    }
+
+    fn advance(&mut self, bytes: u64) -> Result<()> {
+        self.downloaded += bytes;
+
+        if self
+            .last_report
+            .is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL)
+        {
+            self.last_report = Some(Instant::now());
+            set_state(
+                self.video_id.clone(),
+                DownloadState::Downloading(self.snapshot()),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Discards the bytes of a partial file that has to be downloaded again.
+    fn rewind(&mut self, bytes: u64) {
+        self.downloaded = self.downloaded.saturating_sub(bytes);
+        self.resumed = self.resumed.min(self.downloaded);
+    }
+
+    fn snapshot(&self) -> DownloadProgress {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let speed = (elapsed > 0.0)
+            .then(|| (self.downloaded - self.resumed) as f64 / elapsed)
+            .filter(|speed| *speed > 0.0);
+
+        DownloadProgress {
+            downloaded: self.downloaded,
+            total: self.total,
+            speed,
+            eta: self.total.zip(speed).map(|(total, speed)| {
+                (total.saturating_sub(self.downloaded) as f64 / speed) as u64
+            }),
+        }
+    }
+}
+
+async fn stream_length(client: &Client, url: &str) -> Result<Option<u64>> {
+    let response = client
+        .get(url)
+        .header(RANGE, "bytes=0-0")
+        .send()
+        .await?
+        .error_for_status()?;
+
+    if response.status() == StatusCode::PARTIAL_CONTENT {
+        Ok(content_range(&response).and_then(|(_, total)| total))
+    } else {
+        Ok(response.content_length())
+    }
+}
+
+/// Downloads the stream in chunks into a partial file, resuming from its
+/// length, and moves it to `path` once it is complete.
+async fn fetch_stream(
+    client: &Client,
+    url: &str,
+    path: &Path,
+    progress: &mut Progress,
+) -> Result<()> {
+    if fs::try_exists(path).await? {
+        return Ok(());
+    }
+
+    let part_path = part_path(path);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&part_path)
+        .await?;
+    let mut offset = file.metadata().await?.len();
+
+    loop {
+        let mut response = client
+            .get(url)
+            .header(RANGE, chunk_range(offset))
+            .timeout(CHUNK_TIMEOUT)
+            .send()
+            .await?;
+
+        let (complete, total) = match response.status() {
+            // The partial file already holds the whole stream.
+            StatusCode::RANGE_NOT_SATISFIABLE => break,
+            StatusCode::PARTIAL_CONTENT => {
+                let (start, total) = content_range(&response).context("invalid Content-Range")?;
+                ensure!(start == offset, "the server returned an unexpected range");
+
+                (false, total)
+            }
+            // The server ignored the range and sent the whole stream.
+            StatusCode::OK => {
+                file.set_len(0).await?;
+                progress.rewind(offset);
+                offset = 0;
+
+                (true, None)
+            }
+            _ => {
+                response.error_for_status()?;
+                bail!("unexpected response while downloading the stream");
+            }
+        };
+
+        let start = offset;
+
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
+            offset += chunk.len() as u64;
+            progress.advance(chunk.len() as u64)?;
+        }
+
+        let received = offset - start;
+
+        if complete || received == 0 || total.map_or(received < CHUNK_SIZE, |total| offset >= total)
+        {
+            break;
+        }
+    }
+
+    file.flush().await?;
+    drop(file);
+    fs::rename(&part_path, path).await?;
+
+    Ok(())
+}
+
+async fn save_caption(client: &Client, source: &str, stem: &Path, language: &str) -> Result<()> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let text = client
+            .get(source)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        fs::write(with_suffix(stem, &format!("{language}.vtt")), text).await?;
+    } else {
+        let source = Path::new(source);
+        let extension = source
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or("srt");
+
+        fs::copy(
+            source,
+            with_suffix(stem, &format!("{language}.{extension}")),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Muxes the video and audio streams with ffmpeg, embedding the chapters.
+/// Returns `false` if ffmpeg is not installed.
+async fn mux(video: &Path, audio: &Path, chapters: Option<&Path>, output: &Path) -> Result<bool> {
+    let mut command = Command::new(&CONFIG.ffmpeg_path);
+    command
+        .args(["-hide_banner", "-loglevel", "error", "-nostdin", "-y"])
+        .arg("-i")
+        .arg(video)
+        .arg("-i")
+        .arg(audio);
+
+    if let Some(chapters) = chapters {
+        command.arg("-i").arg(chapters).args(["-map_chapters", "2"]);
+    }
+
+    command
+        .args(["-map", "0:v", "-map", "1:a", "-c", "copy"])
+        .arg(output)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let result = match command.output().await {
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        result => {
+            result.with_context(|| format!("Failed to run {}", CONFIG.ffmpeg_path.display()))?
+        }
+    };
+
+    if !result.status.success() {
+        let _ = fs::remove_file(output).await;
+        bail!(
+            "ffmpeg exited with {}: {}",
+            result.status,
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+
+    Ok(true)
+}
+
+fn chunk_range(offset: u64) -> String {
+    format!("bytes={offset}-{}", offset + CHUNK_SIZE - 1)
+}
+
+/// Returns the start and the total length of a `Content-Range` header.
+fn content_range(response: &reqwest::Response) -> Option<(u64, Option<u64>)> {
+    parse_content_range(response.headers().get("content-range")?.to_str().ok()?)
+}
+
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, _) = range.split_once('-')?;
+
+    Some((start.parse().ok()?, total.parse().ok()))
+}
+
+fn mux_container<'a>(video_extension: &'a str, audio_extension: &str) -> &'a str {
+    match (video_extension, audio_extension) {
+        ("mp4", "m4a") | ("webm", "webm") => video_extension,
+        _ => "mkv",
+    }
+}
+
+/// The path of the download without an extension, laid out like the default
+/// `download_template`.
+fn output_stem(directory: &Path, metadata: &VideoMetadata) -> PathBuf {
+    directory
+        .join(sanitize(&metadata.channel))
+        .join(sanitize(&format!(
+            "{} [{}]",
+            metadata.title, metadata.video_id
+        )))
+}
+
+fn sanitize(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>();
+
+    name.trim().trim_start_matches('.').to_owned()
+}
+
+/// Appends a suffix to the path. Titles may contain dots, so
+/// [`Path::with_extension`] can't be used.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(".");
+    path.push(suffix);
+    path.into()
+}
+
+fn part_path(path: &Path) -> PathBuf {
+    with_suffix(path, "part")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CHUNK_SIZE, chunk_range, mux_container, output_stem, parse_content_range, select_format,
+        with_suffix,
+    };
+    use crate::{api::Format, list::SelectionList, video::VideoMetadata};
+    use std::path::Path;
+
+    #[test]
+    fn ranges_are_requested_in_chunks() {
+        assert_eq!(chunk_range(0), format!("bytes=0-{}", CHUNK_SIZE - 1));
+        assert_eq!(
+            chunk_range(CHUNK_SIZE),
+            format!("bytes={CHUNK_SIZE}-{}", 2 * CHUNK_SIZE - 1)
+        );
+
+        assert_eq!(
+            parse_content_range("bytes 1024-2047/4096"),
+            Some((1024, Some(4096)))
+        );
+        assert_eq!(parse_content_range("bytes 0-0/*"), Some((0, None)));
+        assert_eq!(parse_content_range("bytes */4096"), None);
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+    }
+
+    #[test]
+    fn downloads_are_named_after_the_video() {
+        let metadata = VideoMetadata {
+            video_id: "dQw4w9WgXcQ".to_owned(),
+            title: "AC/DC: Live at v1.0?".to_owned(),
+            channel: ".hidden".to_owned(),
+            ..Default::default()
+        };
+
+        let stem = output_stem(Path::new("/downloads"), &metadata);
+        assert_eq!(
+            stem,
+            Path::new("/downloads/hidden/AC_DC_ Live at v1.0_ [dQw4w9WgXcQ]")
+        );
+        assert_eq!(
+            with_suffix(&stem, "audio.m4a"),
+            Path::new("/downloads/hidden/AC_DC_ Live at v1.0_ [dQw4w9WgXcQ].audio.m4a")
+        );
+
+        assert_eq!(mux_container("mp4", "m4a"), "mp4");
+        assert_eq!(mux_container("webm", "webm"), "webm");
+        assert_eq!(mux_container("webm", "m4a"), "mkv");
+    }
+
+    #[test]
+    fn formats_are_selected_again_by_description() {
+        let format = |url: &str, quality: &str| Format::Video {
+            url: url.to_owned(),
+            quality: quality.to_owned(),
+            fps: 30,
+            r#type: "video/mp4".to_owned(),
+        };
+
+        let selected = format("https://expired", "1080p").to_string();
+        let mut formats = SelectionList::new(vec![
+            format("https://fresh/720", "720p"),
+            format("https://fresh/1080", "1080p"),
+        ]);
+        formats.select_all();
+
+        assert!(select_format(&mut formats, &selected).is_some());
+        assert_eq!(
+            formats
+                .get_selected_items()
+                .iter()
+                .map(|format| format.get_url())
+                .collect::<Vec<_>>(),
+            ["https://fresh/1080"]
+        );
+        assert!(select_format(&mut formats, "480p @ 30 fps, video/mp4").is_none());
+    }
+}
@@ -16,9 +16,12 @@ pub enum Command {
     PreviousTab,
     JumpToChannel,
     ToggleHide,
+    ToggleDismissed,
     Subscribe,
     Unsubscribe,
     DeleteVideo,
+    DismissVideo,
+    UndismissVideo,
     SearchForward,
     SearchBackward,
     RepeatLastSearch,
@@ -45,12 +48,28 @@ pub enum Command {
     IncreaseVolume,
     DecreaseVolume,
     ToggleMute,
+    IncreaseSpeed,
+    DecreaseSpeed,
+    ResetSpeed,
+    NextChapter,
+    PreviousChapter,
+    ToggleChapters,
+    ToggleTracks,
     StopPlayback,
     ReleaseVideo,
+    CycleSleepTimer,
     SelectFormats,
     ToggleWatched,
     ToggleHelp,
     ToggleTag,
+    Undo,
+    ToggleTrash,
+    ToggleFilters,
+    ToggleFiltered,
+    ToggleChannelSettings,
+    ToggleStats,
+    Download,
+    ToggleDownloads,
     Quit,
 }
 
@@ -75,9 +94,12 @@ impl TryFrom<&str> for Command {
             "previous_tab" => Command::PreviousTab,
             "jump_to_channel" => Command::JumpToChannel,
             "toggle_hide" => Command::ToggleHide,
+            "toggle_dismissed" => Command::ToggleDismissed,
             "subscribe" => Command::Subscribe,
             "unsubscribe" => Command::Unsubscribe,
             "delete_video" => Command::DeleteVideo,
+            "dismiss_video" => Command::DismissVideo,
+            "undismiss_video" => Command::UndismissVideo,
             "search_forward" => Command::SearchForward,
             "search_backward" => Command::SearchBackward,
             "repeat_last_search" => Command::RepeatLastSearch,
@@ -104,12 +126,28 @@ impl TryFrom<&str> for Command {
             "increase_volume" => Command::IncreaseVolume,
             "decrease_volume" => Command::DecreaseVolume,
             "toggle_mute" => Command::ToggleMute,
+            "increase_speed" => Command::IncreaseSpeed,
+            "decrease_speed" => Command::DecreaseSpeed,
+            "reset_speed" => Command::ResetSpeed,
+            "next_chapter" => Command::NextChapter,
+            "previous_chapter" => Command::PreviousChapter,
+            "toggle_chapters" => Command::ToggleChapters,
+            "toggle_tracks" => Command::ToggleTracks,
             "stop_playback" => Command::StopPlayback,
             "release_video" => Command::ReleaseVideo,
+            "cycle_sleep_timer" => Command::CycleSleepTimer,
             "select_formats" => Command::SelectFormats,
             "toggle_watched" => Command::ToggleWatched,
             "toggle_help" => Command::ToggleHelp,
             "toggle_tag" => Command::ToggleTag,
+            "undo" => Command::Undo,
+            "toggle_trash" => Command::ToggleTrash,
+            "toggle_filters" => Command::ToggleFilters,
+            "toggle_filtered" => Command::ToggleFiltered,
+            "toggle_channel_settings" => Command::ToggleChannelSettings,
+            "toggle_stats" => Command::ToggleStats,
+            "download" => Command::Download,
+            "toggle_downloads" => Command::ToggleDownloads,
             "quit" => Command::Quit,
             _ => anyhow::bail!("\"{command}\" is an invalid command"),
         };
@@ -174,6 +212,158 @@ impl TryFrom<&str> for TagCommand {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TrashCommand {
+    Restore,
+    Delete,
+    Abort,
+}
+
+impl TryFrom<&str> for TrashCommand {
+    type Error = anyhow::Error;
+
+    fn try_from(command: &str) -> Result<Self, Self::Error> {
+        let command = match command {
+            "restore" => TrashCommand::Restore,
+            "delete" => TrashCommand::Delete,
+            "abort" => TrashCommand::Abort,
+            _ => anyhow::bail!("\"{command}\" is an invalid command"),
+        };
+
+        Ok(command)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FilterCommand {
+    CreateRule,
+    CreateChannelRule,
+    DeleteRule,
+    Abort,
+}
+
+impl TryFrom<&str> for FilterCommand {
+    type Error = anyhow::Error;
+
+    fn try_from(command: &str) -> Result<Self, Self::Error> {
+        let command = match command {
+            "create_rule" => FilterCommand::CreateRule,
+            "create_channel_rule" => FilterCommand::CreateChannelRule,
+            "delete_rule" => FilterCommand::DeleteRule,
+            "abort" => FilterCommand::Abort,
+            _ => anyhow::bail!("\"{command}\" is an invalid command"),
+        };
+
+        Ok(command)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ChannelSettingsCommand {
+    Edit,
+    Reset,
+    Abort,
+}
+
+impl TryFrom<&str> for ChannelSettingsCommand {
+    type Error = anyhow::Error;
+
+    fn try_from(command: &str) -> Result<Self, Self::Error> {
+        let command = match command {
+            "edit" => ChannelSettingsCommand::Edit,
+            "reset" => ChannelSettingsCommand::Reset,
+            "abort" => ChannelSettingsCommand::Abort,
+            _ => anyhow::bail!("\"{command}\" is an invalid command"),
+        };
+
+        Ok(command)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StatsCommand {
+    Abort,
+}
+
+impl TryFrom<&str> for StatsCommand {
+    type Error = anyhow::Error;
+
+    fn try_from(command: &str) -> Result<Self, Self::Error> {
+        let command = match command {
+            "abort" => StatsCommand::Abort,
+            _ => anyhow::bail!("\"{command}\" is an invalid command"),
+        };
+
+        Ok(command)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ChapterSelectionCommand {
+    Confirm,
+    Abort,
+}
+
+impl TryFrom<&str> for ChapterSelectionCommand {
+    type Error = anyhow::Error;
+
+    fn try_from(command: &str) -> Result<Self, Self::Error> {
+        let command = match command {
+            "confirm" => ChapterSelectionCommand::Confirm,
+            "abort" => ChapterSelectionCommand::Abort,
+            _ => anyhow::bail!("\"{command}\" is an invalid command"),
+        };
+
+        Ok(command)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TrackSelectionCommand {
+    PreviousTab,
+    NextTab,
+    Select,
+    Abort,
+}
+
+impl TryFrom<&str> for TrackSelectionCommand {
+    type Error = anyhow::Error;
+
+    fn try_from(command: &str) -> Result<Self, Self::Error> {
+        let command = match command {
+            "previous_tab" => TrackSelectionCommand::PreviousTab,
+            "next_tab" => TrackSelectionCommand::NextTab,
+            "select" => TrackSelectionCommand::Select,
+            "abort" => TrackSelectionCommand::Abort,
+            _ => anyhow::bail!("\"{command}\" is an invalid command"),
+        };
+
+        Ok(command)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DownloadsCommand {
+    Cancel,
+    Retry,
+    Abort,
+}
+
+impl TryFrom<&str> for DownloadsCommand {
+    type Error = anyhow::Error;
+
+    fn try_from(command: &str) -> Result<Self, Self::Error> {
+        let command = match command {
+            "cancel" => DownloadsCommand::Cancel,
+            "retry" => DownloadsCommand::Retry,
+            "abort" => DownloadsCommand::Abort,
+            _ => anyhow::bail!("\"{command}\" is an invalid command"),
+        };
+
+        Ok(command)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ChannelSelectionCommand {
     Confirm,
@@ -207,6 +397,7 @@ pub enum FormatSelectionCommand {
     SwitchFormatType,
     Select,
     PlayVideo,
+    Download,
     Abort,
 }
 
@@ -220,6 +411,7 @@ impl TryFrom<&str> for FormatSelectionCommand {
             "switch_format_type" => FormatSelectionCommand::SwitchFormatType,
             "select" => FormatSelectionCommand::Select,
             "play_video" => FormatSelectionCommand::PlayVideo,
+            "download" => FormatSelectionCommand::Download,
             "abort" => FormatSelectionCommand::Abort,
             _ => anyhow::bail!("\"{command}\" is an invalid command"),
         };
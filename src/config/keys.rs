@@ -1,6 +1,7 @@
 use crate::commands::{
-    ChannelSelectionCommand, Command, FormatSelectionCommand, HelpCommand, ImportCommand,
-    TagCommand,
+    ChannelSelectionCommand, ChannelSettingsCommand, ChapterSelectionCommand, Command,
+    DownloadsCommand, FilterCommand, FormatSelectionCommand, HelpCommand, ImportCommand,
+    StatsCommand, TagCommand, TrackSelectionCommand, TrashCommand,
 };
 use anyhow::{Context, Result};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -16,6 +17,13 @@ pub struct UserKeyBindings {
     help: HashMap<String, String>,
     import: HashMap<String, String>,
     tag: HashMap<String, String>,
+    trash: HashMap<String, String>,
+    filter: HashMap<String, String>,
+    channel_settings: HashMap<String, String>,
+    stats: HashMap<String, String>,
+    chapter_selection: HashMap<String, String>,
+    track_selection: HashMap<String, String>,
+    downloads: HashMap<String, String>,
     channel_selection: HashMap<String, String>,
     format_selection: HashMap<String, String>,
 }
@@ -69,6 +77,13 @@ pub struct KeyBindings {
     pub help: HashMap<KeyEvent, HelpCommand>,
     pub import: HashMap<KeyEvent, ImportCommand>,
     pub tag: HashMap<KeyEvent, TagCommand>,
+    pub trash: HashMap<KeyEvent, TrashCommand>,
+    pub filter: HashMap<KeyEvent, FilterCommand>,
+    pub channel_settings: HashMap<KeyEvent, ChannelSettingsCommand>,
+    pub stats: HashMap<KeyEvent, StatsCommand>,
+    pub chapter_selection: HashMap<KeyEvent, ChapterSelectionCommand>,
+    pub track_selection: HashMap<KeyEvent, TrackSelectionCommand>,
+    pub downloads: HashMap<KeyEvent, DownloadsCommand>,
     pub channel_selection: HashMap<KeyEvent, ChannelSelectionCommand>,
     pub format_selection: HashMap<KeyEvent, FormatSelectionCommand>,
 }
@@ -80,6 +95,13 @@ impl Default for KeyBindings {
         let mut help = HashMap::new();
         let mut import = HashMap::new();
         let mut tag = HashMap::new();
+        let mut trash = HashMap::new();
+        let mut filter = HashMap::new();
+        let mut channel_settings = HashMap::new();
+        let mut stats = HashMap::new();
+        let mut chapter_selection = HashMap::new();
+        let mut track_selection = HashMap::new();
+        let mut downloads = HashMap::new();
         let mut channel_selection = HashMap::new();
         let mut format_selection = HashMap::new();
 
@@ -109,9 +131,12 @@ impl Default for KeyBindings {
         insert_binding!(general, "H", Command::PreviousTab);
         insert_binding!(general, "c", Command::JumpToChannel);
         insert_binding!(general, "t", Command::ToggleHide);
+        insert_binding!(general, "alt-t", Command::ToggleDismissed);
         insert_binding!(general, "i", Command::Subscribe);
         insert_binding!(general, "d", Command::Unsubscribe);
         insert_binding!(general, "D", Command::DeleteVideo);
+        insert_binding!(general, "X", Command::DismissVideo);
+        insert_binding!(general, "alt-x", Command::UndismissVideo);
         insert_binding!(general, "/", Command::SearchForward);
         insert_binding!(general, "?", Command::SearchBackward);
         insert_binding!(general, "n", Command::RepeatLastSearch);
@@ -138,11 +163,27 @@ impl Default for KeyBindings {
         insert_binding!(general, "+", Command::IncreaseVolume);
         insert_binding!(general, "-", Command::DecreaseVolume);
         insert_binding!(general, "_", Command::ToggleMute);
+        insert_binding!(general, "]", Command::IncreaseSpeed);
+        insert_binding!(general, "[", Command::DecreaseSpeed);
+        insert_binding!(general, "=", Command::ResetSpeed);
+        insert_binding!(general, "}", Command::NextChapter);
+        insert_binding!(general, "{", Command::PreviousChapter);
+        insert_binding!(general, "alt-c", Command::ToggleChapters);
+        insert_binding!(general, "alt-a", Command::ToggleTracks);
         insert_binding!(general, "x", Command::StopPlayback);
+        insert_binding!(general, "z", Command::CycleSleepTimer);
         insert_binding!(general, "f", Command::SelectFormats);
         insert_binding!(general, "m", Command::ToggleWatched);
         insert_binding!(general, "ctrl-h", Command::ToggleHelp);
         insert_binding!(general, "T", Command::ToggleTag);
+        insert_binding!(general, "u", Command::Undo);
+        insert_binding!(general, "U", Command::ToggleTrash);
+        insert_binding!(general, "alt-f", Command::ToggleFilters);
+        insert_binding!(general, "alt-h", Command::ToggleFiltered);
+        insert_binding!(general, "S", Command::ToggleChannelSettings);
+        insert_binding!(general, "alt-s", Command::ToggleStats);
+        insert_binding!(general, "w", Command::Download);
+        insert_binding!(general, "W", Command::ToggleDownloads);
         insert_binding!(general, "q", Command::Quit);
         insert_binding!(general, "ctrl-c", Command::Quit);
 
@@ -155,6 +196,36 @@ impl Default for KeyBindings {
         insert_binding!(tag, "r", TagCommand::RenameTag);
         insert_binding!(tag, "escape", TagCommand::Abort);
 
+        insert_binding!(trash, "enter", TrashCommand::Restore);
+        insert_binding!(trash, "d", TrashCommand::Delete);
+        insert_binding!(trash, "escape", TrashCommand::Abort);
+
+        insert_binding!(filter, "i", FilterCommand::CreateRule);
+        insert_binding!(filter, "c", FilterCommand::CreateChannelRule);
+        insert_binding!(filter, "d", FilterCommand::DeleteRule);
+        insert_binding!(filter, "escape", FilterCommand::Abort);
+
+        insert_binding!(channel_settings, "enter", ChannelSettingsCommand::Edit);
+        insert_binding!(channel_settings, "d", ChannelSettingsCommand::Reset);
+        insert_binding!(channel_settings, "escape", ChannelSettingsCommand::Abort);
+
+        insert_binding!(stats, "escape", StatsCommand::Abort);
+
+        insert_binding!(chapter_selection, "enter", ChapterSelectionCommand::Confirm);
+        insert_binding!(chapter_selection, "escape", ChapterSelectionCommand::Abort);
+
+        insert_binding!(track_selection, "h", TrackSelectionCommand::PreviousTab);
+        insert_binding!(track_selection, "left", TrackSelectionCommand::PreviousTab);
+        insert_binding!(track_selection, "l", TrackSelectionCommand::NextTab);
+        insert_binding!(track_selection, "right", TrackSelectionCommand::NextTab);
+        insert_binding!(track_selection, "enter", TrackSelectionCommand::Select);
+        insert_binding!(track_selection, "space", TrackSelectionCommand::Select);
+        insert_binding!(track_selection, "escape", TrackSelectionCommand::Abort);
+
+        insert_binding!(downloads, "d", DownloadsCommand::Cancel);
+        insert_binding!(downloads, "r", DownloadsCommand::Retry);
+        insert_binding!(downloads, "escape", DownloadsCommand::Abort);
+
         insert_binding!(import, "space", ImportCommand::ToggleSelection);
         insert_binding!(import, "a", ImportCommand::SelectAll);
         insert_binding!(import, "z", ImportCommand::DeselectAll);
@@ -173,6 +244,7 @@ impl Default for KeyBindings {
         insert_binding!(format_selection, "s", FormatSelectionCommand::SwitchFormatType);
         insert_binding!(format_selection, "space", FormatSelectionCommand::Select);
         insert_binding!(format_selection, "enter", FormatSelectionCommand::PlayVideo);
+        insert_binding!(format_selection, "d", FormatSelectionCommand::Download);
         insert_binding!(format_selection, "escape", FormatSelectionCommand::Abort);
 
         insert_binding!(help, "ctrl-y", HelpCommand::ScrollUp);
@@ -186,6 +258,13 @@ impl Default for KeyBindings {
             help,
             import,
             tag,
+            trash,
+            filter,
+            channel_settings,
+            stats,
+            chapter_selection,
+            track_selection,
+            downloads,
             channel_selection,
             format_selection
         }
@@ -230,6 +309,22 @@ impl TryFrom<UserKeyBindings> for KeyBindings {
         set_bindings(&mut key_bindings.help, &user_key_bindings.help)?;
         set_bindings(&mut key_bindings.import, &user_key_bindings.import)?;
         set_bindings(&mut key_bindings.tag, &user_key_bindings.tag)?;
+        set_bindings(&mut key_bindings.trash, &user_key_bindings.trash)?;
+        set_bindings(&mut key_bindings.filter, &user_key_bindings.filter)?;
+        set_bindings(
+            &mut key_bindings.channel_settings,
+            &user_key_bindings.channel_settings,
+        )?;
+        set_bindings(&mut key_bindings.stats, &user_key_bindings.stats)?;
+        set_bindings(
+            &mut key_bindings.chapter_selection,
+            &user_key_bindings.chapter_selection,
+        )?;
+        set_bindings(
+            &mut key_bindings.track_selection,
+            &user_key_bindings.track_selection,
+        )?;
+        set_bindings(&mut key_bindings.downloads, &user_key_bindings.downloads)?;
         set_bindings(
             &mut key_bindings.channel_selection,
             &user_key_bindings.channel_selection,
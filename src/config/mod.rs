@@ -1,23 +1,29 @@
 pub mod keys;
+pub mod players;
 pub mod theme;
 
-use self::{keys::KeyBindings, theme::Theme};
+use self::{
+    keys::KeyBindings,
+    players::{Player, PlayerProfile, PlayerSource},
+    theme::Theme,
+};
 use crate::{
     CLAP_ARGS,
     api::{ApiBackend, VideoFormat},
-    app::{Mode, VideoPlayer},
+    app::Mode,
     channel::ChannelTab,
     utils,
 };
-use anyhow::Result;
+use anyhow::{Result, ensure};
 use bitflags::bitflags;
 use chrono::format::StrftimeItems;
 use serde::{Deserialize, de};
-use std::{fs, path::PathBuf};
+use std::{borrow::Cow, collections::HashMap, fs, path::PathBuf};
 
 const CONFIG_FILE: &str = "config.toml";
 
 bitflags! {
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
     pub struct EnabledTabs: u8 {
         const VIDEOS  = 0b0001;
         const SHORTS  = 0b0010;
@@ -32,10 +38,27 @@ pub enum VideoInfoPosition {
     Bottom,
 }
 
+/// Which video is played after the current one reaches its end.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all(deserialize = "snake_case"))]
+pub enum AutoplayScope {
+    Off,
+    /// The next unwatched video of the list the video is in.
+    List,
+    /// The next unwatched video of the same channel in chronological order.
+    Channel,
+    /// The next unwatched video of the latest videos feed.
+    Latest,
+}
+
 #[derive(Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct Config {
     pub database: PathBuf,
+    #[serde(deserialize_with = "deserialize_max_backups")]
+    pub max_backups: Option<usize>,
+    pub trash_retention_days: u64,
+    pub inactive_channel_months: u64,
     pub instances: PathBuf,
     pub mode: Mode,
     #[serde(deserialize_with = "deserialize_tabs")]
@@ -48,7 +71,9 @@ pub struct Config {
     pub tick_rate: u64,
     pub request_timeout: u64,
     pub proxy: Option<String>,
+    pub offline: bool,
     pub mpv_proxy: Option<String>,
+    pub cookies: Option<PathBuf>,
     pub highlight_symbol: String,
     pub to_be_refreshed_symbol: String,
     pub refreshing_symbol: String,
@@ -56,7 +81,9 @@ pub struct Config {
     pub unicode_progress_bar: bool,
     #[serde(deserialize_with = "deserialize_date_format")]
     pub datetime_format: String,
-    pub video_player_for_stream_formats: VideoPlayer,
+    pub video_player_for_stream_formats: String,
+    pub video_player_for_ytdlp: String,
+    pub players: HashMap<String, PlayerProfile>,
     pub mpv_video_ipc: bool,
     #[serde(alias = "video_player")]
     pub mpv_path: PathBuf,
@@ -68,6 +95,8 @@ pub struct Config {
     pub always_show_video_info: bool,
     pub watched_threshold: u8,
     pub resume_playback: bool,
+    pub autoplay: AutoplayScope,
+    pub autoplay_countdown: u64,
     pub subtitle_languages: Vec<String>,
     pub prefer_dash_formats: bool,
     pub prefer_original_titles: bool,
@@ -77,6 +106,12 @@ pub struct Config {
     pub preferred_video_codec: Option<VideoFormat>,
     pub preferred_audio_codec: Option<VideoFormat>,
     pub chapters: bool,
+    pub mpris: bool,
+    pub ytdlp_path: PathBuf,
+    pub download_dir: PathBuf,
+    pub download_template: String,
+    pub download_format: Option<String>,
+    pub ffmpeg_path: PathBuf,
 
     pub theme: Theme,
     pub key_bindings: KeyBindings,
@@ -97,6 +132,7 @@ impl Config {
         };
 
         config.override_with_clap_args();
+        config.check_players()?;
 
         if config.database.as_os_str().is_empty() {
             config.database = utils::get_default_database_file()?;
@@ -109,6 +145,45 @@ impl Config {
         Ok(config)
     }
 
+    /// Resolves a video player by name. Profiles defined in `[players]` take
+    /// precedence over the built-in `mpv` and `vlc` players.
+    pub fn player(&'static self, name: &str) -> Option<Player> {
+        match self.players.get(name) {
+            Some(profile) => Some(Player::Profile(Cow::Borrowed(profile))),
+            None if name == "mpv" => Some(Player::Mpv),
+            None if name == "vlc" => Some(Player::Profile(Cow::Owned(PlayerProfile::vlc(
+                &self.vlc_path,
+            )))),
+            None => None,
+        }
+    }
+
+    fn check_players(&self) -> Result<()> {
+        for (name, profile) in &self.players {
+            ensure!(
+                !profile.command.is_empty(),
+                "the command of video player \"{name}\" is empty"
+            );
+        }
+
+        for (name, source) in [
+            (&self.video_player_for_stream_formats, PlayerSource::Formats),
+            (&self.video_player_for_ytdlp, PlayerSource::Ytdlp),
+        ] {
+            let is_supported = match self.players.get(name) {
+                Some(profile) => profile.supports(source),
+                None => matches!(name.as_str(), "mpv" | "vlc"),
+            };
+
+            ensure!(
+                is_supported,
+                "video player \"{name}\" is not defined or doesn't support {source}"
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn override_with_clap_args(&mut self) {
         if let Some(database) = CLAP_ARGS.get_one::<PathBuf>("database") {
             database.clone_into(&mut self.database);
@@ -118,6 +193,10 @@ impl Config {
             instances.clone_into(&mut self.instances);
         }
 
+        if CLAP_ARGS.get_flag("offline") {
+            self.offline = true;
+        }
+
         if let Some(tick_rate) = CLAP_ARGS.get_one::<u64>("tick_rate") {
             self.tick_rate = *tick_rate;
         }
@@ -136,6 +215,9 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             database: PathBuf::default(),
+            max_backups: None,
+            trash_retention_days: 30,
+            inactive_channel_months: 6,
             instances: PathBuf::default(),
             mode: Mode::default(),
             tabs: EnabledTabs::VIDEOS,
@@ -147,14 +229,18 @@ impl Default for Config {
             tick_rate: 10,
             request_timeout: 5,
             proxy: None,
+            offline: false,
             mpv_proxy: None,
+            cookies: None,
             highlight_symbol: String::new(),
             to_be_refreshed_symbol: String::from("□ "),
             refreshing_symbol: String::from("■ "),
             failed_symbol: String::from("✗ "),
             unicode_progress_bar: true,
             datetime_format: String::from("%Y-%m-%d %H:%M"),
-            video_player_for_stream_formats: VideoPlayer::Mpv,
+            video_player_for_stream_formats: String::from("mpv"),
+            video_player_for_ytdlp: String::from("mpv"),
+            players: HashMap::new(),
             mpv_video_ipc: true,
             mpv_path: PathBuf::from("mpv"),
             vlc_path: PathBuf::from("vlc"),
@@ -165,6 +251,8 @@ impl Default for Config {
             always_show_video_info: true,
             watched_threshold: 90,
             resume_playback: true,
+            autoplay: AutoplayScope::Off,
+            autoplay_countdown: 5,
             subtitle_languages: Vec::new(),
             prefer_dash_formats: true,
             prefer_original_titles: true,
@@ -173,6 +261,12 @@ impl Default for Config {
             preferred_video_codec: None,
             preferred_audio_codec: None,
             chapters: true,
+            mpris: true,
+            ytdlp_path: PathBuf::from("yt-dlp"),
+            download_dir: PathBuf::default(),
+            download_template: String::from("%(channel)s/%(title)s [%(id)s].%(ext)s"),
+            download_format: None,
+            ffmpeg_path: PathBuf::from("ffmpeg"),
 
             theme: Theme::default(),
             key_bindings: KeyBindings::default(),
@@ -218,6 +312,23 @@ where
     Ok(date_format)
 }
 
+/// The backup made by the current command is pruned too, so at least one has
+/// to be kept.
+fn deserialize_max_backups<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    let max_backups: Option<usize> = de::Deserialize::deserialize(deserializer)?;
+
+    if max_backups == Some(0) {
+        return Err(Error::custom("max_backups must be at least 1"));
+    }
+
+    Ok(max_backups)
+}
+
 fn deserialize_video_quality<'de, D>(deserializer: D) -> Result<u16, D::Error>
 where
     D: de::Deserializer<'de>,
@@ -226,19 +337,19 @@ where
 
     let quality_str: String = de::Deserialize::deserialize(deserializer)?;
 
-    let quality = if quality_str.to_lowercase() == "best" {
-        u16::MAX
-    } else if let Some(Ok(quality)) = quality_str.strip_suffix('p').map(str::parse::<u16>) {
-        quality
-    } else if let Ok(quality) = quality_str.parse::<u16>() {
-        quality
-    } else {
-        return Err(Error::custom(format!(
-            "\"{quality_str}\" is not a valid quality"
-        )));
-    };
+    parse_video_quality(&quality_str)
+        .ok_or_else(|| Error::custom(format!("\"{quality_str}\" is not a valid quality")))
+}
 
-    Ok(quality)
+/// Parses qualities such as `best`, `1080p` and `720`. `best` is `u16::MAX`.
+pub fn parse_video_quality(quality: &str) -> Option<u16> {
+    if quality.to_lowercase() == "best" {
+        Some(u16::MAX)
+    } else if let Some(Ok(quality)) = quality.strip_suffix('p').map(str::parse::<u16>) {
+        Some(quality)
+    } else {
+        quality.parse::<u16>().ok()
+    }
 }
 
 #[cfg(test)]
@@ -249,4 +360,40 @@ mod tests {
     fn bad_date_format_caught() {
         assert!(toml::from_str::<Config>(r#"datetime_format = "%Y-%Q-%d""#).is_err());
     }
+
+    #[test]
+    fn zero_max_backups_caught() {
+        assert!(toml::from_str::<Config>("max_backups = 0").is_err());
+        assert!(toml::from_str::<Config>("max_backups = 1").is_ok());
+    }
+
+    #[test]
+    fn undefined_players_are_caught() {
+        let config = toml::from_str::<Config>(
+            r#"
+            video_player_for_stream_formats = "ffplay"
+
+            [players.ffplay]
+            command = ["ffplay", "-window_title", "{title}", "{video_url}"]
+            sources = ["formats"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.check_players().is_ok());
+
+        let config = toml::from_str::<Config>(r#"video_player_for_ytdlp = "ffplay""#).unwrap();
+        assert!(config.check_players().is_err());
+
+        let config = toml::from_str::<Config>(
+            r#"
+            video_player_for_ytdlp = "ffplay"
+
+            [players.ffplay]
+            command = ["ffplay", "{video_url}"]
+            sources = ["formats"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.check_players().is_err());
+    }
 }
@@ -0,0 +1,265 @@
+use serde::Deserialize;
+use std::{borrow::Cow, fmt::Display, path::Path};
+
+/// Where a video player gets the video from.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all(deserialize = "snake_case"))]
+pub enum PlayerSource {
+    /// Stream URLs selected in the format selection window.
+    Formats,
+    /// YouTube URLs resolved by the player with yt-dlp, and downloaded files.
+    Ytdlp,
+}
+
+impl Display for PlayerSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Formats => write!(f, "stream formats"),
+            Self::Ytdlp => write!(f, "yt-dlp"),
+        }
+    }
+}
+
+/// The control interface used to track the progress of a player.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all(deserialize = "snake_case"))]
+pub enum PlayerInterface {
+    /// The HTTP interface of VLC, enabled with extra arguments.
+    VlcHttp,
+}
+
+fn all_sources() -> Vec<PlayerSource> {
+    vec![PlayerSource::Formats, PlayerSource::Ytdlp]
+}
+
+/// A video player defined by a command template.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerProfile {
+    pub command: Vec<String>,
+    #[serde(default = "all_sources")]
+    pub sources: Vec<PlayerSource>,
+    #[serde(default)]
+    pub interface: Option<PlayerInterface>,
+}
+
+/// The values substituted for the placeholders of a command template.
+pub struct PlayerInput<'a> {
+    pub video_url: &'a str,
+    pub audio_url: Option<&'a str>,
+    pub captions: &'a [String],
+    pub chapters: Option<&'a Path>,
+    pub title: &'a str,
+    pub start: Option<u64>,
+    pub speed: Option<f64>,
+}
+
+impl PlayerProfile {
+    pub fn vlc(vlc_path: &Path) -> Self {
+        Self {
+            command: [
+                &vlc_path.to_string_lossy(),
+                "--no-video-title-show",
+                "--input-title-format={title}",
+                "--play-and-exit",
+                "--start-time={start}",
+                "{video_url}",
+                "--input-slave={audio_url}",
+                "--sub-file={caption}",
+                "--rate={speed}",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            sources: all_sources(),
+            interface: Some(PlayerInterface::VlcHttp),
+        }
+    }
+
+    pub fn supports(&self, source: PlayerSource) -> bool {
+        self.sources.contains(&source)
+    }
+
+    /// Whether the template repeats an argument for each selected caption.
+    pub fn supports_multiple_captions(&self) -> bool {
+        self.command.iter().any(|arg| arg.contains("{captions}"))
+    }
+
+    /// Substitutes the placeholders of the command template. Arguments with
+    /// a placeholder that has no value are dropped, and the arguments with
+    /// `{captions}` are repeated for each caption.
+    pub fn build_command(&self, input: &PlayerInput) -> Vec<String> {
+        let start = input.start.map(|start| start.to_string());
+        let speed = input.speed.map(|speed| speed.to_string());
+        let chapters = input.chapters.map(Path::to_string_lossy);
+
+        let values = [
+            ("{video_url}", Some(input.video_url)),
+            ("{audio_url}", input.audio_url),
+            ("{caption}", input.captions.first().map(String::as_str)),
+            ("{chapters}", chapters.as_deref()),
+            ("{title}", Some(input.title)),
+            ("{start}", start.as_deref()),
+            ("{speed}", speed.as_deref()),
+            // Set to each caption in turn below.
+            ("{captions}", None),
+        ];
+
+        let mut command = Vec::new();
+
+        for arg in &self.command {
+            if arg.contains("{captions}") {
+                command.extend(input.captions.iter().filter_map(|caption| {
+                    let mut values = values;
+                    values[values.len() - 1].1 = Some(caption);
+                    substitute(arg, &values)
+                }));
+            } else if let Some(arg) = substitute(arg, &values) {
+                command.push(arg);
+            }
+        }
+
+        command
+    }
+}
+
+/// Replaces the placeholders in a single pass, so that placeholders in the
+/// substituted values, like a video title, are left as they are.
+fn substitute(arg: &str, values: &[(&str, Option<&str>)]) -> Option<String> {
+    let mut substituted = String::with_capacity(arg.len());
+    let mut rest = arg;
+
+    while let Some(index) = rest.find('{') {
+        substituted.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        match values
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                substituted.push_str((*value)?);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                substituted.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    substituted.push_str(rest);
+
+    Some(substituted)
+}
+
+/// A video player resolved from its name.
+pub enum Player {
+    /// The built-in mpv integration.
+    Mpv,
+    Profile(Cow<'static, PlayerProfile>),
+}
+
+impl Player {
+    pub fn supports_multiple_captions(&self) -> bool {
+        match self {
+            Self::Mpv => true,
+            Self::Profile(profile) => profile.supports_multiple_captions(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlayerInput, PlayerProfile, PlayerSource};
+    use std::path::Path;
+
+    #[test]
+    fn placeholders_are_substituted() {
+        let profile = toml::from_str::<PlayerProfile>(
+            r#"
+            command = ["celluloid", "--mpv-force-media-title={title}", "--mpv-start={start}",
+                "--mpv-audio-file={audio_url}", "--mpv-sub-file={captions}", "{video_url}"]
+            sources = ["formats"]
+            "#,
+        )
+        .unwrap();
+
+        assert!(profile.supports(PlayerSource::Formats));
+        assert!(!profile.supports(PlayerSource::Ytdlp));
+        assert!(profile.supports_multiple_captions());
+
+        let captions = ["en.srt".to_owned(), "de.srt".to_owned()];
+        let input = PlayerInput {
+            video_url: "https://example.com/video",
+            audio_url: None,
+            captions: &captions,
+            chapters: None,
+            title: "Title",
+            start: Some(42),
+            speed: None,
+        };
+
+        assert_eq!(
+            profile.build_command(&input),
+            [
+                "celluloid",
+                "--mpv-force-media-title=Title",
+                "--mpv-start=42",
+                "--mpv-sub-file=en.srt",
+                "--mpv-sub-file=de.srt",
+                "https://example.com/video"
+            ]
+        );
+
+        let vlc = PlayerProfile::vlc(Path::new("vlc"));
+        assert!(!vlc.supports_multiple_captions());
+        assert_eq!(
+            vlc.build_command(&PlayerInput {
+                start: None,
+                speed: Some(1.5),
+                ..input
+            }),
+            [
+                "vlc",
+                "--no-video-title-show",
+                "--input-title-format=Title",
+                "--play-and-exit",
+                "https://example.com/video",
+                "--sub-file=en.srt",
+                "--rate=1.5"
+            ]
+        );
+    }
+
+    #[test]
+    fn placeholders_in_values_are_kept() {
+        let profile = toml::from_str::<PlayerProfile>(
+            r#"
+            command = ["player", "--title={title}", "--audio={audio_url}", "--sub={captions}",
+                "{video_url}"]
+            "#,
+        )
+        .unwrap();
+
+        let captions = ["{title}.srt".to_owned()];
+        let input = PlayerInput {
+            video_url: "https://example.com/video",
+            audio_url: None,
+            captions: &captions,
+            chapters: None,
+            title: "Best {audio_url} tricks",
+            start: None,
+            speed: None,
+        };
+
+        assert_eq!(
+            profile.build_command(&input),
+            [
+                "player",
+                "--title=Best {audio_url} tricks",
+                "--sub={title}.srt",
+                "https://example.com/video"
+            ]
+        );
+    }
+}
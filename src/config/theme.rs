@@ -133,6 +133,10 @@ pub struct UserTheme {
     focused_watched: Option<UserStyle>,
     new_video_indicator: Option<UserStyle>,
     members_only_indicator: Option<UserStyle>,
+    dismissed_indicator: Option<UserStyle>,
+    filtered_indicator: Option<UserStyle>,
+    downloaded_indicator: Option<UserStyle>,
+    refresh_age: Option<UserStyle>,
     selected_block: Option<UserStyle>,
     video_info: Option<UserStyle>,
     progress_bar: Option<UserStyle>,
@@ -155,6 +159,10 @@ pub struct Theme {
     pub focused_watched: Style,
     pub new_video_indicator: Style,
     pub members_only_indicator: Style,
+    pub dismissed_indicator: Style,
+    pub filtered_indicator: Style,
+    pub downloaded_indicator: Style,
+    pub refresh_age: Style,
     pub selected_block: Style,
     pub video_info: Style,
     pub progress_bar: Style,
@@ -188,6 +196,16 @@ impl Default for Theme {
             members_only_indicator: Style::default()
                 .fg(Color::Green)
                 .add_modifier(Modifier::ITALIC),
+            dismissed_indicator: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::ITALIC),
+            filtered_indicator: Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::ITALIC),
+            downloaded_indicator: Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::ITALIC),
+            refresh_age: Style::default().fg(Color::Yellow),
             selected_block: Style::default().fg(Color::Magenta),
             video_info: Style::default().fg(Color::Green),
             progress_bar: Style::default().fg(Color::Red),
@@ -224,6 +242,10 @@ impl TryFrom<UserTheme> for Theme {
         set_theme_field!(focused_watched);
         set_theme_field!(new_video_indicator);
         set_theme_field!(members_only_indicator);
+        set_theme_field!(dismissed_indicator);
+        set_theme_field!(filtered_indicator);
+        set_theme_field!(downloaded_indicator);
+        set_theme_field!(refresh_age);
         set_theme_field!(selected_block);
         set_theme_field!(video_info);
         set_theme_field!(progress_bar);
@@ -0,0 +1,219 @@
+use crate::{CONFIG, utils};
+use anyhow::{Context, Result, bail};
+use reqwest::{Url, cookie::Jar};
+use std::sync::{Arc, OnceLock};
+
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+pub struct Cookies {
+    pub jar: Arc<Jar>,
+    sapisid: Option<String>,
+}
+
+impl Cookies {
+    /// Value of the `Authorization` header YouTube expects alongside the
+    /// cookies of a signed-in session.
+    pub fn authorization(&self, origin: &str) -> Option<String> {
+        let sapisid = self.sapisid.as_deref()?;
+        let timestamp = utils::now().ok()?;
+        let hash = sha1_smol::Sha1::from(format!("{timestamp} {sapisid} {origin}")).digest();
+
+        Some(format!("SAPISIDHASH {timestamp}_{hash}"))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct NetscapeCookie {
+    domain: String,
+    include_subdomains: bool,
+    path: String,
+    secure: bool,
+    expires: u64,
+    name: String,
+    value: String,
+}
+
+impl NetscapeCookie {
+    fn url(&self) -> Result<Url> {
+        let scheme = if self.secure { "https" } else { "http" };
+        let host = self.domain.trim_start_matches('.');
+
+        Url::parse(&format!("{scheme}://{host}{}", self.path))
+            .with_context(|| format!("invalid domain \"{}\" in cookies file", self.domain))
+    }
+
+    fn is_youtube(&self) -> bool {
+        let domain = self.domain.strip_prefix('.').unwrap_or(&self.domain);
+
+        domain == "youtube.com" || domain.ends_with(".youtube.com")
+    }
+
+    fn to_set_cookie(&self) -> String {
+        let mut cookie = format!("{}={}; Path={}", self.name, self.value, self.path);
+
+        if self.include_subdomains {
+            cookie.push_str("; Domain=");
+            cookie.push_str(&self.domain);
+        }
+
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+
+        cookie
+    }
+}
+
+/// Returns the cookies read from the file set in the `cookies` option. The
+/// file is only read once.
+pub fn get() -> Result<Option<&'static Cookies>> {
+    static COOKIES: OnceLock<Option<Cookies>> = OnceLock::new();
+
+    if let Some(cookies) = COOKIES.get() {
+        return Ok(cookies.as_ref());
+    }
+
+    let cookies = match &CONFIG.cookies {
+        Some(path) => {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("couldn't read cookies file {}", path.display()))?;
+            Some(load(&contents)?)
+        }
+        None => None,
+    };
+
+    let _ = COOKIES.set(cookies);
+
+    Ok(COOKIES.get().unwrap().as_ref())
+}
+
+fn load(contents: &str) -> Result<Cookies> {
+    let now = utils::now()?;
+    let jar = Jar::default();
+    let mut sapisid = None;
+
+    for cookie in parse(contents)? {
+        if cookie.expires != 0 && cookie.expires < now {
+            continue;
+        }
+
+        if cookie.is_youtube()
+            && (cookie.name == "SAPISID" || sapisid.is_none() && cookie.name == "__Secure-3PAPISID")
+        {
+            sapisid = Some(cookie.value.clone());
+        }
+
+        jar.add_cookie_str(&cookie.to_set_cookie(), &cookie.url()?);
+    }
+
+    Ok(Cookies {
+        jar: Arc::new(jar),
+        sapisid,
+    })
+}
+
+fn parse(contents: &str) -> Result<Vec<NetscapeCookie>> {
+    let mut cookies = Vec::new();
+
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line
+            .strip_prefix(HTTP_ONLY_PREFIX)
+            .unwrap_or(line)
+            .trim_end_matches('\r');
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Ok(fields) = <[&str; 7]>::try_from(line.split('\t').collect::<Vec<&str>>()) else {
+            bail!(
+                "line {} of the cookies file isn't in Netscape format",
+                idx + 1
+            );
+        };
+        let [
+            domain,
+            include_subdomains,
+            path,
+            secure,
+            expires,
+            name,
+            value,
+        ] = fields;
+
+        cookies.push(NetscapeCookie {
+            domain: domain.to_owned(),
+            include_subdomains: include_subdomains.eq_ignore_ascii_case("TRUE"),
+            path: path.to_owned(),
+            secure: secure.eq_ignore_ascii_case("TRUE"),
+            expires: expires.parse().unwrap_or_default(),
+            name: name.to_owned(),
+            value: value.to_owned(),
+        });
+    }
+
+    Ok(cookies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NetscapeCookie, load, parse};
+    use reqwest::{Url, cookie::CookieStore};
+
+    const COOKIES: &str = "# Netscape HTTP Cookie File
+# This file is generated by yt-dlp.  Do not edit.
+
+.youtube.com\tTRUE\t/\tTRUE\t0\tSAPISID\tabc/def
+#HttpOnly_.youtube.com\tTRUE\t/\tTRUE\t4102444800\tLOGIN_INFO\txyz
+www.youtube.com\tFALSE\t/\tFALSE\t1\tEXPIRED\tvalue
+";
+
+    #[test]
+    fn netscape_format() {
+        let cookies = parse(COOKIES).unwrap();
+
+        assert_eq!(cookies.len(), 3);
+        assert_eq!(
+            cookies[1],
+            NetscapeCookie {
+                domain: String::from(".youtube.com"),
+                include_subdomains: true,
+                path: String::from("/"),
+                secure: true,
+                expires: 4102444800,
+                name: String::from("LOGIN_INFO"),
+                value: String::from("xyz"),
+            }
+        );
+
+        assert!(parse("youtube.com\tTRUE\t/").is_err());
+    }
+
+    #[test]
+    fn expired_cookies_are_skipped() {
+        let cookies = load(COOKIES).unwrap();
+        let url = Url::parse("https://www.youtube.com/youtubei/v1/player").unwrap();
+        let header = cookies.jar.cookies(&url).unwrap();
+        let header = header.to_str().unwrap();
+
+        assert!(header.contains("SAPISID=abc/def"));
+        assert!(header.contains("LOGIN_INFO=xyz"));
+        assert!(!header.contains("EXPIRED"));
+        assert!(cookies.authorization("https://www.youtube.com").is_some());
+        assert!(
+            cookies
+                .jar
+                .cookies(&Url::parse("https://example.com").unwrap())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn sapisid_of_other_domains_is_ignored() {
+        let cookies = load(".notyoutube.com\tTRUE\t/\tTRUE\t0\tSAPISID\tabc\n").unwrap();
+        assert!(cookies.authorization("https://www.youtube.com").is_none());
+
+        let cookies = load("youtube.com\tFALSE\t/\tTRUE\t0\tSAPISID\tabc\n").unwrap();
+        assert!(cookies.authorization("https://www.youtube.com").is_some());
+    }
+}
@@ -1,18 +1,29 @@
 use crate::{
+    api::VideoFormat,
     channel::{Channel, ChannelTab},
+    channel_settings::ChannelSettings,
+    config::EnabledTabs,
+    filter::{FilterConditions, FilterRule, FilterScope, Filters},
+    state::{MergeStrategy, VideoState},
+    stats::ChannelStats,
+    trash::{TrashEntry, TrashKind, TrashedChannel, TrashedTag, TrashedVideo},
     utils,
     video::{Video, VideoListItem},
 };
 use anyhow::{Context, Result, bail, ensure};
-use chrono::Utc;
-use rusqlite::{Connection, OpenFlags, Transaction, TransactionBehavior, params};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::{
+    Connection, OpenFlags, OptionalExtension, Transaction, TransactionBehavior, params,
+};
 use std::{
+    collections::HashSet,
     fs::{self, OpenOptions},
     ops::RangeInclusive,
     path::{Path, PathBuf},
 };
 
-const LATEST_USER_VERSION: u8 = 4;
+const LATEST_USER_VERSION: u8 = 12;
+const LATEST_VIDEOS_LIMIT: usize = 100;
 const MIN_DOWNGRADE_USER_VERSION: u8 = 1;
 
 fn user_version(conn: &Connection) -> Result<u8> {
@@ -145,6 +156,34 @@ fn apply_down_migrations(
 
 fn apply_down_migration(tx: &Transaction<'_>, current_version: u8) -> Result<()> {
     match current_version {
+        12 => {
+            tx.execute("DROP TABLE downloads", [])?;
+        }
+        11 => {
+            tx.execute(
+                "ALTER TABLE channel_settings DROP COLUMN subtitle_language",
+                [],
+            )?;
+        }
+        10 => {
+            tx.execute("DROP TABLE channel_settings", [])?;
+        }
+        9 => {
+            tx.execute("DROP TABLE filter_rules", [])?;
+        }
+        8 => {
+            tx.execute("DROP TABLE dismissed_videos", [])?;
+        }
+        7 => {
+            tx.execute("DROP TABLE trash", [])?;
+        }
+        6 => {
+            tx.execute("ALTER TABLE channels DROP COLUMN updated_at", [])?;
+            tx.execute("ALTER TABLE videos DROP COLUMN updated_at", [])?;
+        }
+        5 => {
+            tx.execute("ALTER TABLE video_state DROP COLUMN updated_at", [])?;
+        }
         4 => {
             tx.execute("CREATE TABLE watched (video_id TEXT PRIMARY KEY)", [])?;
             tx.execute(
@@ -185,12 +224,14 @@ fn apply_down_migration(tx: &Transaction<'_>, current_version: u8) -> Result<()>
     Ok(())
 }
 
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
 fn backup_db(conn: &Connection, database_path: &Path, schema_version: u8) -> Result<PathBuf> {
     let original_name = database_path
         .file_name()
         .context("database path does not contain a filename")?;
 
-    let timestamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+    let timestamp = Utc::now().format(BACKUP_TIMESTAMP_FORMAT);
 
     let mut backup_name = original_name.to_os_string();
     backup_name.push(format!(".schema-{schema_version}.{timestamp}.bak"));
@@ -236,6 +277,149 @@ fn backup_db(conn: &Connection, database_path: &Path, schema_version: u8) -> Res
     Ok(backup_path)
 }
 
+#[derive(Debug)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub schema_version: u8,
+    pub size: u64,
+    pub created: Option<DateTime<Utc>>,
+}
+
+/// Lists the backups created next to the database, oldest first.
+pub fn list_backups(database_path: &Path) -> Result<Vec<Backup>> {
+    let original_name = database_path
+        .file_name()
+        .context("database path does not contain a filename")?;
+    let prefix = format!("{}.schema-", original_name.to_string_lossy());
+    let directory = match database_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(error)
+                .with_context(|| format!("failed to read directory {}", directory.display()));
+        }
+    };
+
+    let mut backups = Vec::new();
+
+    for entry in entries {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(rest) = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix(&prefix))
+            .and_then(|name| name.strip_suffix(".bak"))
+        else {
+            continue;
+        };
+
+        // "<schema>.<timestamp>" with an optional ".<number>" for name collisions
+        let mut parts = rest.split('.');
+        let Some(Ok(schema_version)) = parts.next().map(str::parse) else {
+            continue;
+        };
+        let created = parts
+            .next()
+            .and_then(|timestamp| {
+                NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT).ok()
+            })
+            .map(|created| created.and_utc());
+        let number: u32 = parts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+
+        backups.push((
+            number,
+            Backup {
+                path: entry.path(),
+                schema_version,
+                size: entry.metadata()?.len(),
+                created,
+            },
+        ));
+    }
+
+    // Backups taken within the same second by consecutive migrations are
+    // ordered by their schema version.
+    backups.sort_by(|(a_number, a), (b_number, b)| {
+        a.created
+            .cmp(&b.created)
+            .then(a.schema_version.cmp(&b.schema_version))
+            .then(a_number.cmp(b_number))
+    });
+
+    Ok(backups.into_iter().map(|(_, backup)| backup).collect())
+}
+
+/// Removes the oldest backups so that at most `keep` remain. Returns the
+/// removed backups.
+pub fn prune_backups(database_path: &Path, keep: usize) -> Result<Vec<PathBuf>> {
+    let backups = list_backups(database_path)?;
+    let mut removed = Vec::new();
+
+    for backup in &backups[..backups.len().saturating_sub(keep)] {
+        fs::remove_file(&backup.path)
+            .with_context(|| format!("failed to remove backup {}", backup.path.display()))?;
+        removed.push(backup.path.clone());
+    }
+
+    Ok(removed)
+}
+
+/// Replaces the database with the backup after backing up the current
+/// database. Returns the path of that backup, if the database existed.
+pub fn restore_backup(database_path: &Path, backup_path: &Path) -> Result<Option<PathBuf>> {
+    let backup = Connection::open_with_flags(backup_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("failed to open backup at {}", backup_path.display()))?;
+    let backup_version = user_version(&backup)
+        .with_context(|| format!("{} is not a ytsub database", backup_path.display()))?;
+
+    ensure!(
+        (MIN_DOWNGRADE_USER_VERSION..=LATEST_USER_VERSION).contains(&backup_version),
+        "backup schema version {backup_version} is not supported by this ytsub build; supported versions are {} through {}",
+        MIN_DOWNGRADE_USER_VERSION,
+        LATEST_USER_VERSION
+    );
+
+    let current_backup = match database_path.try_exists()? {
+        true => {
+            let flags = OpenFlags::default().difference(OpenFlags::SQLITE_OPEN_CREATE);
+            let conn = Connection::open_with_flags(database_path, flags)?;
+            let current_version = user_version(&conn)?;
+            Some(backup_db(&conn, database_path, current_version)?)
+        }
+        false => None,
+    };
+
+    let mut partial_path = database_path.to_owned();
+    partial_path.as_mut_os_string().push(".restore.partial");
+    let partial_file = PartialFile::new(partial_path);
+
+    backup
+        .backup(rusqlite::MAIN_DB, partial_file.path(), None)
+        .with_context(|| format!("failed to copy backup to {}", partial_file.path().display()))?;
+
+    if let Some(current_backup) = &current_backup {
+        fs::set_permissions(
+            partial_file.path(),
+            fs::metadata(current_backup)?.permissions(),
+        )?;
+    }
+
+    fs::rename(partial_file.path(), database_path).with_context(|| {
+        format!(
+            "failed to move restored database to {}",
+            database_path.display()
+        )
+    })?;
+    partial_file.keep();
+
+    Ok(current_backup)
+}
+
 struct PartialFile {
     path: PathBuf,
     persisted: bool,
@@ -432,6 +616,91 @@ fn apply_up_migration(conn: &mut Connection, current_user_version: u8) -> Result
 
             tx.commit()?;
         }
+        4 => {
+            conn.execute("ALTER TABLE video_state ADD COLUMN updated_at INTEGER", [])?;
+            conn.pragma_update(None, "user_version", 5)?;
+        }
+        5 => {
+            let tx = conn.transaction()?;
+
+            tx.execute("ALTER TABLE channels ADD COLUMN updated_at INTEGER", [])?;
+            tx.execute("ALTER TABLE videos ADD COLUMN updated_at INTEGER", [])?;
+
+            tx.pragma_update(None, "user_version", 6)?;
+
+            tx.commit()?;
+        }
+        6 => {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS trash (
+                    id INTEGER PRIMARY KEY,
+                    kind TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    data TEXT NOT NULL,
+                    deleted_at INTEGER NOT NULL
+                )",
+                [],
+            )?;
+            conn.pragma_update(None, "user_version", 7)?;
+        }
+        7 => {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS dismissed_videos (
+                    video_id TEXT PRIMARY KEY,
+                    dismissed_at INTEGER NOT NULL
+                )",
+                [],
+            )?;
+            conn.pragma_update(None, "user_version", 8)?;
+        }
+        8 => {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS filter_rules (
+                    id INTEGER PRIMARY KEY,
+                    channel_id TEXT,
+                    tag_name TEXT,
+                    pattern TEXT,
+                    min_length INTEGER,
+                    max_length INTEGER,
+                    tab INTEGER
+                )",
+                [],
+            )?;
+            conn.pragma_update(None, "user_version", 9)?;
+        }
+        9 => {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS channel_settings (
+                    channel_id TEXT PRIMARY KEY,
+                    default_tab INTEGER,
+                    speed REAL,
+                    audio_only BOOL NOT NULL DEFAULT 0,
+                    video_quality INTEGER,
+                    video_codec TEXT,
+                    hidden_tabs INTEGER NOT NULL DEFAULT 0
+                )",
+                [],
+            )?;
+            conn.pragma_update(None, "user_version", 10)?;
+        }
+        10 => {
+            conn.execute(
+                "ALTER TABLE channel_settings ADD COLUMN subtitle_language TEXT",
+                [],
+            )?;
+            conn.pragma_update(None, "user_version", 11)?;
+        }
+        11 => {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS downloads (
+                    video_id TEXT PRIMARY KEY,
+                    path TEXT NOT NULL,
+                    downloaded_at INTEGER NOT NULL
+                )",
+                [],
+            )?;
+            conn.pragma_update(None, "user_version", 12)?;
+        }
         _ => unreachable!(),
     }
 
@@ -440,8 +709,8 @@ fn apply_up_migration(conn: &mut Connection, current_user_version: u8) -> Result
 
 pub fn create_channel(conn: &Connection, channel: &Channel) -> Result<()> {
     conn.execute(
-        "INSERT INTO channels (channel_id, channel_name, last_refreshed)
-        VALUES (?1, ?2, ?3)",
+        "INSERT INTO channels (channel_id, channel_name, last_refreshed, updated_at)
+        VALUES (?1, ?2, ?3, ?3)",
         params![channel.channel_id, channel.channel_name, utils::now().ok()],
     )?;
 
@@ -484,8 +753,9 @@ pub fn set_last_refreshed_field(
     channel_id: &str,
     time: Option<u64>,
 ) -> Result<()> {
-    let mut stmt = conn.prepare("UPDATE channels SET last_refreshed=?1 WHERE channel_id=?2")?;
-    stmt.execute(params![time, channel_id])?;
+    let mut stmt =
+        conn.prepare("UPDATE channels SET last_refreshed=?1, updated_at=?2 WHERE channel_id=?3")?;
+    stmt.execute(params![time, utils::now()?, channel_id])?;
     Ok(())
 }
 
@@ -524,7 +794,8 @@ fn build_bulk_stmt(
     match query_type {
         StatementType::AddVideo => format!(
             "INSERT OR REPLACE INTO videos ({columns_str})
-            VALUES {values_string}
+            SELECT * FROM (VALUES {values_string})
+            WHERE column1 NOT IN (SELECT video_id FROM dismissed_videos)
             "
         ),
         StatementType::AddToTag => format!(
@@ -543,14 +814,23 @@ fn build_bulk_stmt(
         ),
         StatementType::GetLatestVideos => format!(
             "SELECT DISTINCT videos.video_id, title, published, length, members_only, videos.channel_id,
-            channel_name, COALESCE(video_state.watched, 0), position
+            channel_name, COALESCE(video_state.watched, 0), position,
+            dismissed_videos.video_id IS NOT NULL, downloads.path
             FROM videos
             JOIN channels ON channels.channel_id = videos.channel_id
             JOIN tag_relations ON tag_relations.channel_id = channels.channel_id
             LEFT JOIN video_state ON video_state.video_id = videos.video_id
+            LEFT JOIN dismissed_videos ON dismissed_videos.video_id = videos.video_id
+            LEFT JOIN downloads ON downloads.video_id = videos.video_id
             WHERE tag_relations.tag_name IN ({values_string}) AND videos.tab=?1
+                AND (?2 OR dismissed_videos.video_id IS NULL)
+                AND NOT EXISTS (
+                    SELECT 1
+                    FROM channel_settings
+                    WHERE channel_settings.channel_id = videos.channel_id
+                        AND channel_settings.hidden_tabs & (1 << ?1)
+                )
             ORDER BY videos.published DESC
-            LIMIT 100
             "
         ),
         StatementType::AddWatched => format!(
@@ -575,10 +855,12 @@ pub fn add_videos(
         "length",
         "members_only",
         "tab",
+        "updated_at",
     ];
 
     let mut videos_values = Vec::with_capacity(videos.len() * columns.len());
     let tab = tab as u8;
+    let now = utils::now()?;
 
     for video in videos {
         let values = params![
@@ -588,7 +870,8 @@ pub fn add_videos(
             video.published,
             video.length,
             video.members_only,
-            tab
+            tab,
+            now
         ];
         videos_values.extend_from_slice(values);
     }
@@ -607,13 +890,259 @@ pub fn delete_video(conn: &Connection, video_id: &str) -> Result<()> {
 
 pub fn update_title(conn: &Connection, video_id: &str, title: &str) -> Result<()> {
     conn.execute(
-        "UPDATE videos SET title=?1 WHERE video_id=?2",
-        params![title, video_id],
+        "UPDATE videos SET title=?1, updated_at=?2 WHERE video_id=?3",
+        params![title, utils::now()?, video_id],
+    )?;
+
+    Ok(())
+}
+
+/// Hides the video and keeps refreshes from adding it back if it is deleted.
+pub fn dismiss_video(conn: &Connection, video_id: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO dismissed_videos (video_id, dismissed_at) VALUES (?1, ?2)",
+        params![video_id, utils::now()?],
+    )?;
+
+    Ok(())
+}
+
+pub fn undismiss_video(conn: &Connection, video_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM dismissed_videos WHERE video_id = ?1",
+        [video_id],
+    )?;
+
+    Ok(())
+}
+
+pub fn get_dismissed_video_ids(conn: &Connection) -> Result<HashSet<String>> {
+    let mut stmt = conn.prepare("SELECT video_id FROM dismissed_videos")?;
+    let video_ids = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<HashSet<_>>>()?;
+
+    Ok(video_ids)
+}
+
+pub fn create_filter_rule(
+    conn: &Connection,
+    scope: &FilterScope,
+    conditions: &FilterConditions,
+) -> Result<i64> {
+    let (channel_id, tag_name) = match scope {
+        FilterScope::Global => (None, None),
+        FilterScope::Channel { channel_id, .. } => (Some(channel_id), None),
+        FilterScope::Tag(tag) => (None, Some(tag)),
+    };
+
+    conn.execute(
+        "INSERT INTO filter_rules (channel_id, tag_name, pattern, min_length, max_length, tab)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            channel_id,
+            tag_name,
+            conditions.pattern.as_ref().map(regex_lite::Regex::as_str),
+            conditions.min_length,
+            conditions.max_length,
+            conditions.tab.map(|tab| tab as u8)
+        ],
+    )?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn delete_filter_rule(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM filter_rules WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+pub fn get_filter_rules(conn: &Connection) -> Result<Vec<FilterRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, filter_rules.channel_id, channel_name, tag_name, pattern, min_length,
+        max_length, tab
+        FROM filter_rules
+        LEFT JOIN channels ON channels.channel_id = filter_rules.channel_id
+        ORDER BY id",
+    )?;
+
+    let mut rules = Vec::new();
+
+    for rule in stmt.query_map([], |row| {
+        Ok((
+            row.get(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+            row.get(5)?,
+            row.get(6)?,
+            row.get::<_, Option<u8>>(7)?,
+        ))
+    })? {
+        let (id, channel_id, channel_name, tag_name, pattern, min_length, max_length, tab) = rule?;
+
+        let scope = match (channel_id, tag_name) {
+            (Some(channel_id), _) => FilterScope::Channel {
+                channel_id,
+                channel_name,
+            },
+            (None, Some(tag)) => FilterScope::Tag(tag),
+            (None, None) => FilterScope::Global,
+        };
+
+        rules.push(FilterRule {
+            id,
+            scope,
+            conditions: FilterConditions {
+                pattern: pattern.as_deref().map(regex_lite::Regex::new).transpose()?,
+                min_length,
+                max_length,
+                tab: tab.map(ChannelTab::from_stored).transpose()?,
+            },
+        });
+    }
+
+    Ok(rules)
+}
+
+pub fn get_filters(conn: &Connection) -> Result<Filters> {
+    Ok(Filters::new(
+        get_filter_rules(conn)?,
+        get_tag_relations(conn)?,
+    ))
+}
+
+pub fn get_channel_settings(conn: &Connection, channel_id: &str) -> Result<ChannelSettings> {
+    let Some((default_tab, settings)) = conn
+        .query_row(
+            "SELECT default_tab, speed, audio_only, video_quality, video_codec, hidden_tabs,
+            subtitle_language
+            FROM channel_settings
+            WHERE channel_id = ?1",
+            [channel_id],
+            |row| {
+                let settings = ChannelSettings {
+                    default_tab: None,
+                    speed: row.get(1)?,
+                    audio_only: row.get(2)?,
+                    video_quality: row.get(3)?,
+                    video_codec: row.get::<_, Option<String>>(4)?.map(|codec| {
+                        match codec.as_str() {
+                            "webm" => VideoFormat::WebM,
+                            _ => VideoFormat::Mp4,
+                        }
+                    }),
+                    hidden_tabs: EnabledTabs::from_bits_truncate(row.get(5)?),
+                    subtitle_language: row.get(6)?,
+                };
+
+                Ok((row.get::<_, Option<u8>>(0)?, settings))
+            },
+        )
+        .optional()?
+    else {
+        return Ok(ChannelSettings::default());
+    };
+
+    Ok(ChannelSettings {
+        default_tab: default_tab.map(ChannelTab::from_stored).transpose()?,
+        ..settings
+    })
+}
+
+/// Stores the settings of the channel. Settings that all fall back to the
+/// configuration are removed instead.
+pub fn set_channel_settings(
+    conn: &Connection,
+    channel_id: &str,
+    settings: &ChannelSettings,
+) -> Result<()> {
+    if *settings == ChannelSettings::default() {
+        conn.execute(
+            "DELETE FROM channel_settings WHERE channel_id = ?1",
+            [channel_id],
+        )?;
+        return Ok(());
+    }
+
+    conn.execute(
+        "INSERT OR REPLACE INTO channel_settings (channel_id, default_tab, speed, audio_only,
+        video_quality, video_codec, hidden_tabs, subtitle_language)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            channel_id,
+            settings.default_tab.map(|tab| tab as u8),
+            settings.speed,
+            settings.audio_only,
+            settings.video_quality,
+            settings.video_codec.map(|codec| codec.to_string()),
+            settings.hidden_tabs.bits(),
+            settings.subtitle_language
+        ],
     )?;
 
     Ok(())
 }
 
+/// Returns the watch statistics of every channel. Videos published at or
+/// after `since` count as recent uploads.
+pub fn get_channel_stats(conn: &Connection, since: u64) -> Result<Vec<ChannelStats>> {
+    let mut stmt = conn.prepare(
+        "SELECT channels.channel_id, channel_name, COUNT(videos.video_id),
+        COUNT(CASE WHEN video_state.watched THEN 1 END),
+        COALESCE(SUM(
+            CASE WHEN video_state.watched
+            THEN COALESCE(videos.length, video_state.position, 0)
+            ELSE COALESCE(video_state.position, 0) END
+        ), 0),
+        MAX(CASE WHEN video_state.watched THEN video_state.updated_at END),
+        COUNT(CASE WHEN videos.published >= ?1 THEN 1 END)
+        FROM channels
+        LEFT JOIN videos ON videos.channel_id = channels.channel_id
+        LEFT JOIN video_state ON video_state.video_id = videos.video_id
+        GROUP BY channels.channel_id
+        ORDER BY channel_name COLLATE NOCASE ASC",
+    )?;
+
+    let channels = stmt
+        .query_map([since], |row| {
+            Ok(ChannelStats {
+                channel_id: row.get(0)?,
+                channel_name: row.get(1)?,
+                videos: row.get(2)?,
+                watched: row.get(3)?,
+                watch_time: row.get(4)?,
+                last_watched: row.get(5)?,
+                recent_uploads: row.get(6)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    Ok(channels)
+}
+
+/// Returns the publish times of the videos published at or after `since`.
+pub fn get_upload_times(conn: &Connection, since: u64) -> Result<Vec<u64>> {
+    let mut stmt = conn.prepare("SELECT published FROM videos WHERE published >= ?1")?;
+    let times = stmt
+        .query_map([since], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    Ok(times)
+}
+
+/// Returns when the videos marked as watched at or after `since` were marked.
+pub fn get_watch_times(conn: &Connection, since: u64) -> Result<Vec<u64>> {
+    let mut stmt =
+        conn.prepare("SELECT updated_at FROM video_state WHERE watched AND updated_at >= ?1")?;
+    let times = stmt
+        .query_map([since], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    Ok(times)
+}
+
 pub fn get_channels(conn: &Connection, tags: &[&str]) -> Result<Vec<Channel>> {
     let mut stmt;
     let values;
@@ -654,34 +1183,44 @@ pub fn get_videos(
     conn: &Connection,
     channel_id: &str,
     tab: ChannelTab,
+    show_dismissed: bool,
+    filters: &Filters,
 ) -> Result<Vec<VideoListItem>> {
     let mut stmt = conn.prepare(
         "SELECT videos.video_id, title, published, length, members_only,
-        COALESCE(video_state.watched, 0), position
+        COALESCE(video_state.watched, 0), position, dismissed_videos.video_id IS NOT NULL,
+        downloads.path
         FROM videos
         LEFT JOIN video_state ON video_state.video_id = videos.video_id
+        LEFT JOIN dismissed_videos ON dismissed_videos.video_id = videos.video_id
+        LEFT JOIN downloads ON downloads.video_id = videos.video_id
         WHERE videos.channel_id=?1 AND videos.tab=?2
+            AND (?3 OR dismissed_videos.video_id IS NULL)
         ORDER BY videos.published DESC
         ",
     )?;
     let mut videos = Vec::new();
-    for video in stmt.query_map(params![channel_id, tab as u8], |row| {
+    for video in stmt.query_map(params![channel_id, tab as u8, show_dismissed], |row| {
         let published = row.get(2)?;
+        let video = Video {
+            video_id: row.get(0)?,
+            title: row.get(1)?,
+            published,
+            length: row.get(3)?,
+            members_only: row.get(4).unwrap_or_default(),
+        };
 
         Ok(VideoListItem {
-            video: Video {
-                video_id: row.get(0)?,
-                title: row.get(1)?,
-                published,
-                length: row.get(3)?,
-                members_only: row.get(4).unwrap_or_default(),
-            },
+            filtered: filters.is_filtered(&video, channel_id, tab),
+            video,
             channel_id: channel_id.to_owned(),
             channel_name: None,
             published_text: utils::published_text(row.get(2)?, tab == ChannelTab::Streams)
                 .unwrap_or_default(),
             watched: row.get(5)?,
             position: row.get(6)?,
+            dismissed: row.get(7)?,
+            local_file: row.get::<_, Option<String>>(8)?.map(PathBuf::from),
             is_new: false,
         })
     })? {
@@ -695,22 +1234,32 @@ pub fn get_latest_videos(
     conn: &Connection,
     tags: &[&str],
     tab: ChannelTab,
+    show_dismissed: bool,
+    filters: &Filters,
 ) -> Result<Vec<VideoListItem>> {
     let mut stmt;
-    let mut values = Vec::with_capacity(tags.len() + 1);
-    let tab_param = params![tab as u8];
-    values.extend_from_slice(tab_param);
+    let mut values = Vec::with_capacity(tags.len() + 2);
+    let params = params![tab as u8, show_dismissed];
+    values.extend_from_slice(params);
 
     if tags.is_empty() {
         stmt = conn.prepare(
             "SELECT videos.video_id, title, published, length, members_only, videos.channel_id,
-            channel_name, COALESCE(video_state.watched, 0), position
+            channel_name, COALESCE(video_state.watched, 0), position,
+            dismissed_videos.video_id IS NOT NULL, downloads.path
             FROM videos
             JOIN channels ON channels.channel_id = videos.channel_id
             LEFT JOIN video_state ON video_state.video_id = videos.video_id
-            WHERE videos.tab=?1
+            LEFT JOIN dismissed_videos ON dismissed_videos.video_id = videos.video_id
+            LEFT JOIN downloads ON downloads.video_id = videos.video_id
+            WHERE videos.tab=?1 AND (?2 OR dismissed_videos.video_id IS NULL)
+                AND NOT EXISTS (
+                    SELECT 1
+                    FROM channel_settings
+                    WHERE channel_settings.channel_id = videos.channel_id
+                        AND channel_settings.hidden_tabs & (1 << ?1)
+                )
             ORDER BY videos.published DESC
-            LIMIT 100
             ",
         )?;
     } else {
@@ -722,118 +1271,863 @@ pub fn get_latest_videos(
         stmt = conn.prepare(&build_bulk_stmt(
             StatementType::GetLatestVideos,
             &["tag_name"],
-            2..=values.len(),
+            3..=values.len(),
         ))?;
     }
     let mut videos = Vec::new();
+    let mut unfiltered = 0;
 
+    // Filtered videos don't count towards the limit so that hiding them doesn't
+    // shorten the list.
     for video in stmt.query_map(values.as_slice(), |row| {
         let published = row.get(2)?;
+        let video = Video {
+            video_id: row.get(0)?,
+            title: row.get(1)?,
+            published,
+            length: row.get(3)?,
+            members_only: row.get(4).unwrap_or_default(),
+        };
+        let channel_id: String = row.get(5)?;
 
         Ok(VideoListItem {
-            video: Video {
-                video_id: row.get(0)?,
-                title: row.get(1)?,
-                published,
-                length: row.get(3)?,
-                members_only: row.get(4).unwrap_or_default(),
-            },
-            channel_id: row.get(5)?,
+            filtered: filters.is_filtered(&video, &channel_id, tab),
+            video,
+            channel_id,
             channel_name: Some(row.get(6)?),
             published_text: utils::published_text(row.get(2)?, tab == ChannelTab::Streams)
                 .unwrap_or_default(),
             watched: row.get(7)?,
             position: row.get(8)?,
+            dismissed: row.get(9)?,
+            local_file: row.get::<_, Option<String>>(10)?.map(PathBuf::from),
             is_new: false,
         })
     })? {
-        videos.push(video?);
+        let video = video?;
+
+        if !video.filtered {
+            unfiltered += 1;
+        }
+
+        videos.push(video);
+
+        if unfiltered == LATEST_VIDEOS_LIMIT {
+            break;
+        }
     }
 
     Ok(videos)
 }
 
-pub fn set_watched(conn: &Connection, video_id: &str, watched: bool) -> Result<()> {
-    let mut stmt = conn.prepare(
-        "
-        INSERT INTO video_state (video_id, watched)
-        VALUES (?1, ?2)
-        ON CONFLICT(video_id) DO UPDATE SET watched = excluded.watched
-        ",
+pub fn add_download(conn: &Connection, video_id: &str, path: &Path) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO downloads (video_id, path, downloaded_at) VALUES (?1, ?2, ?3)",
+        params![video_id, path.to_string_lossy(), utils::now()?],
     )?;
 
-    stmt.execute(params![video_id, watched])?;
     Ok(())
 }
 
-pub fn set_position(conn: &Connection, video_id: &str, position: u64) -> Result<()> {
+pub fn set_watched(conn: &Connection, video_id: &str, watched: bool) -> Result<()> {
     let mut stmt = conn.prepare(
         "
-        INSERT INTO video_state (video_id, position)
-        VALUES (?1, ?2)
-        ON CONFLICT(video_id) DO UPDATE SET position = excluded.position
+        INSERT INTO video_state (video_id, watched, updated_at)
+        VALUES (?1, ?2, ?3)
+        ON CONFLICT(video_id) DO UPDATE SET
+            watched = excluded.watched,
+            updated_at = excluded.updated_at
         ",
     )?;
 
-    stmt.execute(params![video_id, position])?;
+    stmt.execute(params![video_id, watched, utils::now()?])?;
     Ok(())
 }
 
-pub fn create_tag(conn: &Connection, tag_name: &str) -> Result<()> {
-    conn.execute(
-        "INSERT INTO tags (tag_name)
-        VALUES (?1)",
-        params![tag_name],
-    )?;
+/// Marks the videos as watched. Videos that aren't in the database yet are
+/// shown as watched once they are fetched. Returns the number of videos that
+/// are already in the database.
+pub fn mark_watched(conn: &mut Connection, video_ids: &[String]) -> Result<usize> {
+    let now = utils::now()?;
+    let tx = conn.transaction()?;
+    let mut existing = 0;
 
-    Ok(())
-}
+    {
+        let mut stmt = tx.prepare(
+            "
+            INSERT INTO video_state (video_id, watched, updated_at)
+            VALUES (?1, 1, ?2)
+            ON CONFLICT(video_id) DO UPDATE SET
+                watched = 1,
+                updated_at = excluded.updated_at
+            ",
+        )?;
+        let mut exists_stmt =
+            tx.prepare("SELECT EXISTS (SELECT 1 FROM videos WHERE video_id = ?1)")?;
 
-pub fn rename_tag(conn: &Connection, old_name: &str, new_name: &str) -> Result<()> {
-    conn.execute(
-        "UPDATE tags SET tag_name=?1 WHERE tag_name=?2",
-        params![new_name, old_name],
-    )?;
+        for video_id in video_ids {
+            stmt.execute(params![video_id, now])?;
 
-    Ok(())
+            if exists_stmt.query_row(params![video_id], |row| row.get(0))? {
+                existing += 1;
+            }
+        }
+    }
+
+    tx.commit()?;
+
+    Ok(existing)
 }
 
-pub fn get_tags(conn: &Connection) -> Result<Vec<String>> {
-    let mut stmt = conn.prepare("SELECT tag_name FROM tags")?;
+pub fn get_watched_video_ids(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT video_id FROM video_state WHERE watched = 1")?;
 
-    let mut tags = Vec::new();
+    let mut video_ids = Vec::new();
 
-    for tag in stmt.query_map([], |row| row.get(0))? {
-        tags.push(tag?);
+    for video_id in stmt.query_map([], |row| row.get(0))? {
+        video_ids.push(video_id?);
     }
 
-    Ok(tags)
+    Ok(video_ids)
 }
 
-pub fn delete_tag(conn: &Connection, tag_name: &str) -> Result<()> {
-    conn.execute("DELETE FROM tags WHERE tag_name=?1", params![tag_name])?;
+pub fn set_position(conn: &Connection, video_id: &str, position: u64) -> Result<()> {
+    let mut stmt = conn.prepare(
+        "
+        INSERT INTO video_state (video_id, position, updated_at)
+        VALUES (?1, ?2, ?3)
+        ON CONFLICT(video_id) DO UPDATE SET
+            position = excluded.position,
+            updated_at = excluded.updated_at
+        ",
+    )?;
+
+    stmt.execute(params![video_id, position, utils::now()?])?;
     Ok(())
 }
 
-pub fn update_channels_of_tag(
-    conn: &Connection,
-    tag_name: &str,
-    channel_ids: &[String],
-) -> Result<()> {
-    let present_channels = get_channels(conn, &[tag_name])?;
+pub fn get_video_states(conn: &Connection) -> Result<Vec<VideoState>> {
+    let mut stmt = conn.prepare(
+        "SELECT video_id, watched, position, updated_at FROM video_state
+        ORDER BY video_id",
+    )?;
 
-    let columns = ["tag_name", "channel_id"];
+    let mut states = Vec::new();
 
-    let new: Vec<&String> = channel_ids
-        .iter()
-        .filter(|channel_id| {
-            !present_channels
-                .iter()
-                .any(|other_channel| other_channel.channel_id == **channel_id)
+    for state in stmt.query_map([], |row| {
+        Ok(VideoState {
+            video_id: row.get(0)?,
+            watched: row.get(1)?,
+            position: row.get(2)?,
+            updated_at: row.get(3)?,
         })
-        .collect();
+    })? {
+        states.push(state?);
+    }
 
-    let mut values = Vec::with_capacity(new.len() * columns.len());
+    Ok(states)
+}
+
+/// Merges the states into `video_state`, resolving conflicts with the local
+/// state according to the strategy.
+/// Writes the states without a transaction of its own, so that the caller can
+/// make it part of a larger one.
+pub fn merge_video_states(
+    conn: &Connection,
+    states: &[VideoState],
+    strategy: MergeStrategy,
+) -> Result<()> {
+    let mut stmt = conn.prepare(&format!(
+        "
+        INSERT INTO video_state (video_id, watched, position, updated_at)
+        VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT(video_id) DO UPDATE SET {}
+        ",
+        video_state_conflict_clause(strategy)
+    ))?;
+
+    for state in states {
+        stmt.execute(params![
+            state.video_id,
+            state.watched,
+            state.position,
+            state.updated_at
+        ])?;
+    }
+
+    Ok(())
+}
+
+fn video_state_conflict_clause(strategy: MergeStrategy) -> String {
+    const NEWER: &str = "IFNULL(excluded.updated_at, 0) > IFNULL(video_state.updated_at, 0)";
+
+    match strategy {
+        MergeStrategy::WatchedWins => format!(
+            "watched = MAX(watched, excluded.watched),
+            position = CASE WHEN {NEWER} THEN IFNULL(excluded.position, position) ELSE position END,
+            updated_at = CASE WHEN {NEWER} THEN excluded.updated_at ELSE updated_at END"
+        ),
+        MergeStrategy::Newest => format!(
+            "watched = CASE WHEN {NEWER} THEN excluded.watched ELSE watched END,
+            position = CASE WHEN {NEWER} THEN IFNULL(excluded.position, position) ELSE position END,
+            updated_at = CASE WHEN {NEWER} THEN excluded.updated_at ELSE updated_at END"
+        ),
+        MergeStrategy::Overwrite => String::from(
+            "watched = excluded.watched,
+            position = excluded.position,
+            updated_at = excluded.updated_at",
+        ),
+    }
+}
+
+/// Assignments for an upsert that keep the columns of whichever row has the
+/// newer `updated_at`.
+fn newer_row_wins(table: &str, columns: &[&str]) -> String {
+    columns
+        .iter()
+        .chain(&["updated_at"])
+        .map(|column| {
+            format!(
+                "{column} = CASE WHEN IFNULL(excluded.updated_at, 0) > IFNULL({table}.updated_at, 0)
+                THEN excluded.{column} ELSE {table}.{column} END"
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug)]
+pub struct MergeOutcome {
+    pub backup_path: PathBuf,
+    pub new_channels: usize,
+    pub new_videos: usize,
+}
+
+/// Merges the subscriptions, videos, tags and video states of another
+/// database into this one. Rows that exist in both are resolved by their
+/// modification times, so merging the same database again is a no-op.
+pub fn merge_database(
+    conn: &mut Connection,
+    database_path: &Path,
+    other_path: &Path,
+) -> Result<MergeOutcome> {
+    ensure!(
+        fs::canonicalize(database_path)? != fs::canonicalize(other_path)?,
+        "cannot merge a database into itself"
+    );
+
+    let other = Connection::open_with_flags(other_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("failed to open database at {}", other_path.display()))?;
+    let other_version = user_version(&other)?;
+    drop(other);
+
+    ensure!(
+        other_version == LATEST_USER_VERSION,
+        "database schema version {other_version} of {} doesn't match the current version \
+        ({LATEST_USER_VERSION}); run this ytsub version on it first",
+        other_path.display()
+    );
+
+    let backup_path = backup_db(conn, database_path, LATEST_USER_VERSION)?;
+
+    let count = |conn: &Connection, table: &str| -> Result<usize> {
+        Ok(
+            conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })?,
+        )
+    };
+    let channels_before = count(conn, "channels")?;
+    let videos_before = count(conn, "videos")?;
+
+    conn.execute(
+        "ATTACH DATABASE ?1 AS other",
+        params![other_path.to_string_lossy()],
+    )?;
+
+    let result = (|| -> Result<()> {
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        tx.execute(
+            &format!(
+                "
+                INSERT INTO channels (channel_id, channel_name, last_refreshed, updated_at)
+                SELECT channel_id, channel_name, last_refreshed, updated_at
+                FROM other.channels WHERE true
+                ON CONFLICT(channel_id) DO UPDATE SET {}
+                ",
+                newer_row_wins("channels", &["channel_name", "last_refreshed"])
+            ),
+            [],
+        )?;
+
+        let video_columns = [
+            "channel_id",
+            "title",
+            "published",
+            "length",
+            "members_only",
+            "tab",
+        ];
+        tx.execute(
+            &format!(
+                "
+                INSERT INTO videos (video_id, {columns}, updated_at)
+                SELECT video_id, {columns}, updated_at
+                FROM other.videos WHERE true
+                ON CONFLICT(video_id) DO UPDATE SET {}
+                ",
+                newer_row_wins("videos", &video_columns),
+                columns = video_columns.join(", ")
+            ),
+            [],
+        )?;
+
+        tx.execute(
+            "INSERT OR IGNORE INTO tags (tag_name) SELECT tag_name FROM other.tags",
+            [],
+        )?;
+        tx.execute(
+            "
+            INSERT OR IGNORE INTO tag_relations (tag_name, channel_id)
+            SELECT tag_name, channel_id FROM other.tag_relations
+            ",
+            [],
+        )?;
+        tx.execute(
+            "
+            INSERT OR IGNORE INTO dismissed_videos (video_id, dismissed_at)
+            SELECT video_id, dismissed_at FROM other.dismissed_videos
+            ",
+            [],
+        )?;
+        tx.execute(
+            "
+            INSERT OR IGNORE INTO channel_settings
+            SELECT * FROM other.channel_settings
+            ",
+            [],
+        )?;
+
+        tx.execute(
+            &format!(
+                "
+                INSERT INTO video_state (video_id, watched, position, updated_at)
+                SELECT video_id, watched, position, updated_at
+                FROM other.video_state WHERE true
+                ON CONFLICT(video_id) DO UPDATE SET {}
+                ",
+                video_state_conflict_clause(MergeStrategy::Newest)
+            ),
+            [],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    })();
+
+    conn.execute("DETACH DATABASE other", [])?;
+    result.with_context(|| {
+        format!(
+            "failed to merge {}; the original database backup is at {}",
+            other_path.display(),
+            backup_path.display()
+        )
+    })?;
+
+    Ok(MergeOutcome {
+        new_channels: count(conn, "channels")? - channels_before,
+        new_videos: count(conn, "videos")? - videos_before,
+        backup_path,
+    })
+}
+
+#[derive(Default)]
+pub struct PruneOptions {
+    /// Only prune videos published before this time.
+    pub published_before: Option<u64>,
+    /// Number of the most recent videos of every channel to keep.
+    pub keep_per_channel: Option<usize>,
+    pub keep_watched: bool,
+    pub keep_in_progress: bool,
+}
+
+pub struct PruneOutcome {
+    pub deleted: usize,
+    pub backup_path: Option<PathBuf>,
+}
+
+/// Deletes the videos matching the options. Their state is kept so that it is
+/// applied again if the videos are fetched again. The database is backed up
+/// first if there is anything to delete.
+pub fn prune_videos(
+    conn: &mut Connection,
+    database_path: &Path,
+    options: &PruneOptions,
+) -> Result<PruneOutcome> {
+    let video_ids = {
+        let mut stmt = conn.prepare(
+            "
+            WITH ranked AS (
+                SELECT video_id, published,
+                    ROW_NUMBER() OVER (PARTITION BY channel_id ORDER BY published DESC) AS rank
+                FROM videos
+            )
+            SELECT ranked.video_id
+            FROM ranked
+            LEFT JOIN video_state ON video_state.video_id = ranked.video_id
+            WHERE (?1 IS NULL OR published < ?1)
+                AND (?2 IS NULL OR rank > ?2)
+                AND NOT (?3 AND IFNULL(watched, 0) = 1)
+                AND NOT (?4 AND IFNULL(watched, 0) = 0 AND position IS NOT NULL)
+            ",
+        )?;
+
+        stmt.query_map(
+            params![
+                options.published_before,
+                options.keep_per_channel,
+                options.keep_watched,
+                options.keep_in_progress
+            ],
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+    };
+
+    if video_ids.is_empty() {
+        return Ok(PruneOutcome {
+            deleted: 0,
+            backup_path: None,
+        });
+    }
+
+    let backup_path = backup_db(conn, database_path, LATEST_USER_VERSION)?;
+    let tx = conn.transaction()?;
+
+    {
+        let mut delete_video = tx.prepare("DELETE FROM videos WHERE video_id = ?1")?;
+        let mut delete_download = tx.prepare("DELETE FROM downloads WHERE video_id = ?1")?;
+
+        for video_id in &video_ids {
+            delete_video.execute(params![video_id])?;
+            delete_download.execute(params![video_id])?;
+        }
+    }
+
+    tx.commit()?;
+
+    Ok(PruneOutcome {
+        deleted: video_ids.len(),
+        backup_path: Some(backup_path),
+    })
+}
+
+pub fn vacuum(conn: &Connection) -> Result<()> {
+    conn.execute("VACUUM", [])?;
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub integrity_errors: Vec<String>,
+    pub foreign_key_violations: Vec<String>,
+    /// Video states without a video. These are kept on purpose for imported
+    /// watch history and pruned videos, so they aren't counted as errors.
+    pub detached_states: usize,
+}
+
+impl CheckReport {
+    pub fn has_errors(&self) -> bool {
+        !self.integrity_errors.is_empty() || !self.foreign_key_violations.is_empty()
+    }
+}
+
+pub fn check(conn: &Connection) -> Result<CheckReport> {
+    let mut report = CheckReport::default();
+
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    for message in stmt.query_map([], |row| row.get::<_, String>(0))? {
+        let message = message?;
+
+        if message != "ok" {
+            report.integrity_errors.push(message);
+        }
+    }
+
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    for violation in stmt.query_map([], |row| {
+        Ok(format!(
+            "row {} of {} references a missing row in {}",
+            row.get::<_, Option<i64>>(1)?
+                .map_or_else(|| String::from("?"), |rowid| rowid.to_string()),
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(2)?
+        ))
+    })? {
+        report.foreign_key_violations.push(violation?);
+    }
+
+    report.detached_states = conn.query_row(
+        "
+        SELECT COUNT(*) FROM video_state
+        WHERE NOT EXISTS (SELECT 1 FROM videos WHERE videos.video_id = video_state.video_id)
+        ",
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(report)
+}
+
+const TRASHED_VIDEO_COLUMNS: &str =
+    "video_id, channel_id, title, published, length, tab, members_only, updated_at";
+
+fn get_trashed_videos(conn: &Connection, column: &str, value: &str) -> Result<Vec<TrashedVideo>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {TRASHED_VIDEO_COLUMNS} FROM videos WHERE {column} = ?1"
+    ))?;
+
+    let videos = stmt
+        .query_map([value], |row| {
+            Ok(TrashedVideo {
+                video_id: row.get(0)?,
+                channel_id: row.get(1)?,
+                title: row.get(2)?,
+                published: row.get(3)?,
+                length: row.get(4)?,
+                tab: row.get(5)?,
+                members_only: row.get(6)?,
+                updated_at: row.get(7)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(videos)
+}
+
+fn restore_videos(conn: &Connection, videos: &[TrashedVideo]) -> Result<()> {
+    let mut stmt = conn.prepare(&format!(
+        "INSERT OR IGNORE INTO videos ({TRASHED_VIDEO_COLUMNS})
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+    ))?;
+
+    for video in videos {
+        stmt.execute(params![
+            video.video_id,
+            video.channel_id,
+            video.title,
+            video.published,
+            video.length,
+            video.tab,
+            video.members_only,
+            video.updated_at
+        ])?;
+    }
+
+    Ok(())
+}
+
+fn add_to_trash<T: serde::Serialize>(
+    conn: &Connection,
+    kind: TrashKind,
+    name: &str,
+    data: &T,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO trash (kind, name, data, deleted_at) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            kind.as_str(),
+            name,
+            serde_json::to_string(data)?,
+            utils::now()?
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Unsubscribes from the channel, keeping it in the trash with its videos and
+/// tags so that it can be restored.
+pub fn trash_channel(conn: &mut Connection, channel_id: &str) -> Result<()> {
+    let tx = conn.transaction()?;
+
+    let mut channel = tx.query_row(
+        "SELECT channel_name, last_refreshed, updated_at FROM channels WHERE channel_id = ?1",
+        [channel_id],
+        |row| {
+            Ok(TrashedChannel {
+                channel_id: channel_id.to_owned(),
+                channel_name: row.get(0)?,
+                last_refreshed: row.get(1)?,
+                updated_at: row.get(2)?,
+                videos: Vec::new(),
+                tags: Vec::new(),
+            })
+        },
+    )?;
+    channel.videos = get_trashed_videos(&tx, "channel_id", channel_id)?;
+    channel.tags = tx
+        .prepare("SELECT tag_name FROM tag_relations WHERE channel_id = ?1")?
+        .query_map([channel_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    add_to_trash(
+        &tx,
+        TrashKind::Channel,
+        channel.channel_name.as_deref().unwrap_or(channel_id),
+        &channel,
+    )?;
+    delete_channel(&tx, channel_id)?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+pub fn trash_video(conn: &mut Connection, video_id: &str) -> Result<()> {
+    let tx = conn.transaction()?;
+
+    let video = get_trashed_videos(&tx, "video_id", video_id)?
+        .pop()
+        .with_context(|| format!("video {video_id} is not in the database"))?;
+
+    add_to_trash(
+        &tx,
+        TrashKind::Video,
+        video.title.as_deref().unwrap_or(video_id),
+        &video,
+    )?;
+    delete_video(&tx, video_id)?;
+    dismiss_video(&tx, video_id)?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+pub fn trash_tag(conn: &mut Connection, tag_name: &str) -> Result<()> {
+    let tx = conn.transaction()?;
+
+    let tag = TrashedTag {
+        tag_name: tag_name.to_owned(),
+        channels: tx
+            .prepare("SELECT channel_id FROM tag_relations WHERE tag_name = ?1")?
+            .query_map([tag_name], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?,
+    };
+
+    add_to_trash(&tx, TrashKind::Tag, tag_name, &tag)?;
+    delete_tag(&tx, tag_name)?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Returns the trash entries, most recently deleted first.
+pub fn get_trash(conn: &Connection) -> Result<Vec<TrashEntry>> {
+    let mut stmt = conn.prepare("SELECT id, kind, name, deleted_at FROM trash ORDER BY id DESC")?;
+
+    let mut entries = Vec::new();
+
+    for entry in stmt.query_map([], |row| {
+        Ok((
+            row.get(0)?,
+            row.get::<_, String>(1)?,
+            row.get(2)?,
+            row.get(3)?,
+        ))
+    })? {
+        let (id, kind, name, deleted_at) = entry?;
+
+        entries.push(TrashEntry {
+            id,
+            kind: TrashKind::try_from(kind.as_str())?,
+            name,
+            deleted_at,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Restores the trash entry and removes it from the trash. Tag relations are
+/// only restored for tags and channels that still exist.
+pub fn restore_from_trash(conn: &mut Connection, id: i64) -> Result<TrashEntry> {
+    let tx = conn.transaction()?;
+
+    let (kind, name, data, deleted_at): (String, String, String, u64) = tx.query_row(
+        "SELECT kind, name, data, deleted_at FROM trash WHERE id = ?1",
+        [id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )?;
+    let kind = TrashKind::try_from(kind.as_str())?;
+
+    match kind {
+        TrashKind::Channel => {
+            let channel: TrashedChannel = serde_json::from_str(&data)?;
+
+            tx.execute(
+                "INSERT OR IGNORE INTO channels (channel_id, channel_name, last_refreshed, updated_at)
+                VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    channel.channel_id,
+                    channel.channel_name,
+                    channel.last_refreshed,
+                    channel.updated_at
+                ],
+            )?;
+            restore_videos(&tx, &channel.videos)?;
+
+            for tag in &channel.tags {
+                tx.execute(
+                    "INSERT OR IGNORE INTO tag_relations (tag_name, channel_id)
+                    SELECT ?1, ?2 WHERE EXISTS (SELECT 1 FROM tags WHERE tag_name = ?1)",
+                    params![tag, channel.channel_id],
+                )?;
+            }
+        }
+        TrashKind::Video => {
+            let video: TrashedVideo = serde_json::from_str(&data)?;
+
+            let subscribed: bool = tx.query_row(
+                "SELECT EXISTS (SELECT 1 FROM channels WHERE channel_id = ?1)",
+                [&video.channel_id],
+                |row| row.get(0),
+            )?;
+            ensure!(
+                subscribed,
+                "the channel of \"{name}\" is no longer subscribed"
+            );
+
+            undismiss_video(&tx, &video.video_id)?;
+            restore_videos(&tx, &[video])?;
+        }
+        TrashKind::Tag => {
+            let tag: TrashedTag = serde_json::from_str(&data)?;
+
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (tag_name) VALUES (?1)",
+                [&tag.tag_name],
+            )?;
+
+            for channel_id in &tag.channels {
+                tx.execute(
+                    "INSERT OR IGNORE INTO tag_relations (tag_name, channel_id)
+                    SELECT ?1, ?2 WHERE EXISTS (SELECT 1 FROM channels WHERE channel_id = ?2)",
+                    params![tag.tag_name, channel_id],
+                )?;
+            }
+        }
+    }
+
+    tx.execute("DELETE FROM trash WHERE id = ?1", [id])?;
+    tx.commit()?;
+
+    Ok(TrashEntry {
+        id,
+        kind,
+        name,
+        deleted_at,
+    })
+}
+
+pub fn delete_from_trash(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM trash WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Permanently deletes the entries that were moved to the trash before the
+/// given time. Returns the number of deleted entries.
+pub fn purge_trash(conn: &Connection, deleted_before: u64) -> Result<usize> {
+    Ok(conn.execute("DELETE FROM trash WHERE deleted_at < ?1", [deleted_before])?)
+}
+
+pub fn create_tag(conn: &Connection, tag_name: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO tags (tag_name)
+        VALUES (?1)",
+        params![tag_name],
+    )?;
+
+    Ok(())
+}
+
+pub fn rename_tag(conn: &Connection, old_name: &str, new_name: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE tags SET tag_name=?1 WHERE tag_name=?2",
+        params![new_name, old_name],
+    )?;
+    conn.execute(
+        "UPDATE filter_rules SET tag_name=?1 WHERE tag_name=?2",
+        params![new_name, old_name],
+    )?;
+
+    Ok(())
+}
+
+pub fn get_tags(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT tag_name FROM tags")?;
+
+    let mut tags = Vec::new();
+
+    for tag in stmt.query_map([], |row| row.get(0))? {
+        tags.push(tag?);
+    }
+
+    Ok(tags)
+}
+
+pub fn delete_tag(conn: &Connection, tag_name: &str) -> Result<()> {
+    conn.execute("DELETE FROM tags WHERE tag_name=?1", params![tag_name])?;
+    Ok(())
+}
+
+pub fn add_channel_to_tag(conn: &Connection, tag_name: &str, channel_id: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO tag_relations (tag_name, channel_id)
+        VALUES (?1, ?2)",
+        params![tag_name, channel_id],
+    )?;
+
+    Ok(())
+}
+
+pub fn get_tag_relations(conn: &Connection) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT tag_name, channel_id FROM tag_relations
+        ORDER BY tag_name COLLATE NOCASE ASC",
+    )?;
+
+    let mut relations = Vec::new();
+
+    for relation in stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+        relations.push(relation?);
+    }
+
+    Ok(relations)
+}
+
+pub fn update_channels_of_tag(
+    conn: &Connection,
+    tag_name: &str,
+    channel_ids: &[String],
+) -> Result<()> {
+    let present_channels = get_channels(conn, &[tag_name])?;
+
+    let columns = ["tag_name", "channel_id"];
+
+    let new: Vec<&String> = channel_ids
+        .iter()
+        .filter(|channel_id| {
+            !present_channels
+                .iter()
+                .any(|other_channel| other_channel.channel_id == **channel_id)
+        })
+        .collect();
+
+    let mut values = Vec::with_capacity(new.len() * columns.len());
     for channel_id in new {
         let v = params![tag_name, *channel_id];
         values.extend_from_slice(v);
@@ -869,8 +2163,22 @@ pub fn update_channels_of_tag(
 #[cfg(test)]
 mod tests {
     use super::{
-        DowngradeOutcome, LATEST_USER_VERSION, apply_up_migration, backup_db, downgrade_database,
-        open_db, reserve_backup_paths, user_version,
+        DowngradeOutcome, LATEST_USER_VERSION, PruneOptions, add_download, add_videos,
+        apply_up_migration, backup_db, check, create_filter_rule, delete_filter_rule,
+        delete_from_trash, dismiss_video, downgrade_database, get_channel_settings,
+        get_channel_stats, get_dismissed_video_ids, get_filter_rules, get_filters,
+        get_latest_videos, get_tag_relations, get_trash, get_video_states, get_videos,
+        get_watched_video_ids, list_backups, mark_watched, merge_database, open_db, prune_backups,
+        prune_videos, purge_trash, rename_tag, reserve_backup_paths, restore_backup,
+        restore_from_trash, set_channel_settings, set_position, trash_channel, trash_tag,
+        trash_video, undismiss_video, user_version,
+    };
+    use crate::{
+        channel::ChannelTab,
+        channel_settings::{ChannelSettings, Setting},
+        filter::{FilterScope, Filters, parse_rule},
+        trash::TrashKind,
+        video::{Video, VideoListItem},
     };
     use anyhow::Result;
     use rusqlite::Connection;
@@ -943,7 +2251,11 @@ mod tests {
     }
 
     fn create_schema_four_database(path: &Path) -> Result<()> {
-        let conn = open_db(path)?;
+        let mut conn = Connection::open(path)?;
+
+        for version in 0..4 {
+            apply_up_migration(&mut conn, version)?;
+        }
 
         conn.execute(
             "
@@ -1013,17 +2325,137 @@ mod tests {
     }
 
     #[test]
-    fn fresh_and_current_databases_are_not_backed_up() -> Result<()> {
+    fn fresh_and_current_databases_are_not_backed_up() -> Result<()> {
+        let directory = tempdir()?;
+        let database_path = directory.path().join("videos.db");
+
+        let conn = open_db(&database_path)?;
+        assert_eq!(user_version(&conn)?, LATEST_USER_VERSION);
+        assert!(completed_backups(directory.path())?.is_empty());
+
+        let conn = open_db(&database_path)?;
+        assert_eq!(user_version(&conn)?, LATEST_USER_VERSION);
+        assert!(completed_backups(directory.path())?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn mark_watched_keeps_saved_positions() -> Result<()> {
+        let directory = tempdir()?;
+        let mut conn = open_db(&directory.path().join("videos.db"))?;
+
+        set_position(&conn, VIDEO_ID, 42)?;
+        mark_watched(
+            &mut conn,
+            &[VIDEO_ID.to_owned(), "history-video".to_owned()],
+        )?;
+
+        let mut watched = get_watched_video_ids(&conn)?;
+        watched.sort();
+        assert_eq!(watched, ["history-video", VIDEO_ID]);
+
+        let position: Option<u64> = conn.query_row(
+            "SELECT position FROM video_state WHERE video_id = ?1",
+            [VIDEO_ID],
+            |row| row.get(0),
+        )?;
+        assert_eq!(position, Some(42));
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_keeps_the_newest_rows_and_can_be_repeated() -> Result<()> {
+        let directory = tempdir()?;
+        let database_path = directory.path().join("videos.db");
+        let other_path = directory.path().join("other.db");
+
+        let mut conn = open_db(&database_path)?;
+        conn.execute_batch(
+            "
+            INSERT INTO channels VALUES ('shared-channel', 'Old name', 100, 100);
+            INSERT INTO videos VALUES ('shared-video', 'shared-channel', 'New title', 0, 60, 0, false, 300);
+            INSERT INTO video_state VALUES ('shared-video', 1, NULL, 300);
+            INSERT INTO tags VALUES ('music');
+            ",
+        )?;
+
+        let other = open_db(&other_path)?;
+        other.execute_batch(
+            "
+            INSERT INTO channels VALUES ('shared-channel', 'New name', 200, 200);
+            INSERT INTO channels VALUES ('other-channel', 'Other', 200, 200);
+            INSERT INTO videos VALUES ('shared-video', 'shared-channel', 'Old title', 0, 60, 0, false, 100);
+            INSERT INTO videos VALUES ('other-video', 'other-channel', 'Other video', 0, 60, 0, false, 100);
+            INSERT INTO video_state VALUES ('shared-video', 0, 42, 400);
+            INSERT INTO tags VALUES ('music');
+            INSERT INTO tag_relations VALUES ('music', 'other-channel');
+            ",
+        )?;
+        drop(other);
+
+        let outcome = merge_database(&mut conn, &database_path, &other_path)?;
+        assert_eq!((outcome.new_channels, outcome.new_videos), (1, 1));
+        assert!(outcome.backup_path.exists());
+
+        let outcome = merge_database(&mut conn, &database_path, &other_path)?;
+        assert_eq!((outcome.new_channels, outcome.new_videos), (0, 0));
+
+        let channel_name: String = conn.query_row(
+            "SELECT channel_name FROM channels WHERE channel_id = 'shared-channel'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(channel_name, "New name");
+
+        let title: String = conn.query_row(
+            "SELECT title FROM videos WHERE video_id = 'shared-video'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(title, "New title");
+
+        let state = &get_video_states(&conn)?[0];
+        assert_eq!((state.watched, state.position), (false, Some(42)));
+
+        assert_eq!(
+            get_tag_relations(&conn)?,
+            [("music".to_owned(), "other-channel".to_owned())]
+        );
+        assert!(merge_database(&mut conn, &database_path, &database_path).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_after_a_delete_can_be_repeated() -> Result<()> {
         let directory = tempdir()?;
         let database_path = directory.path().join("videos.db");
+        let other_path = directory.path().join("other.db");
 
-        let conn = open_db(&database_path)?;
-        assert_eq!(user_version(&conn)?, LATEST_USER_VERSION);
-        assert!(completed_backups(directory.path())?.is_empty());
+        let mut conn = open_db(&database_path)?;
+        let other = open_db(&other_path)?;
+        for db in [&conn, &other] {
+            db.execute_batch(
+                "
+                INSERT INTO channels VALUES ('channel', 'Channel', 100, 100);
+                INSERT INTO videos VALUES ('video', 'channel', 'Video', 0, 60, 0, false, 100);
+                ",
+            )?;
+        }
+        drop(other);
 
-        let conn = open_db(&database_path)?;
-        assert_eq!(user_version(&conn)?, LATEST_USER_VERSION);
-        assert!(completed_backups(directory.path())?.is_empty());
+        trash_video(&mut conn, "video")?;
+        trash_channel(&mut conn, "channel")?;
+
+        let outcome = merge_database(&mut conn, &database_path, &other_path)?;
+        assert_eq!((outcome.new_channels, outcome.new_videos), (1, 1));
+        let outcome = merge_database(&mut conn, &database_path, &other_path)?;
+        assert_eq!((outcome.new_channels, outcome.new_videos), (0, 0));
+
+        assert!(get_dismissed_video_ids(&conn)?.contains("video"));
+        assert_eq!(get_trash(&conn)?.len(), 2);
 
         Ok(())
     }
@@ -1105,6 +2537,583 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn downloaded_files_are_listed_with_videos_until_downgrade() -> Result<()> {
+        let directory = tempdir()?;
+        let database_path = directory.path().join("videos.db");
+        let conn = open_db(&database_path)?;
+        conn.execute_batch(
+            "INSERT INTO channels (channel_id, channel_name) VALUES ('test-channel', 'Test');",
+        )?;
+        let videos = ["downloaded", "streamed"].map(|video_id| Video {
+            video_id: video_id.to_owned(),
+            title: video_id.to_owned(),
+            published: 0,
+            length: None,
+            members_only: false,
+        });
+        add_videos(&conn, "test-channel", &videos, ChannelTab::Videos)?;
+        add_download(&conn, "downloaded", Path::new("/videos/downloaded.webm"))?;
+
+        let local_files = |videos: Vec<VideoListItem>| {
+            videos
+                .into_iter()
+                .map(|video| (video.video.video_id, video.local_file))
+                .collect::<Vec<_>>()
+        };
+        let expected = [
+            (
+                "downloaded".to_owned(),
+                Some(PathBuf::from("/videos/downloaded.webm")),
+            ),
+            ("streamed".to_owned(), None),
+        ];
+        assert_eq!(
+            local_files(get_videos(
+                &conn,
+                "test-channel",
+                ChannelTab::Videos,
+                false,
+                &Filters::default(),
+            )?),
+            expected
+        );
+        assert_eq!(
+            local_files(get_latest_videos(
+                &conn,
+                &[],
+                ChannelTab::Videos,
+                false,
+                &Filters::default(),
+            )?),
+            expected
+        );
+        drop(conn);
+
+        assert_downgraded(
+            downgrade_database(&database_path, Some(11))?,
+            LATEST_USER_VERSION,
+            11,
+        );
+
+        let conn = Connection::open(&database_path)?;
+        assert!(!table_exists(&conn, "downloads")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn downgrade_to_ten_removes_subtitle_languages() -> Result<()> {
+        let directory = tempdir()?;
+        let database_path = directory.path().join("videos.db");
+        let conn = open_db(&database_path)?;
+        let mut settings = ChannelSettings::default();
+        settings.set(Setting::Speed, "2")?;
+        settings.set(Setting::SubtitleLanguage, "en")?;
+        set_channel_settings(&conn, "channel", &settings)?;
+        drop(conn);
+
+        assert_downgraded(
+            downgrade_database(&database_path, Some(10))?,
+            LATEST_USER_VERSION,
+            10,
+        );
+
+        let conn = Connection::open(&database_path)?;
+        assert!(
+            !table_columns(&conn, "channel_settings")?.contains(&"subtitle_language".to_owned())
+        );
+        assert_eq!(
+            conn.query_row("SELECT speed FROM channel_settings", [], |row| {
+                row.get::<_, f64>(0)
+            })?,
+            2.0
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn downgrade_to_four_removes_modification_times() -> Result<()> {
+        let directory = tempdir()?;
+        let database_path = directory.path().join("videos.db");
+        let conn = open_db(&database_path)?;
+        set_position(&conn, VIDEO_ID, 42)?;
+        drop(conn);
+
+        assert_downgraded(
+            downgrade_database(&database_path, Some(4))?,
+            LATEST_USER_VERSION,
+            4,
+        );
+
+        let conn = Connection::open(&database_path)?;
+        assert_eq!(user_version(&conn)?, 4);
+        assert_eq!(
+            table_columns(&conn, "video_state")?,
+            ["video_id", "watched", "position"]
+        );
+        assert!(!table_columns(&conn, "channels")?.contains(&"updated_at".to_owned()));
+        assert!(!table_columns(&conn, "videos")?.contains(&"updated_at".to_owned()));
+        assert!(!table_exists(&conn, "trash")?);
+        assert!(!table_exists(&conn, "dismissed_videos")?);
+        assert!(!table_exists(&conn, "filter_rules")?);
+        assert!(!table_exists(&conn, "channel_settings")?);
+        assert_eq!(
+            conn.query_row(
+                "SELECT position FROM video_state WHERE video_id = ?1",
+                [VIDEO_ID],
+                |row| row.get::<_, u64>(0),
+            )?,
+            42
+        );
+
+        Ok(())
+    }
+
+    fn remaining_videos(conn: &Connection) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare("SELECT video_id FROM videos ORDER BY published DESC")?;
+        let videos = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(videos)
+    }
+
+    #[test]
+    fn prune_respects_the_kept_videos() -> Result<()> {
+        let directory = tempdir()?;
+        let database_path = directory.path().join("videos.db");
+        let mut conn = open_db(&database_path)?;
+        conn.execute_batch(
+            "
+            INSERT INTO channels (channel_id, channel_name) VALUES ('test-channel', 'Test');
+            INSERT INTO videos (video_id, channel_id, title, published, length) VALUES
+                ('newest', 'test-channel', '', 500, 60),
+                ('new', 'test-channel', '', 400, 60),
+                ('watched', 'test-channel', '', 300, 60),
+                ('in-progress', 'test-channel', '', 200, 60),
+                ('old', 'test-channel', '', 100, 60);
+            INSERT INTO video_state (video_id, watched, position) VALUES
+                ('watched', 1, NULL),
+                ('in-progress', 0, 30),
+                ('old', 0, NULL);
+            ",
+        )?;
+
+        let outcome = prune_videos(
+            &mut conn,
+            &database_path,
+            &PruneOptions {
+                published_before: Some(450),
+                keep_per_channel: Some(1),
+                keep_watched: true,
+                keep_in_progress: true,
+            },
+        )?;
+        assert_eq!(outcome.deleted, 2);
+        assert!(outcome.backup_path.is_some());
+        assert_eq!(
+            remaining_videos(&conn)?,
+            ["newest", "watched", "in-progress"]
+        );
+        assert_eq!(check(&conn)?.detached_states, 1);
+
+        let outcome = prune_videos(
+            &mut conn,
+            &database_path,
+            &PruneOptions {
+                keep_per_channel: Some(2),
+                ..PruneOptions::default()
+            },
+        )?;
+        assert_eq!(outcome.deleted, 1);
+        assert!(outcome.backup_path.is_some());
+        assert_eq!(remaining_videos(&conn)?, ["newest", "watched"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pruned_videos_keep_their_state() -> Result<()> {
+        let directory = tempdir()?;
+        let database_path = directory.path().join("videos.db");
+        let mut conn = open_db(&database_path)?;
+        conn.execute(
+            "INSERT INTO channels (channel_id, channel_name) VALUES ('test-channel', 'Test')",
+            [],
+        )?;
+
+        let videos = [Video {
+            video_id: VIDEO_ID.to_owned(),
+            title: String::new(),
+            published: 100,
+            length: Some(60),
+            members_only: false,
+        }];
+        add_videos(&conn, "test-channel", &videos, ChannelTab::Videos)?;
+        mark_watched(&mut conn, &[VIDEO_ID.to_owned()])?;
+        add_download(&conn, VIDEO_ID, &directory.path().join("video.mp4"))?;
+
+        let outcome = prune_videos(
+            &mut conn,
+            &database_path,
+            &PruneOptions {
+                published_before: Some(200),
+                ..PruneOptions::default()
+            },
+        )?;
+        assert_eq!(outcome.deleted, 1);
+        assert!(remaining_videos(&conn)?.is_empty());
+        assert_eq!(
+            conn.query_row("SELECT COUNT(*) FROM downloads", [], |row| row
+                .get::<_, i64>(0))?,
+            0
+        );
+
+        add_videos(&conn, "test-channel", &videos, ChannelTab::Videos)?;
+        assert_eq!(get_watched_video_ids(&conn)?, [VIDEO_ID]);
+        assert_eq!(check(&conn)?.detached_states, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_reports_problems() -> Result<()> {
+        let directory = tempdir()?;
+        let conn = open_db(&directory.path().join("videos.db"))?;
+
+        set_position(&conn, VIDEO_ID, 42)?;
+        let report = check(&conn)?;
+        assert!(!report.has_errors());
+        assert_eq!(report.detached_states, 1);
+
+        conn.pragma_update(None, "foreign_keys", "off")?;
+        conn.execute(
+            "INSERT INTO videos (video_id, channel_id) VALUES (?1, 'missing-channel')",
+            [VIDEO_ID],
+        )?;
+        let report = check(&conn)?;
+        assert_eq!(report.foreign_key_violations.len(), 1);
+        assert_eq!(report.detached_states, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn trashed_items_can_be_restored() -> Result<()> {
+        let directory = tempdir()?;
+        let mut conn = open_db(&directory.path().join("videos.db"))?;
+        conn.execute_batch(
+            "
+            INSERT INTO channels (channel_id, channel_name) VALUES ('test-channel', 'Test');
+            INSERT INTO videos (video_id, channel_id, title, published, length) VALUES
+                ('first', 'test-channel', 'First', 200, 60),
+                ('second', 'test-channel', 'Second', 100, 60);
+            INSERT INTO tags (tag_name) VALUES ('music');
+            INSERT INTO tag_relations (tag_name, channel_id) VALUES ('music', 'test-channel');
+            ",
+        )?;
+
+        trash_video(&mut conn, "first")?;
+        assert_eq!(remaining_videos(&conn)?, ["second"]);
+
+        trash_tag(&mut conn, "music")?;
+        trash_channel(&mut conn, "test-channel")?;
+        assert!(remaining_videos(&conn)?.is_empty());
+        assert!(get_tag_relations(&conn)?.is_empty());
+
+        let trash = get_trash(&conn)?;
+        assert_eq!(
+            trash
+                .iter()
+                .map(|entry| (entry.kind, entry.name.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (TrashKind::Channel, "Test"),
+                (TrashKind::Tag, "music"),
+                (TrashKind::Video, "First"),
+            ]
+        );
+
+        // The video can't be restored without its channel.
+        assert!(restore_from_trash(&mut conn, trash[2].id).is_err());
+
+        restore_from_trash(&mut conn, trash[0].id)?;
+        assert_eq!(remaining_videos(&conn)?, ["second"]);
+        assert!(get_tag_relations(&conn)?.is_empty());
+
+        restore_from_trash(&mut conn, trash[1].id)?;
+        restore_from_trash(&mut conn, trash[2].id)?;
+        assert_eq!(remaining_videos(&conn)?, ["first", "second"]);
+        assert_eq!(
+            get_tag_relations(&conn)?,
+            [("music".to_owned(), "test-channel".to_owned())]
+        );
+        assert!(get_trash(&conn)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dismissed_videos_are_hidden_and_not_added_again() -> Result<()> {
+        let directory = tempdir()?;
+        let mut conn = open_db(&directory.path().join("videos.db"))?;
+        conn.execute_batch(
+            "INSERT INTO channels (channel_id, channel_name) VALUES ('test-channel', 'Test');",
+        )?;
+        let videos = ["dismissed", "deleted", "kept"].map(|video_id| Video {
+            video_id: video_id.to_owned(),
+            title: video_id.to_owned(),
+            published: 0,
+            length: None,
+            members_only: false,
+        });
+        add_videos(&conn, "test-channel", &videos, ChannelTab::Videos)?;
+
+        dismiss_video(&conn, "dismissed")?;
+        trash_video(&mut conn, "deleted")?;
+        add_videos(&conn, "test-channel", &videos, ChannelTab::Videos)?;
+
+        let shown = |conn: &Connection, show_dismissed: bool| -> Result<Vec<(String, bool)>> {
+            Ok(get_videos(
+                conn,
+                "test-channel",
+                ChannelTab::Videos,
+                show_dismissed,
+                &Filters::default(),
+            )?
+            .into_iter()
+            .map(|video| (video.video.video_id, video.dismissed))
+            .collect())
+        };
+        assert_eq!(shown(&conn, false)?, [("kept".to_owned(), false)]);
+        assert_eq!(
+            shown(&conn, true)?,
+            [("dismissed".to_owned(), true), ("kept".to_owned(), false)]
+        );
+        assert_eq!(
+            get_latest_videos(&conn, &[], ChannelTab::Videos, false, &Filters::default())?.len(),
+            1
+        );
+
+        undismiss_video(&conn, "dismissed")?;
+        let id = get_trash(&conn)?[0].id;
+        restore_from_trash(&mut conn, id)?;
+        assert!(get_dismissed_video_ids(&conn)?.is_empty());
+        assert_eq!(shown(&conn, false)?.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn filter_rules_mark_matching_videos() -> Result<()> {
+        let directory = tempdir()?;
+        let conn = open_db(&directory.path().join("videos.db"))?;
+        conn.execute_batch(
+            "
+            INSERT INTO channels (channel_id, channel_name) VALUES ('test-channel', 'Test');
+            INSERT INTO videos (video_id, channel_id, title, published, length, tab) VALUES
+                ('live', 'test-channel', 'LIVE: Rerun', 300, 3600, 0),
+                ('short', 'test-channel', 'Clip', 200, 30, 0),
+                ('video', 'test-channel', 'Video', 100, 600, 0);
+            INSERT INTO tags (tag_name) VALUES ('music');
+            INSERT INTO tag_relations (tag_name, channel_id) VALUES ('music', 'test-channel');
+            ",
+        )?;
+
+        create_filter_rule(&conn, &FilterScope::Global, &parse_rule("^LIVE")?.1)?;
+        let id = create_filter_rule(
+            &conn,
+            &FilterScope::Tag("music".to_owned()),
+            &parse_rule("tab:videos max:1:00")?.1,
+        )?;
+        rename_tag(&conn, "music", "songs")?;
+
+        let rules = get_filter_rules(&conn)?;
+        assert_eq!(
+            rules.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["[Global] ^LIVE", "[Tag songs] tab:videos max:1:00"]
+        );
+
+        let filtered = |conn: &Connection| -> Result<Vec<bool>> {
+            Ok(
+                get_latest_videos(conn, &[], ChannelTab::Videos, false, &get_filters(conn)?)?
+                    .into_iter()
+                    .map(|video| video.filtered)
+                    .collect(),
+            )
+        };
+        assert_eq!(filtered(&conn)?, [true, true, false]);
+
+        delete_filter_rule(&conn, id)?;
+        assert_eq!(filtered(&conn)?, [true, false, false]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn channel_stats_count_watched_videos_and_watch_time() -> Result<()> {
+        let directory = tempdir()?;
+        let conn = open_db(&directory.path().join("videos.db"))?;
+        conn.execute_batch(
+            "
+            INSERT INTO channels (channel_id, channel_name) VALUES ('a', 'A'), ('b', 'B');
+            INSERT INTO videos (video_id, channel_id, title, published, length, tab) VALUES
+                ('watched', 'a', 'Watched', 300, 600, 0),
+                ('started', 'a', 'Started', 200, 1200, 0),
+                ('old', 'a', 'Old', 50, 100, 0);
+            INSERT INTO video_state (video_id, watched, position, updated_at) VALUES
+                ('watched', 1, NULL, 400),
+                ('started', 0, 90, 500);
+            ",
+        )?;
+
+        let stats = get_channel_stats(&conn, 100)?;
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            (
+                stats[0].videos,
+                stats[0].watched,
+                stats[0].watch_time,
+                stats[0].last_watched,
+                stats[0].recent_uploads
+            ),
+            (3, 1, 690, Some(400), 2)
+        );
+        assert_eq!((stats[1].videos, stats[1].last_watched), (0, None));
+
+        Ok(())
+    }
+
+    #[test]
+    fn channel_settings_hide_tabs_from_latest_videos() -> Result<()> {
+        let directory = tempdir()?;
+        let conn = open_db(&directory.path().join("videos.db"))?;
+        conn.execute_batch(
+            "
+            INSERT INTO channels (channel_id, channel_name) VALUES
+                ('hidden', 'Hidden'), ('shown', 'Shown');
+            INSERT INTO videos (video_id, channel_id, title, published, tab) VALUES
+                ('hidden-short', 'hidden', 'Short', 300, 1),
+                ('shown-short', 'shown', 'Short', 200, 1),
+                ('hidden-video', 'hidden', 'Video', 100, 0);
+            ",
+        )?;
+
+        let latest = |conn: &Connection, tab| -> Result<Vec<String>> {
+            Ok(
+                get_latest_videos(conn, &[], tab, false, &Filters::default())?
+                    .into_iter()
+                    .map(|video| video.video_id.clone())
+                    .collect(),
+            )
+        };
+
+        let mut settings = ChannelSettings::default();
+        settings.set(Setting::HiddenTabs, "shorts")?;
+        settings.set(Setting::Speed, "1.25")?;
+        settings.set(Setting::SubtitleLanguage, "de")?;
+        set_channel_settings(&conn, "hidden", &settings)?;
+
+        assert_eq!(get_channel_settings(&conn, "hidden")?, settings);
+        assert_eq!(latest(&conn, ChannelTab::Shorts)?, ["shown-short"]);
+        assert_eq!(latest(&conn, ChannelTab::Videos)?, ["hidden-video"]);
+
+        set_channel_settings(&conn, "hidden", &ChannelSettings::default())?;
+        assert_eq!(
+            conn.query_row("SELECT COUNT(*) FROM channel_settings", [], |row| {
+                row.get::<_, u32>(0)
+            })?,
+            0
+        );
+        assert_eq!(
+            latest(&conn, ChannelTab::Shorts)?,
+            ["hidden-short", "shown-short"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn purge_removes_old_trash_entries() -> Result<()> {
+        let directory = tempdir()?;
+        let mut conn = open_db(&directory.path().join("videos.db"))?;
+        conn.execute_batch("INSERT INTO tags (tag_name) VALUES ('old'), ('new');")?;
+
+        trash_tag(&mut conn, "old")?;
+        conn.execute("UPDATE trash SET deleted_at = 100", [])?;
+        trash_tag(&mut conn, "new")?;
+
+        assert_eq!(purge_trash(&conn, 200)?, 1);
+        let trash = get_trash(&conn)?;
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].name, "new");
+
+        delete_from_trash(&conn, trash[0].id)?;
+        assert!(get_trash(&conn)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn backups_are_listed_oldest_first_and_pruned() -> Result<()> {
+        let directory = tempdir()?;
+        let database_path = directory.path().join("videos.db");
+        let conn = open_db(&database_path)?;
+
+        let first = backup_db(&conn, &database_path, 5)?;
+        let second = backup_db(&conn, &database_path, 6)?;
+        fs::write(directory.path().join("videos.db.schema-x.bak"), "")?;
+        fs::write(directory.path().join("other.db.schema-6.bak"), "")?;
+
+        let backups = list_backups(&database_path)?;
+        assert_eq!(
+            backups
+                .iter()
+                .map(|backup| (backup.path.clone(), backup.schema_version))
+                .collect::<Vec<_>>(),
+            [(first.clone(), 5), (second.clone(), 6)]
+        );
+        assert!(backups.iter().all(|backup| backup.created.is_some()));
+
+        assert_eq!(prune_backups(&database_path, 1)?, [first]);
+        assert_eq!(list_backups(&database_path)?.len(), 1);
+        assert!(second.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore_replaces_the_database_and_backs_it_up() -> Result<()> {
+        let directory = tempdir()?;
+        let database_path = directory.path().join("videos.db");
+        let conn = open_db(&database_path)?;
+        set_position(&conn, VIDEO_ID, 42)?;
+        let backup_path = backup_db(&conn, &database_path, LATEST_USER_VERSION)?;
+        set_position(&conn, VIDEO_ID, 84)?;
+        drop(conn);
+
+        let current_backup = restore_backup(&database_path, &backup_path)?.unwrap();
+
+        let position = |path: &Path| -> Result<u64> {
+            Ok(Connection::open(path)?.query_row(
+                "SELECT position FROM video_state WHERE video_id = ?1",
+                [VIDEO_ID],
+                |row| row.get(0),
+            )?)
+        };
+        assert_eq!(position(&database_path)?, 42);
+        assert_eq!(position(&current_backup)?, 84);
+
+        let invalid_path = directory.path().join("invalid.db");
+        Connection::open(&invalid_path)?.execute("CREATE TABLE test (id INTEGER)", [])?;
+        assert!(restore_backup(&database_path, &invalid_path).is_err());
+        assert_eq!(position(&database_path)?, 42);
+
+        Ok(())
+    }
+
     #[test]
     fn downgrade_four_to_three_preserves_watched_state_and_backup() -> Result<()> {
         let directory = tempdir()?;
@@ -1263,7 +3272,7 @@ mod tests {
         assert_downgraded(downgrade_database(&database_path, Some(1))?, 4, 1);
 
         let conn = open_db(&database_path)?;
-        assert_eq!(user_version(&conn)?, 4);
+        assert_eq!(user_version(&conn)?, LATEST_USER_VERSION);
         assert!(conn.query_row(
             "SELECT watched FROM video_state WHERE video_id = ?1",
             [VIDEO_ID],
@@ -1310,7 +3319,7 @@ mod tests {
         let future_database_path = directory.path().join("future.db");
         create_schema_four_database(&future_database_path)?;
         let conn = Connection::open(&future_database_path)?;
-        conn.pragma_update(None, "user_version", 5)?;
+        conn.pragma_update(None, "user_version", LATEST_USER_VERSION + 1)?;
         assert!(downgrade_database(&future_database_path, Some(3)).is_err());
 
         assert!(completed_backups(directory.path())?.is_empty());
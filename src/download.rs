@@ -0,0 +1,166 @@
+use crate::{
+    list::ListItem,
+    utils::{file_size, length_as_hhmmss},
+    video::VideoMetadata,
+};
+use std::{fmt::Display, path::PathBuf};
+
+/// Prefix of the progress lines printed by yt-dlp with the progress template.
+pub const PROGRESS_PREFIX: &str = "ytsub-progress ";
+/// Prefix of the line that yt-dlp prints with the path of the finished file.
+pub const FILE_PREFIX: &str = "ytsub-file ";
+
+/// The yt-dlp progress template that prints the fields parsed by
+/// [`DownloadProgress::parse`].
+pub fn progress_template() -> String {
+    format!(
+        "download:{PROGRESS_PREFIX}%(progress.downloaded_bytes)s %(progress.total_bytes)s \
+        %(progress.total_bytes_estimate)s %(progress.speed)s %(progress.eta)s"
+    )
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    /// Bytes per second.
+    pub speed: Option<f64>,
+    pub eta: Option<u64>,
+}
+
+impl DownloadProgress {
+    /// Parses a line printed with the progress template. yt-dlp prints `NA`
+    /// for the fields it doesn't know yet.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.strip_prefix(PROGRESS_PREFIX)?.split_whitespace();
+        let mut next = || {
+            fields
+                .next()
+                .and_then(|field| field.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+        };
+
+        let downloaded = next()? as u64;
+        let total = next();
+        let estimate = next();
+
+        Some(Self {
+            downloaded,
+            total: total.or(estimate).map(|total| total as u64),
+            speed: next(),
+            eta: next().map(|eta| eta as u64),
+        })
+    }
+
+    pub fn percent(&self) -> Option<f64> {
+        self.total
+            .filter(|total| *total > 0)
+            .map(|total| (self.downloaded as f64 / total as f64 * 100.0).min(100.0))
+    }
+}
+
+impl Display for DownloadProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.percent() {
+            Some(percent) => write!(f, "{percent:5.1}%")?,
+            None => write!(f, "{}", file_size(self.downloaded))?,
+        }
+
+        if let Some(speed) = self.speed {
+            write!(f, " {}/s", file_size(speed as u64))?;
+        }
+
+        if let Some(eta) = self.eta {
+            write!(f, " {}", length_as_hhmmss(eta as u32))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DownloadState {
+    Queued,
+    Downloading(DownloadProgress),
+    Finished(PathBuf),
+    Failed(String),
+    Cancelled,
+}
+
+impl DownloadState {
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Queued | Self::Downloading(_))
+    }
+
+    pub fn can_retry(&self) -> bool {
+        matches!(self, Self::Failed(_) | Self::Cancelled)
+    }
+}
+
+/// A video in the downloads window.
+pub struct Download {
+    pub metadata: VideoMetadata,
+    pub state: DownloadState,
+}
+
+impl Download {
+    pub fn new(metadata: VideoMetadata) -> Self {
+        Self {
+            metadata,
+            state: DownloadState::Queued,
+        }
+    }
+}
+
+impl ListItem for Download {
+    fn id(&self) -> &str {
+        &self.metadata.video_id
+    }
+}
+
+impl Display for Download {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match &self.state {
+            DownloadState::Queued => "Queued".to_owned(),
+            DownloadState::Downloading(progress) => progress.to_string(),
+            DownloadState::Finished(_) => "Finished".to_owned(),
+            DownloadState::Failed(_) => "Failed".to_owned(),
+            DownloadState::Cancelled => "Cancelled".to_owned(),
+        };
+
+        write!(
+            f,
+            "{status:<28} {} - {}",
+            self.metadata.channel, self.metadata.title
+        )?;
+
+        if let DownloadState::Failed(error) = &self.state {
+            write!(f, " ({error})")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DownloadProgress, progress_template};
+
+    #[test]
+    fn progress_lines_are_parsed() {
+        assert!(progress_template().starts_with("download:ytsub-progress "));
+
+        let progress =
+            DownloadProgress::parse("ytsub-progress 1048576 4194304 NA 524288.5 6").unwrap();
+        assert_eq!(progress.total, Some(4_194_304));
+        assert_eq!(progress.percent(), Some(25.0));
+        assert_eq!(progress.to_string(), " 25.0% 512.0 KiB/s 0:06");
+
+        let progress = DownloadProgress::parse("ytsub-progress 2048 NA 8192.0 NA NA").unwrap();
+        assert_eq!(progress.total, Some(8192));
+        assert_eq!(progress.speed, None);
+
+        assert!(DownloadProgress::parse("ytsub-progress NA NA NA NA NA").is_none());
+        assert!(DownloadProgress::parse("[download] 25.0% of 4.00MiB").is_none());
+    }
+}
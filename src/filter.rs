@@ -0,0 +1,248 @@
+use crate::{channel::ChannelTab, utils, video::Video};
+use anyhow::{Context, Result, ensure};
+use regex_lite::Regex;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FilterScope {
+    Global,
+    Channel {
+        channel_id: String,
+        channel_name: Option<String>,
+    },
+    Tag(String),
+}
+
+/// Conditions a video has to meet to be hidden by a rule. Unset conditions
+/// match every video.
+#[derive(Clone, Debug, Default)]
+pub struct FilterConditions {
+    pub pattern: Option<Regex>,
+    pub min_length: Option<u32>,
+    pub max_length: Option<u32>,
+    pub tab: Option<ChannelTab>,
+}
+
+impl FilterConditions {
+    pub fn matches(&self, video: &Video, tab: ChannelTab) -> bool {
+        self.tab.is_none_or(|rule_tab| rule_tab == tab)
+            && self
+                .min_length
+                .is_none_or(|min| video.length.is_some_and(|length| length >= min))
+            && self
+                .max_length
+                .is_none_or(|max| video.length.is_some_and(|length| length <= max))
+            && self
+                .pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(&video.title))
+    }
+}
+
+/// Parses rules such as `tab:shorts max:1:00 #shorts`. `tab:`, `min:` and
+/// `max:` words set the tab and length bounds and the rest of the text is the
+/// title pattern. A `tag:` word limits the rule to the channels of a tag.
+pub fn parse_rule(text: &str) -> Result<(Option<String>, FilterConditions)> {
+    let mut tag = None;
+    let mut conditions = FilterConditions::default();
+    let mut pattern = Vec::new();
+
+    for word in text.split_whitespace() {
+        if let Some(name) = word.strip_prefix("tag:") {
+            tag = Some(name.to_owned());
+        } else if let Some(tab) = word.strip_prefix("tab:") {
+            conditions.tab = Some(ChannelTab::try_from(tab)?);
+        } else if let Some(length) = word.strip_prefix("min:") {
+            conditions.min_length = Some(parse_length(length)?);
+        } else if let Some(length) = word.strip_prefix("max:") {
+            conditions.max_length = Some(parse_length(length)?);
+        } else {
+            pattern.push(word);
+        }
+    }
+
+    if !pattern.is_empty() {
+        conditions.pattern = Some(Regex::new(&pattern.join(" "))?);
+    }
+
+    ensure!(
+        conditions.pattern.is_some()
+            || conditions.min_length.is_some()
+            || conditions.max_length.is_some()
+            || conditions.tab.is_some(),
+        "a rule needs a title pattern, a length bound or a tab"
+    );
+
+    Ok((tag, conditions))
+}
+
+fn parse_length(length: &str) -> Result<u32> {
+    utils::length_as_seconds(length)
+        .with_context(|| format!("\"{length}\" is not a length in seconds or [hh:]mm:ss"))
+}
+
+impl Display for FilterConditions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut words = Vec::new();
+
+        if let Some(tab) = self.tab {
+            words.push(format!("tab:{tab}"));
+        }
+        if let Some(min) = self.min_length {
+            words.push(format!("min:{}", utils::length_as_hhmmss(min)));
+        }
+        if let Some(max) = self.max_length {
+            words.push(format!("max:{}", utils::length_as_hhmmss(max)));
+        }
+        if let Some(pattern) = &self.pattern {
+            words.push(pattern.as_str().to_owned());
+        }
+
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FilterRule {
+    pub id: i64,
+    pub scope: FilterScope,
+    pub conditions: FilterConditions,
+}
+
+impl FilterRule {
+    fn applies_to(&self, channel_id: &str, tags: Option<&HashSet<String>>) -> bool {
+        match &self.scope {
+            FilterScope::Global => true,
+            FilterScope::Channel {
+                channel_id: rule_channel,
+                ..
+            } => rule_channel == channel_id,
+            FilterScope::Tag(tag) => tags.is_some_and(|tags| tags.contains(tag)),
+        }
+    }
+}
+
+impl Display for FilterRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scope = match &self.scope {
+            FilterScope::Global => "Global".to_owned(),
+            FilterScope::Channel {
+                channel_id,
+                channel_name,
+            } => channel_name.as_ref().unwrap_or(channel_id).clone(),
+            FilterScope::Tag(tag) => format!("Tag {tag}"),
+        };
+
+        write!(f, "[{scope}] {}", self.conditions)
+    }
+}
+
+/// The filter rules and the tags of the channels they are applied to.
+#[derive(Default)]
+pub struct Filters {
+    rules: Vec<FilterRule>,
+    channel_tags: HashMap<String, HashSet<String>>,
+}
+
+impl Filters {
+    pub fn new(rules: Vec<FilterRule>, tag_relations: Vec<(String, String)>) -> Self {
+        let mut channel_tags: HashMap<String, HashSet<String>> = HashMap::new();
+
+        for (tag, channel_id) in tag_relations {
+            channel_tags.entry(channel_id).or_default().insert(tag);
+        }
+
+        Self {
+            rules,
+            channel_tags,
+        }
+    }
+
+    pub fn is_filtered(&self, video: &Video, channel_id: &str, tab: ChannelTab) -> bool {
+        let tags = self.channel_tags.get(channel_id);
+
+        self.rules
+            .iter()
+            .any(|rule| rule.applies_to(channel_id, tags) && rule.conditions.matches(video, tab))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FilterConditions, FilterRule, FilterScope, Filters, parse_rule};
+    use crate::{channel::ChannelTab, video::Video};
+    use anyhow::Result;
+
+    fn video(title: &str, length: Option<u32>) -> Video {
+        Video {
+            video_id: String::new(),
+            title: title.to_owned(),
+            published: 0,
+            length,
+            members_only: false,
+        }
+    }
+
+    fn rule(scope: FilterScope, conditions: FilterConditions) -> FilterRule {
+        FilterRule {
+            id: 0,
+            scope,
+            conditions,
+        }
+    }
+
+    #[test]
+    fn rules_are_parsed_and_displayed() -> Result<()> {
+        let (tag, conditions) = parse_rule("tag:music tab:shorts max:1:00 (?i) #shorts")?;
+        assert_eq!(tag.as_deref(), Some("music"));
+        assert_eq!(conditions.to_string(), "tab:shorts max:1:00 (?i) #shorts");
+
+        let (tag, conditions) = parse_rule("min:90")?;
+        assert_eq!(tag, None);
+        assert_eq!(conditions.min_length, Some(90));
+
+        assert!(parse_rule("tag:music").is_err());
+        assert!(parse_rule("tab:clips").is_err());
+        assert!(parse_rule("max:1m").is_err());
+        assert!(parse_rule("[unclosed").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rules_apply_to_their_scope() -> Result<()> {
+        let filters = Filters::new(
+            vec![
+                rule(FilterScope::Global, parse_rule("(?i)^live:")?.1),
+                rule(
+                    FilterScope::Channel {
+                        channel_id: "channel".to_owned(),
+                        channel_name: None,
+                    },
+                    parse_rule("max:60")?.1,
+                ),
+                rule(
+                    FilterScope::Tag("music".to_owned()),
+                    parse_rule("tab:streams")?.1,
+                ),
+            ],
+            vec![("music".to_owned(), "tagged".to_owned())],
+        );
+
+        let live = video("LIVE: Rerun", Some(3600));
+        let short = video("Short", Some(30));
+        let unknown_length = video("Premiere", None);
+
+        assert!(filters.is_filtered(&live, "other", ChannelTab::Videos));
+        assert!(filters.is_filtered(&short, "channel", ChannelTab::Videos));
+        assert!(!filters.is_filtered(&short, "other", ChannelTab::Videos));
+        assert!(!filters.is_filtered(&unknown_length, "channel", ChannelTab::Videos));
+        assert!(filters.is_filtered(&unknown_length, "tagged", ChannelTab::Streams));
+        assert!(!filters.is_filtered(&unknown_length, "tagged", ChannelTab::Videos));
+
+        Ok(())
+    }
+}
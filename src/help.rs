@@ -4,7 +4,7 @@ use ratatui::layout::Rect;
 use std::ops::{Deref, DerefMut};
 use unicode_width::UnicodeWidthStr;
 
-const DESCRIPTIONS_LEN: usize = 52;
+const DESCRIPTIONS_LEN: usize = 71;
 const DESCRIPTIONS: [&str; DESCRIPTIONS_LEN] = [
     "Switch to subscriptions mode",
     "Switch to latest videos mode",
@@ -22,9 +22,12 @@ const DESCRIPTIONS: [&str; DESCRIPTIONS_LEN] = [
     "Select previous tab",
     "Jump to the channel of the selected video from latest videos mode",
     "Hide/unhide watched videos",
+    "Show/hide dismissed videos",
     "Subscribe",
     "Unsubscribe",
-    "Delete the selected video from database",
+    "Move the selected video to the trash",
+    "Dismiss the selected video",
+    "Undismiss the selected video",
     "Search Forward",
     "Search backward",
     "Repeat last search",
@@ -51,12 +54,28 @@ const DESCRIPTIONS: [&str; DESCRIPTIONS_LEN] = [
     "Increase volume by 2%",
     "Decrease volume by 2%",
     "Mute/unmute playback",
+    "Increase playback speed by 0.25",
+    "Decrease playback speed by 0.25",
+    "Reset playback speed",
+    "Seek to the next chapter",
+    "Seek to the previous chapter",
+    "Toggle chapter selection window",
+    "Toggle audio and subtitle track selection window",
     "Stop playback",
     "Release control of the current video",
+    "Cycle the sleep timer: 15, 30 or 60 minutes, end of video or off",
     "Toggle format selection window",
     "Mark/unmark video as watched",
     "Toggle help window",
     "Toggle tag selection window",
+    "Restore the most recently deleted channel, video or tag",
+    "Toggle trash window",
+    "Toggle filter rules window",
+    "Show/hide videos hidden by filter rules",
+    "Toggle channel settings window",
+    "Toggle statistics window",
+    "Download the selected video",
+    "Toggle downloads window",
     "Quit application",
 ];
 
@@ -80,6 +99,41 @@ const TAG_DESCRIPTIONS: [&str; TAG_DESCRIPTIONS_LEN] = [
     " - Abort",
 ];
 
+const TRASH_DESCRIPTIONS_LEN: usize = 3;
+const TRASH_DESCRIPTIONS: [&str; TRASH_DESCRIPTIONS_LEN] =
+    [" - Restore,", " - Delete permanently,", " - Abort"];
+
+const FILTER_DESCRIPTIONS_LEN: usize = 4;
+const FILTER_DESCRIPTIONS: [&str; FILTER_DESCRIPTIONS_LEN] = [
+    " - Create rule,",
+    " - Create rule for the channel,",
+    " - Delete rule,",
+    " - Abort",
+];
+
+const CHANNEL_SETTINGS_DESCRIPTIONS_LEN: usize = 3;
+const CHANNEL_SETTINGS_DESCRIPTIONS: [&str; CHANNEL_SETTINGS_DESCRIPTIONS_LEN] =
+    [" - Edit,", " - Reset to default,", " - Abort"];
+
+const STATS_DESCRIPTIONS_LEN: usize = 1;
+const STATS_DESCRIPTIONS: [&str; STATS_DESCRIPTIONS_LEN] = [" - Abort"];
+
+const CHAPTER_SELECTION_DESCRIPTIONS_LEN: usize = 2;
+const CHAPTER_SELECTION_DESCRIPTIONS: [&str; CHAPTER_SELECTION_DESCRIPTIONS_LEN] =
+    [" - Seek to chapter,", " - Abort"];
+
+const TRACK_SELECTION_DESCRIPTIONS_LEN: usize = 4;
+const TRACK_SELECTION_DESCRIPTIONS: [&str; TRACK_SELECTION_DESCRIPTIONS_LEN] = [
+    " - Previous tab,",
+    " - Next tab,",
+    " - Switch to track,",
+    " - Abort",
+];
+
+const DOWNLOADS_DESCRIPTIONS_LEN: usize = 3;
+const DOWNLOADS_DESCRIPTIONS: [&str; DOWNLOADS_DESCRIPTIONS_LEN] =
+    [" - Cancel,", " - Retry,", " - Abort"];
+
 const CHANNEL_SELECTION_DESCRIPTIONS_LEN: usize = 5;
 const CHANNEL_SELECTION_DESCRIPTIONS: [&str; CHANNEL_SELECTION_DESCRIPTIONS_LEN] = [
     " - Confirm,",
@@ -89,13 +143,14 @@ const CHANNEL_SELECTION_DESCRIPTIONS: [&str; CHANNEL_SELECTION_DESCRIPTIONS_LEN]
     " - Deselect all",
 ];
 
-const FORMAT_SELECTION_DESCRIPTIONS_LEN: usize = 6;
+const FORMAT_SELECTION_DESCRIPTIONS_LEN: usize = 7;
 const FORMAT_SELECTION_DESCRIPTIONS: [&str; FORMAT_SELECTION_DESCRIPTIONS_LEN] = [
     " - Previous tab,",
     " - Next tab,",
     " - Switch format,",
     " - Select,",
     " - Play video,",
+    " - Download,",
     " - Abort",
 ];
 
@@ -149,6 +204,13 @@ pub struct Help<'a> {
     pub general: [(String, &'a str); DESCRIPTIONS_LEN],
     pub import: [(String, &'a str); IMPORT_DESCRIPTIONS_LEN],
     pub tag: [(String, &'a str); TAG_DESCRIPTIONS_LEN],
+    pub trash: [(String, &'a str); TRASH_DESCRIPTIONS_LEN],
+    pub filter: [(String, &'a str); FILTER_DESCRIPTIONS_LEN],
+    pub channel_settings: [(String, &'a str); CHANNEL_SETTINGS_DESCRIPTIONS_LEN],
+    pub stats: [(String, &'a str); STATS_DESCRIPTIONS_LEN],
+    pub chapter_selection: [(String, &'a str); CHAPTER_SELECTION_DESCRIPTIONS_LEN],
+    pub track_selection: [(String, &'a str); TRACK_SELECTION_DESCRIPTIONS_LEN],
+    pub downloads: [(String, &'a str); DOWNLOADS_DESCRIPTIONS_LEN],
     pub channel_selection: [(String, &'a str); CHANNEL_SELECTION_DESCRIPTIONS_LEN],
     pub format_selection: [(String, &'a str); FORMAT_SELECTION_DESCRIPTIONS_LEN],
 }
@@ -165,6 +227,13 @@ impl Help<'_> {
             general: [HELP_ENTRY; DESCRIPTIONS_LEN],
             import: [HELP_ENTRY; IMPORT_DESCRIPTIONS_LEN],
             tag: [HELP_ENTRY; TAG_DESCRIPTIONS_LEN],
+            trash: [HELP_ENTRY; TRASH_DESCRIPTIONS_LEN],
+            filter: [HELP_ENTRY; FILTER_DESCRIPTIONS_LEN],
+            channel_settings: [HELP_ENTRY; CHANNEL_SETTINGS_DESCRIPTIONS_LEN],
+            stats: [HELP_ENTRY; STATS_DESCRIPTIONS_LEN],
+            chapter_selection: [HELP_ENTRY; CHAPTER_SELECTION_DESCRIPTIONS_LEN],
+            track_selection: [HELP_ENTRY; TRACK_SELECTION_DESCRIPTIONS_LEN],
+            downloads: [HELP_ENTRY; DOWNLOADS_DESCRIPTIONS_LEN],
             channel_selection: [HELP_ENTRY; CHANNEL_SELECTION_DESCRIPTIONS_LEN],
             format_selection: [HELP_ENTRY; FORMAT_SELECTION_DESCRIPTIONS_LEN],
         };
@@ -192,6 +261,29 @@ impl Help<'_> {
         generate_entries!(help.general, KEY_BINDINGS.general, DESCRIPTIONS);
         generate_entries!(help.import, KEY_BINDINGS.import, IMPORT_DESCRIPTIONS);
         generate_entries!(help.tag, KEY_BINDINGS.tag, TAG_DESCRIPTIONS);
+        generate_entries!(help.trash, KEY_BINDINGS.trash, TRASH_DESCRIPTIONS);
+        generate_entries!(help.filter, KEY_BINDINGS.filter, FILTER_DESCRIPTIONS);
+        generate_entries!(
+            help.channel_settings,
+            KEY_BINDINGS.channel_settings,
+            CHANNEL_SETTINGS_DESCRIPTIONS
+        );
+        generate_entries!(help.stats, KEY_BINDINGS.stats, STATS_DESCRIPTIONS);
+        generate_entries!(
+            help.chapter_selection,
+            KEY_BINDINGS.chapter_selection,
+            CHAPTER_SELECTION_DESCRIPTIONS
+        );
+        generate_entries!(
+            help.track_selection,
+            KEY_BINDINGS.track_selection,
+            TRACK_SELECTION_DESCRIPTIONS
+        );
+        generate_entries!(
+            help.downloads,
+            KEY_BINDINGS.downloads,
+            DOWNLOADS_DESCRIPTIONS
+        );
         generate_entries!(
             help.channel_selection,
             KEY_BINDINGS.channel_selection,
@@ -0,0 +1,195 @@
+use anyhow::{Context, Result, bail};
+use regex_lite::Regex;
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, BufReader},
+    path::Path,
+    sync::LazyLock,
+};
+use url::Url;
+
+const NEWPIPE_DATABASE: &str = "newpipe.db";
+const YOUTUBE_SERVICE_ID: u32 = 0;
+
+#[derive(Deserialize)]
+struct TakeoutEntry {
+    #[serde(rename = "titleUrl")]
+    title_url: Option<String>,
+}
+
+/// Reads the ids of the watched videos from a Google Takeout watch history
+/// (`.json` or `.html`) or a NewPipe backup (`.zip`). Each id is returned once.
+pub fn read_watched_videos(path: &Path) -> Result<Vec<String>> {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+
+    let video_ids = match extension.as_deref() {
+        Some("json") => read_takeout_json(BufReader::new(File::open(path)?))?,
+        Some("html") => read_takeout_html(&fs::read_to_string(path)?),
+        Some("zip") => read_newpipe_backup(path)?,
+        _ => bail!("Unsupported history file. Expected a .json, .html or .zip file"),
+    };
+
+    let mut seen = HashSet::new();
+
+    Ok(video_ids
+        .into_iter()
+        .filter(|video_id| seen.insert(video_id.clone()))
+        .collect())
+}
+
+fn read_takeout_json<R: io::Read>(rdr: R) -> Result<Vec<String>> {
+    let entries: Vec<TakeoutEntry> = serde_json::from_reader(rdr)?;
+
+    Ok(entries
+        .iter()
+        .filter_map(|entry| entry.title_url.as_deref())
+        .filter_map(video_id_from_url)
+        .collect())
+}
+
+fn read_takeout_html(html: &str) -> Vec<String> {
+    static RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"href="(https://(?:www|music)\.youtube\.com/watch\?v=[^"]+)""#).unwrap()
+    });
+
+    RE.captures_iter(html)
+        .filter_map(|captures| video_id_from_url(&captures[1].replace("&amp;", "&")))
+        .collect()
+}
+
+fn read_newpipe_backup(path: &Path) -> Result<Vec<String>> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    let mut database = archive
+        .by_name(NEWPIPE_DATABASE)
+        .with_context(|| format!("{NEWPIPE_DATABASE} not found in the NewPipe backup"))?;
+
+    // The file is created with a random name that only the user can access,
+    // and removed when it is dropped.
+    let mut extracted = tempfile::Builder::new()
+        .prefix("ytsub-newpipe-")
+        .suffix(".db")
+        .tempfile()?;
+    io::copy(&mut database, extracted.as_file_mut())?;
+
+    read_newpipe_database(extracted.path())
+}
+
+fn read_newpipe_database(path: &Path) -> Result<Vec<String>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt = conn.prepare(
+        "SELECT streams.url FROM stream_history
+        JOIN streams ON streams.uid = stream_history.stream_id
+        WHERE streams.service_id = ?1
+        ORDER BY stream_history.access_date DESC",
+    )?;
+
+    let mut video_ids = Vec::new();
+
+    for url in stmt.query_map([YOUTUBE_SERVICE_ID], |row| row.get::<_, String>(0))? {
+        if let Some(video_id) = video_id_from_url(&url?) {
+            video_ids.push(video_id);
+        }
+    }
+
+    Ok(video_ids)
+}
+
+fn video_id_from_url(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+
+    if url.host_str() == Some("youtu.be") {
+        return url
+            .path_segments()?
+            .next()
+            .filter(|id| !id.is_empty())
+            .map(ToOwned::to_owned);
+    }
+
+    url.query_pairs()
+        .find(|(key, _)| key == "v")
+        .map(|(_, id)| id.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_takeout_html, read_takeout_json, read_watched_videos, video_id_from_url};
+    use rusqlite::Connection;
+    use std::{fs::File, io::Write};
+    use tempfile::tempdir;
+
+    #[test]
+    fn takeout_json() {
+        let json = serde_json::json!([
+            {
+                "header": "YouTube",
+                "title": "Watched Some video",
+                "titleUrl": "https://www.youtube.com/watch?v\u{003d}dQw4w9WgXcQ",
+                "time": "2026-05-27T12:00:00.000Z"
+            },
+            {
+                "header": "YouTube",
+                "title": "Watched a video that has been removed",
+                "time": "2026-05-26T12:00:00.000Z"
+            }
+        ]);
+
+        let video_ids = read_takeout_json(json.to_string().as_bytes()).unwrap();
+
+        assert_eq!(video_ids, ["dQw4w9WgXcQ"]);
+    }
+
+    #[test]
+    fn takeout_html() {
+        let html = r#"<div class="content-cell">Watched&nbsp;<a href="https://www.youtube.com/watch?v=dQw4w9WgXcQ">Some video</a><br><a href="https://www.youtube.com/channel/UCuAXFkgsw1L7xaCfnd5JJOw">Channel</a><br>May 27, 2026</div>
+<div class="content-cell">Watched&nbsp;<a href="https://www.youtube.com/watch?v=jNQXAC9IVRw&amp;t=5s">Another video</a></div>"#;
+
+        assert_eq!(read_takeout_html(html), ["dQw4w9WgXcQ", "jNQXAC9IVRw"]);
+    }
+
+    #[test]
+    fn video_urls() {
+        assert_eq!(
+            video_id_from_url("https://youtu.be/dQw4w9WgXcQ").as_deref(),
+            Some("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            video_id_from_url("https://www.youtube.com/channel/UCuAXFkgsw1L7xaCfnd5JJOw"),
+            None
+        );
+    }
+
+    #[test]
+    fn newpipe_backup() {
+        let dir = tempdir().unwrap();
+        let database_path = dir.path().join("newpipe.db");
+        let backup_path = dir.path().join("NewPipeData.zip");
+
+        let conn = Connection::open(&database_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE streams (uid INTEGER PRIMARY KEY, service_id INTEGER, url TEXT);
+            CREATE TABLE stream_history (stream_id INTEGER, access_date INTEGER, repeat_count INTEGER);
+            INSERT INTO streams VALUES (1, 0, 'https://www.youtube.com/watch?v=dQw4w9WgXcQ');
+            INSERT INTO streams VALUES (2, 1, 'https://soundcloud.com/artist/track');
+            INSERT INTO streams VALUES (3, 0, 'https://www.youtube.com/watch?v=jNQXAC9IVRw');
+            INSERT INTO stream_history VALUES (1, 100, 1);
+            INSERT INTO stream_history VALUES (2, 200, 1);
+            INSERT INTO stream_history VALUES (1, 300, 2);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let mut zip = zip::ZipWriter::new(File::create(&backup_path).unwrap());
+        zip.start_file("newpipe.db", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&std::fs::read(&database_path).unwrap())
+            .unwrap();
+        zip.finish().unwrap();
+
+        assert_eq!(read_watched_videos(&backup_path).unwrap(), ["dQw4w9WgXcQ"]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::CONFIG;
+use crate::{CONFIG, cookies};
 use anyhow::{Context, Result, ensure};
 use reqwest::{Client, NoProxy, Proxy, Url};
 use std::time::Duration;
@@ -26,5 +26,25 @@ pub fn client() -> Result<Client> {
         builder = builder.proxy(proxy);
     }
 
+    if let Some(cookies) = cookies::get()? {
+        builder = builder.cookie_provider(cookies.jar.clone());
+    }
+
     builder.build().context("failed to build HTTP client")
 }
+
+pub async fn is_online() -> bool {
+    const PROBE_URL: &str = "https://www.youtube.com/generate_204";
+    const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+    let Ok(client) = client() else {
+        return false;
+    };
+
+    client
+        .head(PROBE_URL)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .is_ok()
+}
@@ -7,20 +7,29 @@ use serde::{Deserialize, Serialize};
 use std::{
     fmt::Display,
     fs::File,
-    io::{self, BufReader},
+    io::{self, BufRead, BufReader, Write},
     path::Path,
 };
+use url::Url;
 
 #[derive(Clone, Copy)]
 pub enum Format {
     YoutubeCsv,
     NewPipe,
+    Opml,
+    FreeTube,
+    LibreTube,
+    Invidious,
 }
 
 impl From<&str> for Format {
     fn from(format: &str) -> Self {
         match format {
             "newpipe" => Self::NewPipe,
+            "opml" => Self::Opml,
+            "freetube" => Self::FreeTube,
+            "libretube" => Self::LibreTube,
+            "invidious" => Self::Invidious,
             _ => Self::YoutubeCsv,
         }
     }
@@ -29,6 +38,10 @@ impl From<&str> for Format {
 pub trait Import {
     fn channel_id(&self) -> String;
     fn channel_title(&self) -> String;
+
+    fn tags(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 #[derive(Deserialize, Serialize)]
@@ -163,10 +176,530 @@ impl Import for NewPipeInner {
     }
 }
 
+/// Title of the folder that Invidious and other clients wrap all the
+/// subscriptions in. It isn't treated as a tag.
+const OPML_ROOT_FOLDER: &str = "YouTube Subscriptions";
+
+#[derive(Deserialize, Serialize, Default)]
+struct OpmlOutline {
+    #[serde(rename = "@text", default)]
+    text: String,
+    #[serde(rename = "@title", skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(rename = "@type", skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    #[serde(rename = "@xmlUrl", skip_serializing_if = "Option::is_none")]
+    xml_url: Option<String>,
+    #[serde(rename = "@htmlUrl", skip_serializing_if = "Option::is_none")]
+    html_url: Option<String>,
+    #[serde(rename = "outline", default, skip_serializing_if = "Vec::is_empty")]
+    outlines: Vec<Self>,
+}
+
+impl OpmlOutline {
+    fn feed(channel: &Channel) -> Self {
+        Self {
+            text: channel.channel_name.clone(),
+            title: Some(channel.channel_name.clone()),
+            kind: Some(String::from("rss")),
+            xml_url: Some(format!(
+                "https://www.youtube.com/feeds/videos.xml?channel_id={}",
+                channel.channel_id
+            )),
+            html_url: Some(format!(
+                "https://www.youtube.com/channel/{}",
+                channel.channel_id
+            )),
+            outlines: Vec::new(),
+        }
+    }
+
+    fn folder(name: &str, outlines: Vec<Self>) -> Self {
+        Self {
+            text: name.to_owned(),
+            title: Some(name.to_owned()),
+            outlines,
+            ..Default::default()
+        }
+    }
+
+    fn name(&self) -> String {
+        self.title.clone().unwrap_or_else(|| self.text.clone())
+    }
+
+    fn collect_feeds(self, folder: Option<&str>, entries: &mut Vec<TaggedChannel>) {
+        let channel_id = self
+            .xml_url
+            .as_deref()
+            .or(self.html_url.as_deref())
+            .and_then(channel_id_from_url);
+
+        if let Some(channel_id) = channel_id {
+            TaggedChannel::add(entries, channel_id, self.name(), folder);
+        } else if self.xml_url.is_none() {
+            let name = self.name();
+            let folder = (name != OPML_ROOT_FOLDER)
+                .then_some(name.as_str())
+                .or(folder);
+
+            for outline in self.outlines {
+                outline.collect_feeds(folder, entries);
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default)]
+struct OpmlHead {
+    #[serde(default)]
+    title: String,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+struct OpmlBody {
+    #[serde(rename = "outline", default)]
+    outlines: Vec<OpmlOutline>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "opml")]
+pub struct Opml {
+    #[serde(rename = "@version")]
+    version: String,
+    #[serde(default)]
+    head: OpmlHead,
+    body: OpmlBody,
+}
+
+impl Opml {
+    pub fn import(path: &Path) -> Result<Vec<ImportItem>> {
+        let file = File::open(path)?;
+        Self::read_subscriptions(file)
+    }
+
+    fn read_subscriptions<R: io::Read>(rdr: R) -> Result<Vec<ImportItem>> {
+        let opml: Self = quick_xml::de::from_reader(BufReader::new(rdr))?;
+        let mut entries = Vec::new();
+
+        for outline in opml.body.outlines {
+            outline.collect_feeds(None, &mut entries);
+        }
+
+        Ok(entries.into_iter().map(ImportItem::from).collect())
+    }
+
+    /// Writes the channels under a folder for every tag they belong to. Channels
+    /// without tags are written at the top level.
+    pub fn export(
+        channels: &[Channel],
+        tag_relations: &[(String, String)],
+        path: &Path,
+    ) -> Result<()> {
+        let (tags, untagged) = group_by_tag(channels, tag_relations);
+
+        let body = tags
+            .into_iter()
+            .map(|(tag, channels)| {
+                OpmlOutline::folder(tag, channels.into_iter().map(OpmlOutline::feed).collect())
+            })
+            .chain(untagged.into_iter().map(OpmlOutline::feed))
+            .collect();
+
+        let opml = Self {
+            version: String::from("1.1"),
+            head: OpmlHead {
+                title: String::from("ytsub subscriptions"),
+            },
+            body: OpmlBody { outlines: body },
+        };
+
+        let mut buffer = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let mut serializer = quick_xml::se::Serializer::new(&mut buffer);
+        serializer.indent(' ', 2);
+        opml.serialize(serializer)?;
+        buffer.push('\n');
+
+        File::create(path)?.write_all(buffer.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// Id of the FreeTube profile that every subscription belongs to.
+const FREETUBE_ALL_CHANNELS: &str = "allChannels";
+
+#[derive(Deserialize, Serialize)]
+struct FreeTubeSubscription {
+    id: String,
+    name: String,
+    #[serde(default)]
+    thumbnail: String,
+}
+
+impl FreeTubeSubscription {
+    fn new(channel: &Channel) -> Self {
+        Self {
+            id: channel.channel_id.clone(),
+            name: channel.channel_name.clone(),
+            thumbnail: String::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FreeTubeProfile {
+    name: String,
+    bg_color: String,
+    text_color: String,
+    subscriptions: Vec<FreeTubeSubscription>,
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+}
+
+impl FreeTubeProfile {
+    fn new(name: &str, id: Option<&str>, channels: &[&Channel]) -> Self {
+        Self {
+            name: name.to_owned(),
+            bg_color: String::from("#000000"),
+            text_color: String::from("#FFFFFF"),
+            subscriptions: channels
+                .iter()
+                .map(|channel| FreeTubeSubscription::new(channel))
+                .collect(),
+            id: id.map(ToOwned::to_owned),
+        }
+    }
+}
+
+/// FreeTube profiles file, which stores a JSON object for every profile on a
+/// separate line. Profiles other than "All Channels" are mapped to tags.
+pub struct FreeTube;
+
+impl FreeTube {
+    pub fn import(path: &Path) -> Result<Vec<ImportItem>> {
+        let file = File::open(path)?;
+        Self::read_subscriptions(file)
+    }
+
+    fn read_subscriptions<R: io::Read>(rdr: R) -> Result<Vec<ImportItem>> {
+        let mut entries = Vec::new();
+
+        for line in BufReader::new(rdr).lines() {
+            let line = line?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let profile: FreeTubeProfile = serde_json::from_str(&line)?;
+            let tag = (profile.id.as_deref() != Some(FREETUBE_ALL_CHANNELS))
+                .then_some(profile.name.as_str());
+
+            for subscription in profile.subscriptions {
+                TaggedChannel::add(&mut entries, subscription.id, subscription.name, tag);
+            }
+        }
+
+        Ok(entries.into_iter().map(ImportItem::from).collect())
+    }
+
+    pub fn export(
+        channels: &[Channel],
+        tag_relations: &[(String, String)],
+        path: &Path,
+    ) -> Result<()> {
+        let mut file = File::create(path)?;
+        let all_channels = channels.iter().collect::<Vec<&Channel>>();
+        let (tags, _) = group_by_tag(channels, tag_relations);
+
+        let profiles = std::iter::once(FreeTubeProfile::new(
+            "All Channels",
+            Some(FREETUBE_ALL_CHANNELS),
+            &all_channels,
+        ))
+        .chain(
+            tags.iter()
+                .map(|(tag, channels)| FreeTubeProfile::new(tag, None, channels)),
+        );
+
+        for profile in profiles {
+            serde_json::to_writer(&mut file, &profile)?;
+            file.write_all(b"\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LibreTubeSubscription {
+    channel_id: String,
+    url: String,
+    name: String,
+    avatar: Option<String>,
+    #[serde(default)]
+    verified: bool,
+}
+
+impl LibreTubeSubscription {
+    fn new(channel: &Channel) -> Self {
+        Self {
+            channel_id: channel.channel_id.clone(),
+            url: format!("/channel/{}", channel.channel_id),
+            name: channel.channel_name.clone(),
+            avatar: None,
+            verified: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct LibreTubeGroup {
+    name: String,
+    channels: Vec<String>,
+    #[serde(default)]
+    index: usize,
+}
+
+/// LibreTube backup file. Only the subscriptions and channel groups are read
+/// and written. Channel groups are mapped to tags.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibreTube {
+    #[serde(default)]
+    local_subscriptions: Vec<LibreTubeSubscription>,
+    #[serde(default)]
+    channel_groups: Vec<LibreTubeGroup>,
+}
+
+impl LibreTube {
+    pub fn import(path: &Path) -> Result<Vec<ImportItem>> {
+        let file = File::open(path)?;
+        Self::read_subscriptions(file)
+    }
+
+    fn read_subscriptions<R: io::Read>(rdr: R) -> Result<Vec<ImportItem>> {
+        let backup: Self = serde_json::from_reader(BufReader::new(rdr))?;
+        let mut entries = Vec::new();
+
+        for subscription in backup.local_subscriptions {
+            TaggedChannel::add(
+                &mut entries,
+                subscription.channel_id,
+                subscription.name,
+                None,
+            );
+        }
+
+        for group in backup.channel_groups {
+            for channel_id in group.channels {
+                if let Some(entry) = entries
+                    .iter_mut()
+                    .find(|entry| entry.channel_id == channel_id)
+                {
+                    entry.tags.push(group.name.clone());
+                }
+            }
+        }
+
+        Ok(entries.into_iter().map(ImportItem::from).collect())
+    }
+
+    pub fn export(
+        channels: &[Channel],
+        tag_relations: &[(String, String)],
+        path: &Path,
+    ) -> Result<()> {
+        let file = File::create(path)?;
+        let (tags, _) = group_by_tag(channels, tag_relations);
+
+        let backup = Self {
+            local_subscriptions: channels.iter().map(LibreTubeSubscription::new).collect(),
+            channel_groups: tags
+                .into_iter()
+                .enumerate()
+                .map(|(index, (tag, channels))| LibreTubeGroup {
+                    name: tag.to_owned(),
+                    channels: channels
+                        .iter()
+                        .map(|channel| channel.channel_id.clone())
+                        .collect(),
+                    index,
+                })
+                .collect(),
+        };
+
+        Ok(serde_json::to_writer(file, &backup)?)
+    }
+}
+
+struct InvidiousSubscription(String);
+
+impl Import for InvidiousSubscription {
+    fn channel_id(&self) -> String {
+        self.0.clone()
+    }
+
+    // the export only contains channel ids. The title is filled in from the
+    // first fetch after subscribing.
+    fn channel_title(&self) -> String {
+        String::new()
+    }
+}
+
+/// Data exported from an Invidious account. Preferences aren't used.
+#[derive(Deserialize, Serialize)]
+pub struct Invidious {
+    #[serde(default)]
+    subscriptions: Vec<String>,
+    #[serde(default)]
+    watch_history: Vec<String>,
+    #[serde(default)]
+    playlists: Vec<serde_json::Value>,
+}
+
+impl Invidious {
+    fn read(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    pub fn import(path: &Path) -> Result<Vec<ImportItem>> {
+        Ok(Self::read(path)?.into_import_items())
+    }
+
+    pub fn import_watch_history(path: &Path) -> Result<Vec<String>> {
+        Ok(Self::read(path)?.watch_history)
+    }
+
+    fn into_import_items(self) -> Vec<ImportItem> {
+        self.subscriptions
+            .into_iter()
+            .map(InvidiousSubscription)
+            .map(ImportItem::from)
+            .collect()
+    }
+
+    pub fn export(channels: &[Channel], watched: Vec<String>, path: &Path) -> Result<()> {
+        let file = File::create(path)?;
+
+        let data = Self {
+            subscriptions: channels
+                .iter()
+                .map(|channel| channel.channel_id.clone())
+                .collect(),
+            watch_history: watched,
+            playlists: Vec::new(),
+        };
+
+        Ok(serde_json::to_writer(file, &data)?)
+    }
+}
+
+/// Channel read from a format that groups subscriptions, such as OPML
+/// folders or FreeTube profiles.
+struct TaggedChannel {
+    channel_id: String,
+    channel_title: String,
+    tags: Vec<String>,
+}
+
+impl TaggedChannel {
+    /// Adds the channel to `entries`, merging the tags if it is already present.
+    fn add(entries: &mut Vec<Self>, channel_id: String, channel_title: String, tag: Option<&str>) {
+        let entry = match entries
+            .iter()
+            .position(|entry| entry.channel_id == channel_id)
+        {
+            Some(idx) => &mut entries[idx],
+            None => {
+                entries.push(Self {
+                    channel_id,
+                    channel_title,
+                    tags: Vec::new(),
+                });
+                entries.last_mut().unwrap()
+            }
+        };
+
+        if let Some(tag) = tag
+            && !entry.tags.iter().any(|t| t == tag)
+        {
+            entry.tags.push(tag.to_owned());
+        }
+    }
+}
+
+impl Import for TaggedChannel {
+    fn channel_id(&self) -> String {
+        self.channel_id.clone()
+    }
+
+    fn channel_title(&self) -> String {
+        self.channel_title.clone()
+    }
+
+    fn tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+}
+
+/// Groups the channels by the tags they belong to. Channels without tags are
+/// returned separately.
+fn group_by_tag<'a>(
+    channels: &'a [Channel],
+    tag_relations: &'a [(String, String)],
+) -> (Vec<(&'a str, Vec<&'a Channel>)>, Vec<&'a Channel>) {
+    let mut tags: Vec<(&str, Vec<&Channel>)> = Vec::new();
+    let mut untagged = Vec::new();
+
+    for channel in channels {
+        let mut tagged = false;
+
+        for (tag, _) in tag_relations
+            .iter()
+            .filter(|(_, channel_id)| *channel_id == channel.channel_id)
+        {
+            tagged = true;
+
+            match tags.iter_mut().find(|(name, _)| name == tag) {
+                Some((_, channels)) => channels.push(channel),
+                None => tags.push((tag, vec![channel])),
+            }
+        }
+
+        if !tagged {
+            untagged.push(channel);
+        }
+    }
+
+    (tags, untagged)
+}
+
+/// Extracts the channel id from YouTube feed and channel URLs, including the
+/// ones pointing to Invidious instances.
+fn channel_id_from_url(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+
+    if let Some((_, id)) = url.query_pairs().find(|(key, _)| key == "channel_id") {
+        return Some(id.into_owned());
+    }
+
+    let mut segments = url.path_segments()?;
+    segments.find(|segment| *segment == "channel")?;
+    segments
+        .next()
+        .filter(|id| id.starts_with("UC"))
+        .map(ToOwned::to_owned)
+}
+
 pub struct ImportItem {
     pub sub_state: RefreshState,
     pub channel_title: String,
     pub channel_id: String,
+    pub tags: Vec<String>,
 }
 
 impl<T: Import> From<T> for ImportItem {
@@ -175,6 +708,7 @@ impl<T: Import> From<T> for ImportItem {
             sub_state: RefreshState::Completed,
             channel_title: item.channel_title(),
             channel_id: item.channel_id(),
+            tags: item.tags(),
         }
     }
 }
@@ -193,7 +727,9 @@ impl Display for ImportItem {
 
 #[cfg(test)]
 mod tests {
-    use super::{NewPipe, YoutubeCsv};
+    use super::{FreeTube, Invidious, LibreTube, NewPipe, Opml, YoutubeCsv, channel_id_from_url};
+    use crate::channel::Channel;
+    use tempfile::tempdir;
 
     #[test]
     fn non_english_headers() {
@@ -246,4 +782,194 @@ mod tests {
 
         assert_eq!(s.subscriptions.len(), 1);
     }
+
+    #[test]
+    fn opml_channel_ids() {
+        assert_eq!(
+            channel_id_from_url(
+                "https://www.youtube.com/feeds/videos.xml?channel_id=UC-ocBsC30pAnk5pxJXvnXLg"
+            )
+            .as_deref(),
+            Some("UC-ocBsC30pAnk5pxJXvnXLg")
+        );
+        assert_eq!(
+            channel_id_from_url("https://invidious.example/feed/channel/UCotlzoNU0DtqlbpBw5m-6Fg")
+                .as_deref(),
+            Some("UCotlzoNU0DtqlbpBw5m-6Fg")
+        );
+        assert_eq!(
+            channel_id_from_url("https://www.youtube.com/@kurzgesagt"),
+            None
+        );
+    }
+
+    #[test]
+    fn opml_folders_as_tags() {
+        let opml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="1.1">
+  <body>
+    <outline text="YouTube Subscriptions" title="YouTube Subscriptions">
+      <outline text="JustRecycle." type="rss" xmlUrl="https://www.youtube.com/feeds/videos.xml?channel_id=UC-ocBsC30pAnk5pxJXvnXLg"/>
+      <outline text="Science" title="Science">
+        <outline text="Kurzgesagt" type="rss" xmlUrl="https://www.youtube.com/feeds/videos.xml?channel_id=UCsXVk37bltHxD1rDPwtNM8Q"/>
+      </outline>
+      <outline text="Favorites">
+        <outline text="Kurzgesagt" type="rss" xmlUrl="https://www.youtube.com/feeds/videos.xml?channel_id=UCsXVk37bltHxD1rDPwtNM8Q"/>
+      </outline>
+      <outline text="Some blog" type="rss" xmlUrl="https://example.com/feed.xml"/>
+    </outline>
+  </body>
+</opml>"#;
+
+        let items = Opml::read_subscriptions(opml.as_bytes()).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].channel_id, "UC-ocBsC30pAnk5pxJXvnXLg");
+        assert!(items[0].tags.is_empty());
+        assert_eq!(items[1].channel_title, "Kurzgesagt");
+        assert_eq!(items[1].tags, ["Science", "Favorites"]);
+    }
+
+    #[test]
+    fn opml_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("subscriptions.opml");
+        let channels = [
+            Channel::new(
+                String::from("UC-ocBsC30pAnk5pxJXvnXLg"),
+                String::from("JustRecycle."),
+                None,
+            ),
+            Channel::new(
+                String::from("UCsXVk37bltHxD1rDPwtNM8Q"),
+                String::from("Kurzgesagt"),
+                None,
+            ),
+        ];
+        let relations = [(
+            String::from("Science"),
+            String::from("UCsXVk37bltHxD1rDPwtNM8Q"),
+        )];
+
+        Opml::export(&channels, &relations, &path).unwrap();
+        let items = Opml::import(&path).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].channel_id, "UCsXVk37bltHxD1rDPwtNM8Q");
+        assert_eq!(items[0].tags, ["Science"]);
+        assert_eq!(items[1].channel_title, "JustRecycle.");
+        assert!(items[1].tags.is_empty());
+    }
+
+    #[test]
+    fn freetube_profiles() {
+        let profiles = concat!(
+            r##"{"name":"All Channels","bgColor":"#000000","textColor":"#FFFFFF","subscriptions":[{"id":"UC-ocBsC30pAnk5pxJXvnXLg","name":"JustRecycle.","thumbnail":""},{"id":"UCsXVk37bltHxD1rDPwtNM8Q","name":"Kurzgesagt","thumbnail":""}],"_id":"allChannels"}"##,
+            "\n",
+            r##"{"name":"Science","bgColor":"#FF0000","textColor":"#FFFFFF","subscriptions":[{"id":"UCsXVk37bltHxD1rDPwtNM8Q","name":"Kurzgesagt","thumbnail":""}],"_id":"V5Lw3FzYnA5HyjQk"}"##,
+            "\n",
+        );
+
+        let items = FreeTube::read_subscriptions(profiles.as_bytes()).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert!(items[0].tags.is_empty());
+        assert_eq!(items[1].channel_id, "UCsXVk37bltHxD1rDPwtNM8Q");
+        assert_eq!(items[1].tags, ["Science"]);
+    }
+
+    #[test]
+    fn libretube_groups() {
+        let json = serde_json::json!({
+            "watchHistory": [],
+            "localSubscriptions": [
+                {
+                    "channelId": "UC-ocBsC30pAnk5pxJXvnXLg",
+                    "url": "/channel/UC-ocBsC30pAnk5pxJXvnXLg",
+                    "name": "JustRecycle.",
+                    "avatar": null,
+                    "verified": false
+                },
+                {
+                    "channelId": "UCsXVk37bltHxD1rDPwtNM8Q",
+                    "url": "/channel/UCsXVk37bltHxD1rDPwtNM8Q",
+                    "name": "Kurzgesagt",
+                    "avatar": "https://example.com/avatar.jpg",
+                    "verified": true
+                }
+            ],
+            "channelGroups": [
+                { "name": "Science", "channels": ["UCsXVk37bltHxD1rDPwtNM8Q"], "index": 0 }
+            ]
+        });
+
+        let items = LibreTube::read_subscriptions(json.to_string().as_bytes()).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert!(items[0].tags.is_empty());
+        assert_eq!(items[1].channel_title, "Kurzgesagt");
+        assert_eq!(items[1].tags, ["Science"]);
+    }
+
+    #[test]
+    fn grouped_formats_round_trip() {
+        let dir = tempdir().unwrap();
+        let channels = [
+            Channel::new(
+                String::from("UC-ocBsC30pAnk5pxJXvnXLg"),
+                String::from("JustRecycle."),
+                None,
+            ),
+            Channel::new(
+                String::from("UCsXVk37bltHxD1rDPwtNM8Q"),
+                String::from("Kurzgesagt"),
+                None,
+            ),
+        ];
+        let relations = [
+            (
+                String::from("Favorites"),
+                String::from("UCsXVk37bltHxD1rDPwtNM8Q"),
+            ),
+            (
+                String::from("Science"),
+                String::from("UCsXVk37bltHxD1rDPwtNM8Q"),
+            ),
+        ];
+
+        let path = dir.path().join("freetube-profiles.db");
+        FreeTube::export(&channels, &relations, &path).unwrap();
+        let items = FreeTube::import(&path).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].tags, ["Favorites", "Science"]);
+
+        let path = dir.path().join("libretube-backup.json");
+        LibreTube::export(&channels, &relations, &path).unwrap();
+        let items = LibreTube::import(&path).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert!(items[0].tags.is_empty());
+        assert_eq!(items[1].tags, ["Favorites", "Science"]);
+    }
+
+    #[test]
+    fn invidious() {
+        let json = serde_json::json!({
+            "subscriptions": ["UC-ocBsC30pAnk5pxJXvnXLg", "UCsXVk37bltHxD1rDPwtNM8Q"],
+            "watch_history": ["dQw4w9WgXcQ"],
+            "preferences": { "locale": "en-US", "dark_mode": "dark" },
+            "playlists": []
+        });
+
+        let data: Invidious = serde_json::from_value(json).unwrap();
+
+        assert_eq!(data.watch_history, ["dQw4w9WgXcQ"]);
+
+        let items = data.into_import_items();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].channel_id, "UCsXVk37bltHxD1rDPwtNM8Q");
+        assert!(items[1].channel_title.is_empty());
+    }
 }
@@ -1,10 +1,11 @@
 use crate::{
     CONFIG, KEY_BINDINGS,
     api::ApiBackend,
-    app::{App, VideoPlayer},
+    app::App,
     commands::{
-        ChannelSelectionCommand, Command, FormatSelectionCommand, HelpCommand, ImportCommand,
-        TagCommand,
+        ChannelSelectionCommand, ChannelSettingsCommand, ChapterSelectionCommand, Command,
+        DownloadsCommand, FilterCommand, FormatSelectionCommand, HelpCommand, ImportCommand,
+        StatsCommand, TagCommand, TrackSelectionCommand, TrashCommand,
     },
     help::HelpWindowState,
     list::Scrollable as _,
@@ -22,6 +23,15 @@ pub enum InputMode {
     Tag,
     TagCreation,
     TagRenaming,
+    Trash,
+    Filter,
+    FilterCreation,
+    ChannelSettings,
+    ChannelSettingEditing,
+    Stats,
+    ChapterSelection,
+    TrackSelection,
+    Downloads,
     ChannelSelection,
     FormatSelection,
 }
@@ -39,6 +49,13 @@ pub fn handle_event(key: KeyEvent, app: &mut App) -> bool {
         InputMode::Confirmation => handle_key_confirmation_mode(key, app),
         InputMode::Import => return handle_key_import_mode(key, app),
         InputMode::Tag => return handle_key_tag_mode(key, app),
+        InputMode::Trash => return handle_key_trash_mode(key, app),
+        InputMode::Filter => return handle_key_filter_mode(key, app),
+        InputMode::ChannelSettings => return handle_key_channel_settings_mode(key, app),
+        InputMode::Stats => return handle_key_stats_mode(key, app),
+        InputMode::ChapterSelection => return handle_key_chapter_selection_mode(key, app),
+        InputMode::TrackSelection => return handle_key_track_selection_mode(key, app),
+        InputMode::Downloads => return handle_key_downloads_mode(key, app),
         InputMode::ChannelSelection => return handle_key_channel_selection_mode(key, app),
         InputMode::FormatSelection => return handle_key_format_selection_mode(key, app),
         _ => handle_key_editing_mode(key, app),
@@ -72,9 +89,12 @@ fn handle_key_normal_mode(key: KeyEvent, app: &mut App) -> bool {
             }
             Command::JumpToChannel => app.jump_to_channel(),
             Command::ToggleHide => app.toggle_hide(),
+            Command::ToggleDismissed => app.toggle_dismissed(),
             Command::Subscribe => app.prompt_for_subscription(),
             Command::Unsubscribe => app.prompt_for_unsubscribing(),
             Command::DeleteVideo => app.delete_selected_video(),
+            Command::DismissVideo => app.dismiss_selected_video(),
+            Command::UndismissVideo => app.undismiss_selected_video(),
             Command::SearchForward => app.search_forward(),
             Command::SearchBackward => app.search_backward(),
             Command::RepeatLastSearch => app.repeat_last_search(),
@@ -101,12 +121,28 @@ fn handle_key_normal_mode(key: KeyEvent, app: &mut App) -> bool {
             Command::IncreaseVolume => app.adjust_volume(2),
             Command::DecreaseVolume => app.adjust_volume(-2),
             Command::ToggleMute => app.toggle_mute(),
+            Command::IncreaseSpeed => app.adjust_speed(0.25),
+            Command::DecreaseSpeed => app.adjust_speed(-0.25),
+            Command::ResetSpeed => app.reset_speed(),
+            Command::NextChapter => app.adjust_chapter(1),
+            Command::PreviousChapter => app.adjust_chapter(-1),
+            Command::ToggleChapters => app.toggle_chapters(),
+            Command::ToggleTracks => app.toggle_tracks(),
             Command::StopPlayback => app.stop_playback(),
             Command::ReleaseVideo => app.release_video(),
+            Command::CycleSleepTimer => app.cycle_sleep_timer(),
             Command::SelectFormats => app.enter_format_selection(),
             Command::ToggleWatched => app.toggle_watched(),
             Command::ToggleHelp => app.toggle_help(),
             Command::ToggleTag => app.toggle_tag_selection(),
+            Command::Undo => app.undo(),
+            Command::ToggleTrash => app.toggle_trash(),
+            Command::ToggleFilters => app.toggle_filters(),
+            Command::ToggleFiltered => app.toggle_filtered(),
+            Command::ToggleChannelSettings => app.toggle_channel_settings(),
+            Command::ToggleStats => app.toggle_stats(),
+            Command::Download => app.download_video(),
+            Command::ToggleDownloads => app.toggle_downloads(),
             Command::Quit => return true,
         }
     }
@@ -232,6 +268,211 @@ fn handle_key_tag_mode(key: KeyEvent, app: &mut App) -> bool {
     false
 }
 
+fn handle_key_trash_mode(key: KeyEvent, app: &mut App) -> bool {
+    if let Some(command) = KEY_BINDINGS.trash.get(&key) {
+        match command {
+            TrashCommand::Restore => app.restore_selected_trash_entry(),
+            TrashCommand::Delete => app.delete_selected_trash_entry(),
+            TrashCommand::Abort => app.toggle_trash(),
+        }
+    } else if let Some(command) = KEY_BINDINGS.get(&key) {
+        match command {
+            Command::OnDown => app.trash.next(),
+            Command::OnUp => app.trash.previous(),
+            Command::SelectFirst => app.trash.select_first(),
+            Command::SelectLast => app.trash.select_last(),
+            Command::PageUp => app.trash.page_up(),
+            Command::PageDown => app.trash.page_down(),
+            Command::HalfPageUp => app.trash.half_page_up(),
+            Command::HalfPageDown => app.trash.half_page_down(),
+            Command::SearchForward => app.search_forward(),
+            Command::SearchBackward => app.search_backward(),
+            Command::RepeatLastSearch => app.repeat_last_search(),
+            Command::RepeatLastSearchOpposite => app.repeat_last_search_opposite(),
+            Command::ToggleTrash => app.toggle_trash(),
+            Command::Quit => return true,
+            _ => (),
+        }
+    }
+
+    false
+}
+
+fn handle_key_downloads_mode(key: KeyEvent, app: &mut App) -> bool {
+    if let Some(command) = KEY_BINDINGS.downloads.get(&key) {
+        match command {
+            DownloadsCommand::Cancel => app.cancel_selected_download(),
+            DownloadsCommand::Retry => app.retry_selected_download(),
+            DownloadsCommand::Abort => app.toggle_downloads(),
+        }
+    } else if let Some(command) = KEY_BINDINGS.get(&key) {
+        match command {
+            Command::OnDown => app.downloads.next(),
+            Command::OnUp => app.downloads.previous(),
+            Command::SelectFirst => app.downloads.select_first(),
+            Command::SelectLast => app.downloads.select_last(),
+            Command::PageUp => app.downloads.page_up(),
+            Command::PageDown => app.downloads.page_down(),
+            Command::HalfPageUp => app.downloads.half_page_up(),
+            Command::HalfPageDown => app.downloads.half_page_down(),
+            Command::SearchForward => app.search_forward(),
+            Command::SearchBackward => app.search_backward(),
+            Command::RepeatLastSearch => app.repeat_last_search(),
+            Command::RepeatLastSearchOpposite => app.repeat_last_search_opposite(),
+            Command::ToggleDownloads => app.toggle_downloads(),
+            Command::Quit => return true,
+            _ => (),
+        }
+    }
+
+    false
+}
+
+fn handle_key_filter_mode(key: KeyEvent, app: &mut App) -> bool {
+    if let Some(command) = KEY_BINDINGS.filter.get(&key) {
+        match command {
+            FilterCommand::CreateRule => app.enter_filter_creation(false),
+            FilterCommand::CreateChannelRule => app.enter_filter_creation(true),
+            FilterCommand::DeleteRule => app.delete_selected_filter_rule(),
+            FilterCommand::Abort => app.toggle_filters(),
+        }
+    } else if let Some(command) = KEY_BINDINGS.get(&key) {
+        match command {
+            Command::OnDown => app.filter_rules.next(),
+            Command::OnUp => app.filter_rules.previous(),
+            Command::SelectFirst => app.filter_rules.select_first(),
+            Command::SelectLast => app.filter_rules.select_last(),
+            Command::PageUp => app.filter_rules.page_up(),
+            Command::PageDown => app.filter_rules.page_down(),
+            Command::HalfPageUp => app.filter_rules.half_page_up(),
+            Command::HalfPageDown => app.filter_rules.half_page_down(),
+            Command::SearchForward => app.search_forward(),
+            Command::SearchBackward => app.search_backward(),
+            Command::RepeatLastSearch => app.repeat_last_search(),
+            Command::RepeatLastSearchOpposite => app.repeat_last_search_opposite(),
+            Command::ToggleFilters => app.toggle_filters(),
+            Command::Quit => return true,
+            _ => (),
+        }
+    }
+
+    false
+}
+
+fn handle_key_channel_settings_mode(key: KeyEvent, app: &mut App) -> bool {
+    if let Some(command) = KEY_BINDINGS.channel_settings.get(&key) {
+        match command {
+            ChannelSettingsCommand::Edit => app.edit_selected_channel_setting(),
+            ChannelSettingsCommand::Reset => app.reset_selected_channel_setting(),
+            ChannelSettingsCommand::Abort => app.toggle_channel_settings(),
+        }
+    } else if let Some(command) = KEY_BINDINGS.get(&key) {
+        match command {
+            Command::OnDown => app.channel_settings.next(),
+            Command::OnUp => app.channel_settings.previous(),
+            Command::SelectFirst => app.channel_settings.select_first(),
+            Command::SelectLast => app.channel_settings.select_last(),
+            Command::PageUp => app.channel_settings.page_up(),
+            Command::PageDown => app.channel_settings.page_down(),
+            Command::HalfPageUp => app.channel_settings.half_page_up(),
+            Command::HalfPageDown => app.channel_settings.half_page_down(),
+            Command::SearchForward => app.search_forward(),
+            Command::SearchBackward => app.search_backward(),
+            Command::RepeatLastSearch => app.repeat_last_search(),
+            Command::RepeatLastSearchOpposite => app.repeat_last_search_opposite(),
+            Command::ToggleChannelSettings => app.toggle_channel_settings(),
+            Command::Quit => return true,
+            _ => (),
+        }
+    }
+
+    false
+}
+
+fn handle_key_stats_mode(key: KeyEvent, app: &mut App) -> bool {
+    if let Some(command) = KEY_BINDINGS.stats.get(&key) {
+        match command {
+            StatsCommand::Abort => app.toggle_stats(),
+        }
+    } else if let Some(command) = KEY_BINDINGS.get(&key) {
+        match command {
+            Command::OnDown => app.stats_channels.next(),
+            Command::OnUp => app.stats_channels.previous(),
+            Command::SelectFirst => app.stats_channels.select_first(),
+            Command::SelectLast => app.stats_channels.select_last(),
+            Command::PageUp => app.stats_channels.page_up(),
+            Command::PageDown => app.stats_channels.page_down(),
+            Command::HalfPageUp => app.stats_channels.half_page_up(),
+            Command::HalfPageDown => app.stats_channels.half_page_down(),
+            Command::ToggleStats => app.toggle_stats(),
+            Command::Quit => return true,
+            _ => (),
+        }
+    }
+
+    false
+}
+
+fn handle_key_chapter_selection_mode(key: KeyEvent, app: &mut App) -> bool {
+    if let Some(command) = KEY_BINDINGS.chapter_selection.get(&key) {
+        match command {
+            ChapterSelectionCommand::Confirm => app.seek_to_selected_chapter(),
+            ChapterSelectionCommand::Abort => app.toggle_chapters(),
+        }
+    } else if let Some(command) = KEY_BINDINGS.get(&key) {
+        match command {
+            Command::OnDown => app.chapters.next(),
+            Command::OnUp => app.chapters.previous(),
+            Command::SelectFirst => app.chapters.select_first(),
+            Command::SelectLast => app.chapters.select_last(),
+            Command::PageUp => app.chapters.page_up(),
+            Command::PageDown => app.chapters.page_down(),
+            Command::HalfPageUp => app.chapters.half_page_up(),
+            Command::HalfPageDown => app.chapters.half_page_down(),
+            Command::SearchForward => app.search_forward(),
+            Command::SearchBackward => app.search_backward(),
+            Command::RepeatLastSearch => app.repeat_last_search(),
+            Command::RepeatLastSearchOpposite => app.repeat_last_search_opposite(),
+            Command::ToggleChapters => app.toggle_chapters(),
+            Command::Quit => return true,
+            _ => (),
+        }
+    }
+
+    false
+}
+
+fn handle_key_track_selection_mode(key: KeyEvent, app: &mut App) -> bool {
+    if let Some(command) = KEY_BINDINGS.track_selection.get(&key) {
+        match command {
+            TrackSelectionCommand::PreviousTab => app.tracks.previous_tab(),
+            TrackSelectionCommand::NextTab => app.tracks.next_tab(),
+            TrackSelectionCommand::Select => app.select_track(),
+            TrackSelectionCommand::Abort => app.toggle_tracks(),
+        }
+    } else if let Some(command) = KEY_BINDINGS.get(&key) {
+        match command {
+            Command::OnDown => app.tracks.get_mut_selected_tab().next(),
+            Command::OnUp => app.tracks.get_mut_selected_tab().previous(),
+            Command::SelectFirst => app.tracks.get_mut_selected_tab().select_first(),
+            Command::SelectLast => app.tracks.get_mut_selected_tab().select_last(),
+            Command::PageUp => app.tracks.get_mut_selected_tab().page_up(),
+            Command::PageDown => app.tracks.get_mut_selected_tab().page_down(),
+            Command::HalfPageUp => app.tracks.get_mut_selected_tab().half_page_up(),
+            Command::HalfPageDown => app.tracks.get_mut_selected_tab().half_page_down(),
+            Command::SearchForward => app.search_forward(),
+            Command::SearchBackward => app.search_backward(),
+            Command::RepeatLastSearch => app.repeat_last_search(),
+            Command::RepeatLastSearchOpposite => app.repeat_last_search_opposite(),
+            Command::ToggleTracks => app.toggle_tracks(),
+            Command::Quit => return true,
+            _ => (),
+        }
+    }
+
+    false
+}
+
 fn handle_key_channel_selection_mode(key: KeyEvent, app: &mut App) -> bool {
     if let Some(command) = KEY_BINDINGS.channel_selection.get(&key) {
         match command {
@@ -267,13 +508,16 @@ fn handle_key_format_selection_mode(key: KeyEvent, app: &mut App) -> bool {
     if let Some(command) = KEY_BINDINGS.format_selection.get(&key) {
         match command {
             FormatSelectionCommand::PlayVideo => app.confirm_selected_streams(),
+            FormatSelectionCommand::Download => app.download_selected_streams(),
             FormatSelectionCommand::Abort => app.input_mode = InputMode::Normal,
             FormatSelectionCommand::Select => {
                 let tab_index = app.stream_formats.selected_tab;
                 let formats = app.stream_formats.get_mut_selected_tab();
 
                 if tab_index == 2
-                    && matches!(CONFIG.video_player_for_stream_formats, VideoPlayer::Mpv)
+                    && CONFIG
+                        .player(&CONFIG.video_player_for_stream_formats)
+                        .is_some_and(|player| player.supports_multiple_captions())
                 {
                     formats.toggle_selected();
                 } else {
@@ -333,13 +577,19 @@ fn complete(app: &mut App) {
         InputMode::Search => app.complete_search(),
         InputMode::TagCreation => app.create_tag(),
         InputMode::TagRenaming => app.rename_selected_tag(),
+        InputMode::FilterCreation => app.create_filter_rule(),
+        InputMode::ChannelSettingEditing => app.complete_channel_setting_editing(),
         _ => (),
     }
 }
 
 fn abort(app: &mut App) {
     match app.input_mode {
-        InputMode::Subscribe | InputMode::TagCreation | InputMode::TagRenaming => {
+        InputMode::Subscribe
+        | InputMode::TagCreation
+        | InputMode::TagRenaming
+        | InputMode::FilterCreation
+        | InputMode::ChannelSettingEditing => {
             app.input_mode = app.prev_input_mode.clone();
             app.input.clear();
         }
@@ -1,14 +1,20 @@
 mod api;
 mod app;
+mod autoplay;
 mod channel;
+mod channel_settings;
 mod cli;
 mod client;
 mod clipboard;
 mod commands;
 mod config;
+mod cookies;
 mod database;
+mod download;
 mod emulator;
+mod filter;
 mod help;
+mod history;
 mod http;
 mod import;
 mod input;
@@ -21,11 +27,16 @@ mod progress;
 mod protobuf;
 mod ro_cell;
 mod search;
+mod state;
+mod stats;
 mod stream_formats;
 mod thumbnail;
+mod tracks;
+mod trash;
 mod ui;
 mod utils;
 mod video;
+mod vlc;
 
 use crate::client::IoEvent;
 use crate::config::Config;
@@ -33,7 +44,7 @@ use crate::config::keys::KeyBindings;
 use crate::config::theme::Theme;
 use crate::emulator::Emulator;
 use crate::mpv::PlaybackPhase;
-use anyhow::Result;
+use anyhow::{Context, Result, ensure};
 use app::App;
 use channel::RefreshState;
 use clap::ArgMatches;
@@ -51,9 +62,10 @@ use input::InputMode;
 use ratatui::DefaultTerminal;
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
+use stats::Stats;
 use std::io;
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use std::time::Duration;
 use std::time::Instant;
@@ -82,49 +94,90 @@ async fn main() -> Result<()> {
 
     let subcommand = CLAP_ARGS.subcommand();
 
-    if let Some(("database", database_matches)) = subcommand
-        && let Some(("downgrade", downgrade_matches)) = database_matches.subcommand()
-    {
-        let target_version = downgrade_matches.get_one::<u8>("target").copied();
-
-        match database::downgrade_database(&CONFIG.database, target_version)? {
-            database::DowngradeOutcome::Downgraded {
-                from,
-                to,
-                backup_path,
-            } => {
-                println!("Downgraded database schema from {from} to {to}.");
-                println!("Backup: {}", backup_path.display());
-
-                let removed_data = downgrade_removed_data(from, to);
-                if !removed_data.is_empty() {
-                    println!(
-                        "Removed during downgrade: {}. The original data remains in the backup.",
-                        removed_data.join(", ")
-                    );
-                }
+    if let Some(("database", database_matches)) = subcommand {
+        match database_matches.subcommand() {
+            Some(("downgrade", matches)) => {
+                downgrade_database(matches.get_one::<u8>("target").copied())?;
+            }
+            Some(("import-history", matches)) => {
+                import_history(matches.get_one::<PathBuf>("source").unwrap())?;
+            }
+            Some(("merge", matches)) => {
+                merge_database(matches.get_one::<PathBuf>("source").unwrap())?;
+            }
+            Some(("export-state", matches)) => {
+                export_state(matches.get_one::<PathBuf>("target").unwrap())?;
+            }
+            Some(("import-state", matches)) => {
+                let strategy = matches
+                    .get_one::<String>("strategy")
+                    .map(String::as_str)
+                    .unwrap()
+                    .into();
+                import_state(matches.get_one::<PathBuf>("source").unwrap(), strategy)?;
             }
-            database::DowngradeOutcome::AlreadyAtTarget { version } => {
-                println!("Database is already at schema version {version}.");
+            Some(("backups", _)) => list_backups()?,
+            Some(("prune", matches)) => prune_videos(&database::PruneOptions {
+                published_before: matches
+                    .get_one::<u64>("older_than")
+                    .map(|days| utils::now().map(|now| now.saturating_sub(days * 24 * 60 * 60)))
+                    .transpose()?,
+                keep_per_channel: matches.get_one::<usize>("keep_per_channel").copied(),
+                keep_watched: matches.get_flag("keep_watched"),
+                keep_in_progress: matches.get_flag("keep_in_progress"),
+            })?,
+            Some(("vacuum", _)) => vacuum_database()?,
+            Some(("check", _)) => check_database()?,
+            Some(("restore", matches)) => {
+                restore_backup(matches.get_one::<PathBuf>("backup").unwrap())?;
+            }
+            _ => unreachable!(),
+        }
+
+        // only these subcommands write a backup of the database
+        if matches!(
+            database_matches.subcommand_name(),
+            Some("downgrade" | "merge" | "prune" | "restore")
+        ) {
+            for backup in prune_backups()? {
+                println!("Removed old backup {}", backup.display());
             }
         }
 
         return Ok(());
     }
 
+    if let Some(("stats", matches)) = subcommand {
+        return show_stats(
+            matches
+                .get_one::<u64>("inactive_months")
+                .copied()
+                .unwrap_or(CONFIG.inactive_channel_months),
+            *matches.get_one::<usize>("top").unwrap(),
+        );
+    }
+
     let (io_tx, io_rx) = mpsc::unbounded_channel();
 
     let mut app = App::new(io_tx)?;
 
+    prune_backups()?;
+
     match subcommand {
-        Some(("import", matches)) => app.select_channels_to_import(
-            matches.get_one::<PathBuf>("source").unwrap(),
-            matches
+        Some(("import", matches)) => {
+            let source = matches.get_one::<PathBuf>("source").unwrap();
+            let format = matches
                 .get_one::<String>("format")
                 .map(String::as_str)
                 .unwrap()
-                .into(),
-        )?,
+                .into();
+
+            if matches.get_flag("watch_history") {
+                app.import_watch_history(source, format)?;
+            }
+
+            app.select_channels_to_import(source, format)?;
+        }
         Some(("export", matches)) => {
             return app.export_subscriptions(
                 matches.get_one::<PathBuf>("target").unwrap(),
@@ -163,9 +216,294 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+fn downgrade_database(target_version: Option<u8>) -> Result<()> {
+    match database::downgrade_database(&CONFIG.database, target_version)? {
+        database::DowngradeOutcome::Downgraded {
+            from,
+            to,
+            backup_path,
+        } => {
+            println!("Downgraded database schema from {from} to {to}.");
+            println!("Backup: {}", backup_path.display());
+
+            let removed_data = downgrade_removed_data(from, to);
+            if !removed_data.is_empty() {
+                println!(
+                    "Removed during downgrade: {}. The original data remains in the backup.",
+                    removed_data.join(", ")
+                );
+            }
+        }
+        database::DowngradeOutcome::AlreadyAtTarget { version } => {
+            println!("Database is already at schema version {version}.");
+        }
+    }
+
+    Ok(())
+}
+
+fn import_history(source: &Path) -> Result<()> {
+    let video_ids = history::read_watched_videos(source)
+        .with_context(|| format!("Failed to read watch history from {}", source.display()))?;
+
+    let mut conn = database::open_db(&CONFIG.database)?;
+    let existing = database::mark_watched(&mut conn, &video_ids)?;
+
+    println!(
+        "Marked {existing} videos as watched. {} videos not in the database yet will be marked \
+        when they are fetched.",
+        video_ids.len() - existing
+    );
+
+    Ok(())
+}
+
+fn list_backups() -> Result<()> {
+    let backups = database::list_backups(&CONFIG.database)?;
+
+    if backups.is_empty() {
+        println!("No backups of {}.", CONFIG.database.display());
+        return Ok(());
+    }
+
+    for backup in backups {
+        let created = backup.created.map_or_else(
+            || String::from("unknown date"),
+            |created| {
+                created
+                    .with_timezone(&chrono::Local)
+                    .format(&CONFIG.datetime_format)
+                    .to_string()
+            },
+        );
+
+        println!(
+            "{}  schema {}  {}  {created}",
+            backup.path.file_name().map_or_else(
+                || backup.path.display().to_string(),
+                |name| { name.to_string_lossy().into_owned() }
+            ),
+            backup.schema_version,
+            utils::file_size(backup.size),
+        );
+    }
+
+    Ok(())
+}
+
+fn restore_backup(backup: &Path) -> Result<()> {
+    // backups are listed by file name, so look for them next to the database
+    let backup = if backup.exists() || backup.components().count() > 1 {
+        backup.to_owned()
+    } else {
+        CONFIG.database.with_file_name(backup)
+    };
+
+    let current_backup = database::restore_backup(&CONFIG.database, &backup)?;
+
+    println!("Restored {}.", backup.display());
+
+    if let Some(current_backup) = current_backup {
+        println!(
+            "Backup of the replaced database: {}",
+            current_backup.display()
+        );
+    }
+
+    Ok(())
+}
+
+fn prune_backups() -> Result<Vec<PathBuf>> {
+    match CONFIG.max_backups {
+        Some(keep) => database::prune_backups(&CONFIG.database, keep),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn prune_videos(options: &database::PruneOptions) -> Result<()> {
+    let mut conn = database::open_db(&CONFIG.database)?;
+    let outcome = database::prune_videos(&mut conn, &CONFIG.database, options)?;
+
+    println!("Deleted {} videos.", outcome.deleted);
+
+    if let Some(backup_path) = outcome.backup_path {
+        println!("Backup: {}", backup_path.display());
+    }
+
+    Ok(())
+}
+
+fn vacuum_database() -> Result<()> {
+    let conn = database::open_db(&CONFIG.database)?;
+    let size_before = std::fs::metadata(&CONFIG.database)?.len();
+
+    database::vacuum(&conn)?;
+
+    let size_after = std::fs::metadata(&CONFIG.database)?.len();
+    println!(
+        "Database size: {} before, {} after.",
+        utils::file_size(size_before),
+        utils::file_size(size_after)
+    );
+
+    Ok(())
+}
+
+fn check_database() -> Result<()> {
+    let conn = database::open_db(&CONFIG.database)?;
+    let report = database::check(&conn)?;
+
+    for error in &report.integrity_errors {
+        println!("Integrity error: {error}");
+    }
+
+    for violation in &report.foreign_key_violations {
+        println!("Foreign key violation: {violation}");
+    }
+
+    if report.detached_states > 0 {
+        println!(
+            "{} watch states are kept for videos that aren't in the database, such as pruned or \
+            imported ones. They are applied if the videos are fetched again.",
+            report.detached_states
+        );
+    }
+
+    ensure!(!report.has_errors(), "The database check found problems");
+
+    println!("No problems found.");
+
+    Ok(())
+}
+
+fn show_stats(inactive_months: u64, top: usize) -> Result<()> {
+    let conn = database::open_db(&CONFIG.database)?;
+    let stats = Stats::collect(&conn, utils::now()?)?;
+
+    println!(
+        "Videos: {} watched, {} unwatched",
+        stats.watched(),
+        stats.unwatched()
+    );
+    println!("Watch time: {}", stats::watch_time_text(stats.watch_time()));
+    println!(
+        "Uploads in the last {} weeks: {}",
+        stats::WEEKS,
+        stats::sparkline(&stats.uploads_per_week)
+    );
+    println!(
+        "Watched in the last {} weeks: {}",
+        stats::WEEKS,
+        stats::sparkline(&stats.watched_per_week)
+    );
+
+    println!("\nMost watched channels:");
+    for channel in stats.most_watched().into_iter().take(top) {
+        println!(
+            "  {:>10}  {}",
+            stats::watch_time_text(channel.watch_time),
+            channel.channel_name
+        );
+    }
+
+    println!("\nChannels without a watched video in {inactive_months} months:");
+    for channel in stats.inactive_channels(inactive_months) {
+        println!(
+            "  {} (last watched {})",
+            channel.channel_name,
+            stats::last_watched_text(channel.last_watched)
+        );
+    }
+
+    println!(
+        "\n{:>7}  {:>9}  {:>12}  Channel",
+        "Watched", "Unwatched", "Uploads/week"
+    );
+    for channel in &stats.channels {
+        println!(
+            "{:>7}  {:>9}  {:>12.1}  {}",
+            channel.watched,
+            channel.unwatched(),
+            channel.uploads_per_week(),
+            channel.channel_name
+        );
+    }
+
+    Ok(())
+}
+
+fn merge_database(source: &Path) -> Result<()> {
+    let mut conn = database::open_db(&CONFIG.database)?;
+    let outcome = database::merge_database(&mut conn, &CONFIG.database, source)?;
+
+    println!(
+        "Merged {}: {} new channels, {} new videos.",
+        source.display(),
+        outcome.new_channels,
+        outcome.new_videos
+    );
+    println!("Backup: {}", outcome.backup_path.display());
+
+    Ok(())
+}
+
+fn export_state(target: &Path) -> Result<()> {
+    let conn = database::open_db(&CONFIG.database)?;
+    let (videos, tags) = state::export(&conn, target)
+        .with_context(|| format!("Failed to export state to {}", target.display()))?;
+
+    println!("Exported the state of {videos} videos and {tags} tags.");
+
+    Ok(())
+}
+
+fn import_state(source: &Path, strategy: state::MergeStrategy) -> Result<()> {
+    let mut conn = database::open_db(&CONFIG.database)?;
+    let summary = state::import(&mut conn, source, strategy)
+        .with_context(|| format!("Failed to import state from {}", source.display()))?;
+
+    println!(
+        "Imported the state of {} videos and {} tags.",
+        summary.videos, summary.tags
+    );
+
+    if summary.skipped_channels > 0 {
+        println!(
+            "Skipped {} tagged channels that aren't subscribed.",
+            summary.skipped_channels
+        );
+    }
+
+    Ok(())
+}
+
 fn downgrade_removed_data(from: u8, to: u8) -> Vec<&'static str> {
     let mut removed = Vec::new();
 
+    if from >= 12 && to < 12 {
+        removed.push("the record of downloaded videos");
+    }
+    if from >= 11 && to < 11 {
+        removed.push("subtitle languages of channels");
+    }
+    if from >= 10 && to < 10 {
+        removed.push("channel settings");
+    }
+    if from >= 9 && to < 9 {
+        removed.push("filter rules");
+    }
+    if from >= 8 && to < 8 {
+        removed.push("dismissed videos");
+    }
+    if from >= 7 && to < 7 {
+        removed.push("the trash");
+    }
+    if from >= 6 && to < 6 {
+        removed.push("channel and video modification times");
+    }
+    if from >= 5 && to < 5 {
+        removed.push("video state timestamps");
+    }
     if from >= 4 && to < 4 {
         removed.push("saved playback positions");
     }
@@ -183,6 +521,8 @@ fn render(app: &mut App, terminal: &mut DefaultTerminal) -> Result<()> {
     const SEARCH_MODE_CURSOR_OFFSET: u16 = 1;
     const SUBSCRIBE_MODE_CURSOR_OFFSET: u16 = 25;
     const TAG_CREATION_MODE_CURSOR_OFFSET: u16 = 10;
+    const FILTER_CREATION_MODE_CURSOR_OFFSET: u16 = 13;
+    const CHANNEL_SETTING_EDITING_MODE_CURSOR_OFFSET: u16 = 7;
 
     let prev_covered_area = app.thumbnail.as_ref().and_then(|t| t.covered_area);
 
@@ -201,11 +541,15 @@ fn render(app: &mut App, terminal: &mut DefaultTerminal) -> Result<()> {
         mode @ (InputMode::Subscribe
         | InputMode::Search
         | InputMode::TagCreation
-        | InputMode::TagRenaming) => {
+        | InputMode::TagRenaming
+        | InputMode::FilterCreation
+        | InputMode::ChannelSettingEditing) => {
             let offset = match mode {
                 InputMode::Search => SEARCH_MODE_CURSOR_OFFSET,
                 InputMode::Subscribe => SUBSCRIBE_MODE_CURSOR_OFFSET,
                 InputMode::TagCreation | InputMode::TagRenaming => TAG_CREATION_MODE_CURSOR_OFFSET,
+                InputMode::FilterCreation => FILTER_CREATION_MODE_CURSOR_OFFSET,
+                InputMode::ChannelSettingEditing => CHANNEL_SETTING_EDITING_MODE_CURSOR_OFFSET,
                 _ => 0,
             };
             terminal
@@ -238,7 +582,21 @@ async fn run_tui(
     TX.init(req_tx);
 
     let (player, mut playback_update, _player_task) = mpv::PlayerHandle::spawn();
-    let mut client = client::Client::new(rx, player).await?;
+
+    #[cfg(target_os = "linux")]
+    let mpris = if CONFIG.mpris {
+        match mpv::Mpris::start(player.clone()).await {
+            Ok(mpris) => Some(mpris),
+            Err(error) => {
+                app.set_warning_message(&format!("Failed to register MPRIS player: {error}"));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut client = client::Client::new(rx, player.clone()).await?;
     tokio::spawn(async move { client.run().await });
 
     if CONFIG.show_thumbnails {
@@ -250,6 +608,7 @@ async fn run_tui(
 
     let (mut timeout, mut last_render) = (None, Instant::now());
     let mut playback_update_open = true;
+    let mut autoplay_tick = tokio::time::interval(Duration::from_millis(250));
 
     loop {
         tokio::select! {
@@ -277,6 +636,11 @@ async fn run_tui(
                     last_render = Instant::now();
                 }
             }
+            _ = autoplay_tick.tick(), if app.autoplay.is_some() => {
+                app.check_autoplay();
+                render(&mut app, terminal)?;
+                last_render = Instant::now();
+            }
             result = playback_update.recv(), if playback_update_open => {
                 match result {
                     Some(update) => {
@@ -284,6 +648,11 @@ async fn run_tui(
                             app.set_error_message(&format!("Playback failed: {error}"));
                         }
 
+                        #[cfg(target_os = "linux")]
+                        if let Some(mpris) = &mpris {
+                            mpris.update(&update.state, &update.cause);
+                        }
+
                         app.handle_playback_update(update);
 
                         timeout = None;
@@ -302,6 +671,9 @@ async fn run_tui(
         }
     }
 
+    // Video sessions kept idle for autoplay would outlive ytsub otherwise.
+    let _ = tokio::time::timeout(Duration::from_secs(1), player.shutdown()).await;
+
     Ok(())
 }
 
@@ -356,6 +728,12 @@ fn handle_event(event: ClientRequest, app: &mut App) {
             app.stream_formats = *formats;
         }
         ClientRequest::SetWatched(video_id, is_watched) => app.set_watched(&video_id, is_watched),
+        ClientRequest::SetDownloadState(video_id, state) => {
+            app.set_download_state(&video_id, state);
+        }
+        ClientRequest::ExternalPlaybackUpdate(update) => {
+            app.handle_external_playback_update(update);
+        }
         ClientRequest::SetMessage(msg, message_type, duration) => {
             app.message.set_message(&msg);
             app.message.message_type = message_type;
@@ -364,5 +742,6 @@ fn handle_event(event: ClientRequest, app: &mut App) {
             }
         }
         ClientRequest::ClearMessage => app.message.clear_message(),
+        ClientRequest::SetOffline(offline) => app.set_offline(offline),
     }
 }
@@ -1,11 +1,28 @@
-use super::{MpvLaunch, MpvSession, PlaybackKind, VideoRequest, ipc::MpvNotification};
+use super::{MpvLaunch, MpvSession, PlaybackKind, VideoRequest, VideoSource, ipc::MpvNotification};
+use crate::CONFIG;
+use crate::config::AutoplayScope;
+use crate::list::ListItem;
+use crate::utils::length_as_hhmmss;
 use crate::video::{PlaybackSpec, VideoMetadata};
 use anyhow::{Context, Result};
 use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt::Display;
 use std::time::Duration;
-use tokio::{sync::mpsc, task::JoinHandle, time::MissedTickBehavior};
-
-enum PlayRequest {
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+    time::{Instant, MissedTickBehavior},
+};
+
+pub const MIN_SPEED: f64 = 0.25;
+pub const MAX_SPEED: f64 = 4.0;
+/// The durations of the sleep timer presets in minutes.
+const SLEEP_TIMER_PRESETS: [u64; 3] = [15, 30, 60];
+/// How long the volume fades out before the sleep timer pauses playback.
+const SLEEP_FADE_DURATION: Duration = Duration::from_secs(30);
+
+pub(super) enum PlayRequest {
     Audio { spec: PlaybackSpec, source: String },
     Video(VideoRequest),
 }
@@ -18,53 +35,207 @@ impl PlayRequest {
         }
     }
 
-    fn source(&self) -> &str {
+    fn kind(&self) -> PlaybackKind {
+        match self {
+            PlayRequest::Audio { .. } => PlaybackKind::Audio,
+            PlayRequest::Video(_) => PlaybackKind::Video,
+        }
+    }
+
+    fn source(&self) -> Cow<'_, str> {
         match self {
-            PlayRequest::Audio { source, .. } => source,
+            PlayRequest::Audio { source, .. } => source.into(),
             PlayRequest::Video(request) => request.source(),
         }
     }
 }
 
-enum PlayerCommand {
-    Play(PlayRequest),
+pub(super) enum PlayerCommand {
+    Play(Box<PlayRequest>),
     Toggle,
+    SetPause(bool),
     Seek(i32),
+    /// Seeks to the given position in seconds.
+    SeekTo(u64),
     AdjustVolume(i8),
+    SetVolume(f64),
     ToggleMute,
+    AdjustSpeed(f64),
+    SetSpeed(f64),
+    ResetSpeed,
+    AdjustChapter(i64),
+    SetChapter(usize),
+    SetTrack(TrackKind, String),
+    CycleSleepTimer,
     Stop,
     ReleaseVideo,
+    /// Leaves the session before exiting, acknowledged through the sender.
+    Shutdown(oneshot::Sender<()>),
 }
 
 #[derive(Clone)]
 pub struct PlaybackState {
     pub metadata: Option<VideoMetadata>,
+    /// The kind of the mpv session, or `None` without a session.
+    pub kind: Option<PlaybackKind>,
     pub phase: PlaybackPhase,
     pub elapsed: Option<u64>,
     pub duration: Option<u64>,
     pub volume: Option<u64>,
     pub muted: Option<bool>,
+    pub speed: Option<f64>,
+    pub chapters: Vec<PlaybackChapter>,
+    pub chapter: Option<usize>,
+    pub tracks: Vec<PlaybackTrack>,
+    pub sleep_timer: Option<SleepTimer>,
 }
 
 impl PlaybackState {
     fn idle() -> Self {
         Self {
             metadata: None,
+            kind: None,
             phase: PlaybackPhase::Idle,
             elapsed: None,
             duration: None,
             volume: None,
             muted: None,
+            speed: None,
+            chapters: Vec::new(),
+            chapter: None,
+            tracks: Vec::new(),
+            sleep_timer: None,
         }
     }
 
-    fn is_loaded(&self) -> bool {
+    pub(super) fn is_loaded(&self) -> bool {
         matches!(self.phase, PlaybackPhase::Playing | PlaybackPhase::Paused)
     }
 
     fn is_playing(&self) -> bool {
         matches!(self.phase, PlaybackPhase::Playing)
     }
+
+    pub fn current_chapter(&self) -> Option<&PlaybackChapter> {
+        self.chapter.and_then(|index| self.chapters.get(index))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SleepTimer {
+    /// Pauses playback after one of the presets.
+    Minutes(u64, Instant),
+    /// Stops once the current video ends.
+    EndOfVideo,
+}
+
+impl SleepTimer {
+    /// Returns the preset after this one, or `None` after the last one.
+    fn next(timer: Option<Self>, now: Instant) -> Option<Self> {
+        let minutes = match timer {
+            None => SLEEP_TIMER_PRESETS.first(),
+            Some(Self::Minutes(minutes, _)) => SLEEP_TIMER_PRESETS
+                .iter()
+                .skip_while(|preset| **preset != minutes)
+                .nth(1),
+            Some(Self::EndOfVideo) => return None,
+        };
+
+        Some(match minutes {
+            Some(minutes) => Self::Minutes(*minutes, now + Duration::from_secs(minutes * 60)),
+            None => Self::EndOfVideo,
+        })
+    }
+}
+
+impl Display for SleepTimer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Minutes(_, deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                write!(f, "{}", length_as_hhmmss(remaining.as_secs() as u32))
+            }
+            Self::EndOfVideo => write!(f, "end of video"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlaybackChapter {
+    pub title: String,
+    pub start: u64,
+}
+
+impl Display for PlaybackChapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>8}  {}",
+            length_as_hhmmss(self.start as u32),
+            self.title
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackKind {
+    Audio,
+    Subtitle,
+}
+
+impl TrackKind {
+    const fn property(self) -> &'static str {
+        match self {
+            Self::Audio => "aid",
+            Self::Subtitle => "sid",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlaybackTrack {
+    /// The mpv track ID, or `"no"` for disabling the tracks of the kind.
+    pub id: String,
+    pub kind: TrackKind,
+    pub title: Option<String>,
+    pub language: Option<String>,
+    pub codec: Option<String>,
+    pub selected: bool,
+}
+
+impl PlaybackTrack {
+    pub fn off(kind: TrackKind) -> Self {
+        Self {
+            id: "no".to_owned(),
+            kind,
+            title: Some("Off".to_owned()),
+            language: None,
+            codec: None,
+            selected: false,
+        }
+    }
+}
+
+impl ListItem for PlaybackTrack {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Display for PlaybackTrack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.title, &self.language) {
+            (Some(title), Some(language)) => write!(f, "{title} ({language})")?,
+            (Some(name), None) | (None, Some(name)) => write!(f, "{name}")?,
+            (None, None) => write!(f, "Track {}", self.id)?,
+        }
+
+        if let Some(codec) = &self.codec {
+            write!(f, ", {codec}")?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for PlaybackState {
@@ -85,6 +256,8 @@ pub enum PlaybackUpdateCause {
     Paused,
     Resumed,
     Seeked,
+    /// The speed was changed by a speed command.
+    SpeedChanged,
     Released,
     Replaced,
     Ended(PlaybackEndReason),
@@ -116,6 +289,9 @@ struct PlayerController {
     state: PlaybackState,
     requested_entry_id: Option<i64>,
     event_entry_id: Option<i64>,
+    speed_requested: bool,
+    /// The volume before the sleep timer started fading it out.
+    volume_before_fade: Option<f64>,
     command_rx: mpsc::Receiver<PlayerCommand>,
     update_tx: mpsc::Sender<PlaybackUpdate>,
 }
@@ -130,6 +306,8 @@ impl PlayerController {
             state: PlaybackState::idle(),
             requested_entry_id: None,
             event_entry_id: None,
+            speed_requested: false,
+            volume_before_fade: None,
             command_rx: commands,
             update_tx,
         }
@@ -138,6 +316,8 @@ impl PlayerController {
     async fn run(&mut self) -> Result<()> {
         let mut progress_tick = tokio::time::interval(Duration::from_millis(250));
         progress_tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut sleep_timer_tick = tokio::time::interval(Duration::from_secs(1));
+        sleep_timer_tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
         loop {
             if let Some(session) = self.session.as_mut() {
@@ -150,6 +330,9 @@ impl PlayerController {
                             self.publish_state(PlaybackUpdateCause::Progress).await;
                         }
                     }
+                    _ = sleep_timer_tick.tick(), if self.state.sleep_timer.is_some() => {
+                        self.update_sleep_timer().await;
+                    }
                     command = self.command_rx.recv() => {
                         let Some(command) = command else {
                             return Ok(());
@@ -181,14 +364,22 @@ impl PlayerController {
         self.event_entry_id = None;
     }
 
+    /// Returns the session for the request, reusing the current one if it can.
     async fn ensure_session(&mut self, request: &PlayRequest) -> Result<&mut MpvSession> {
-        let reuse_audio = matches!(request, PlayRequest::Audio { .. })
-            && self
-                .session
-                .as_ref()
-                .is_some_and(|session| session.kind == PlaybackKind::Audio);
+        let reuse = match (request, self.session.as_ref().map(|session| session.kind)) {
+            (PlayRequest::Audio { .. }, Some(PlaybackKind::Audio)) => true,
+            // Video sessions are kept idle for autoplay. Stream formats are
+            // always played in a new session, since their streams are
+            // passed on the command line.
+            (PlayRequest::Video(request), Some(PlaybackKind::Video)) => {
+                CONFIG.autoplay != AutoplayScope::Off
+                    && self.requested_entry_id.is_none()
+                    && !matches!(request.source, VideoSource::Direct { .. })
+            }
+            _ => false,
+        };
 
-        if !reuse_audio {
+        if !reuse {
             self.close_session().await;
 
             let launch = match request {
@@ -219,13 +410,20 @@ impl PlayerController {
         self.state.phase = PlaybackPhase::Idle;
         self.state.elapsed = None;
         self.state.duration = None;
+        self.state.chapters.clear();
+        self.state.chapter = None;
+        self.state.tracks.clear();
     }
 
     fn disconnect_session(&mut self) {
         self.session = None;
         self.set_idle();
+        self.state.kind = None;
+        self.state.sleep_timer = None;
+        self.volume_before_fade = None;
         self.state.volume = None;
         self.state.muted = None;
+        self.state.speed = None;
     }
 
     async fn stop_playback(&mut self) -> Result<()> {
@@ -245,6 +443,96 @@ impl PlayerController {
         Ok(())
     }
 
+    /// Returns the time left until the sleep timer pauses or the video ends.
+    fn sleep_timer_remaining(&self) -> Option<Duration> {
+        match self.state.sleep_timer? {
+            SleepTimer::Minutes(_, deadline) => {
+                Some(deadline.saturating_duration_since(Instant::now()))
+            }
+            SleepTimer::EndOfVideo => {
+                let remaining = self.state.duration?.saturating_sub(self.state.elapsed?);
+                let speed = self.state.speed.unwrap_or(1.0);
+
+                Some(Duration::from_secs_f64(remaining as f64 / speed))
+            }
+        }
+    }
+
+    /// Fades out the volume over the last seconds of the sleep timer, restores
+    /// it if the remaining time grows again, and pauses playback when it runs
+    /// out. The pause is reported like any other, so the position is saved.
+    async fn update_sleep_timer(&mut self) {
+        let Some(remaining) = self.sleep_timer_remaining() else {
+            return;
+        };
+        let Some(session) = &self.session else {
+            return;
+        };
+
+        if remaining.is_zero() && matches!(self.state.sleep_timer, Some(SleepTimer::Minutes(..))) {
+            if self.state.is_loaded() {
+                let _ = session
+                    .ipc
+                    .call(serde_json::json!(["set_property", "pause", true]))
+                    .await;
+            }
+
+            self.clear_sleep_timer().await;
+        } else if remaining < SLEEP_FADE_DURATION && self.state.is_playing() {
+            let volume = *self
+                .volume_before_fade
+                .get_or_insert(self.state.volume.unwrap_or(100) as f64);
+
+            let _ = session
+                .ipc
+                .call(serde_json::json!([
+                    "set_property",
+                    "volume",
+                    faded_volume(volume, remaining)
+                ]))
+                .await;
+        } else if remaining >= SLEEP_FADE_DURATION
+            && let Some(volume) = self.volume_before_fade.take()
+        {
+            // Seeking back or extending the timer leaves the fade.
+            let _ = session
+                .ipc
+                .call(serde_json::json!(["set_property", "volume", volume]))
+                .await;
+        }
+
+        // The remaining time is shown with the progress updates while playing.
+        if !self.state.is_playing() {
+            self.publish_state(PlaybackUpdateCause::Other).await;
+        }
+    }
+
+    /// Stops the sleep timer and restores the volume it faded out.
+    async fn clear_sleep_timer(&mut self) {
+        self.state.sleep_timer = None;
+
+        if let Some(volume) = self.volume_before_fade.take()
+            && let Some(session) = &self.session
+        {
+            let _ = session
+                .ipc
+                .call(serde_json::json!(["set_property", "volume", volume]))
+                .await;
+        }
+    }
+
+    /// Lets a video session kept idle for autoplay quit when its video ends.
+    async fn stop_idling(&self) {
+        if let Some(session) = &self.session
+            && session.kind == PlaybackKind::Video
+        {
+            let _ = session
+                .ipc
+                .call(serde_json::json!(["set_property", "idle", "no"]))
+                .await;
+        }
+    }
+
     async fn release_video(&mut self) {
         if self
             .session
@@ -254,6 +542,8 @@ impl PlayerController {
             let mut released_state = self.state.clone();
             released_state.phase = PlaybackPhase::Idle;
 
+            self.stop_idling().await;
+
             self.disconnect_session();
 
             self.publish_update(released_state, PlaybackUpdateCause::Released)
@@ -261,6 +551,31 @@ impl PlayerController {
         }
     }
 
+    /// Sets the speed of the current file only, so the next file starts at the
+    /// speed of its channel.
+    async fn set_speed(&mut self, speed: f64) -> Result<()> {
+        if !self.state.is_loaded() || self.state.speed == Some(speed) {
+            return Ok(());
+        }
+
+        let Some(session) = &self.session else {
+            return Ok(());
+        };
+
+        session
+            .ipc
+            .call(serde_json::json!([
+                "set_property",
+                "file-local-options/speed",
+                speed
+            ]))
+            .await?;
+
+        self.speed_requested = true;
+
+        Ok(())
+    }
+
     async fn handle_command(&mut self, command: PlayerCommand) -> Result<()> {
         let result: Result<()> = match command {
             PlayerCommand::Play(request) => {
@@ -269,16 +584,21 @@ impl PlayerController {
                 }
 
                 self.state.metadata = Some(request.spec().metadata.clone());
+                self.state.kind = Some(request.kind());
                 self.state.phase = PlaybackPhase::Loading;
                 self.state.elapsed = None;
                 self.state.duration = None;
+                self.state.chapters.clear();
+                self.state.chapter = None;
+                self.state.tracks.clear();
+                self.speed_requested = false;
                 self.publish_state(PlaybackUpdateCause::Loading).await;
 
                 async {
                     let session = self.ensure_session(&request).await?;
                     let entry_id = session
                         .ipc
-                        .load_file(request.source(), request.spec().start_position)
+                        .load_file(&request.source(), load_options(&request))
                         .await?;
 
                     session
@@ -305,6 +625,19 @@ impl PlayerController {
                     Ok(())
                 }
             }
+            PlayerCommand::SetPause(paused) => {
+                if self.state.is_loaded()
+                    && let Some(session) = &self.session
+                {
+                    session
+                        .ipc
+                        .call(serde_json::json!(["set_property", "pause", paused]))
+                        .await
+                        .map(|_| ())
+                } else {
+                    Ok(())
+                }
+            }
             PlayerCommand::Seek(sec) => {
                 if self.state.is_loaded()
                     && let Some(session) = &self.session
@@ -318,6 +651,19 @@ impl PlayerController {
                     Ok(())
                 }
             }
+            PlayerCommand::SeekTo(sec) => {
+                if self.state.is_loaded()
+                    && let Some(session) = &self.session
+                {
+                    session
+                        .ipc
+                        .call(serde_json::json!(["seek", sec, "absolute"]))
+                        .await
+                        .map(|_| ())
+                } else {
+                    Ok(())
+                }
+            }
             PlayerCommand::AdjustVolume(value) => {
                 if let Some(session) = &self.session {
                     session
@@ -329,6 +675,17 @@ impl PlayerController {
                     Ok(())
                 }
             }
+            PlayerCommand::SetVolume(value) => {
+                if let Some(session) = &self.session {
+                    session
+                        .ipc
+                        .call(serde_json::json!(["set_property", "volume", value]))
+                        .await
+                        .map(|_| ())
+                } else {
+                    Ok(())
+                }
+            }
             PlayerCommand::ToggleMute => {
                 if let Some(session) = &self.session {
                     session
@@ -340,11 +697,79 @@ impl PlayerController {
                     Ok(())
                 }
             }
+            PlayerCommand::AdjustSpeed(value) => {
+                let speed = step_speed(self.state.speed.unwrap_or(1.0), value);
+                self.set_speed(speed).await
+            }
+            PlayerCommand::SetSpeed(speed) => {
+                self.set_speed(speed.clamp(MIN_SPEED, MAX_SPEED)).await
+            }
+            PlayerCommand::ResetSpeed => self.set_speed(1.0).await,
+            PlayerCommand::AdjustChapter(value) => {
+                if self.state.is_loaded()
+                    && !self.state.chapters.is_empty()
+                    && let Some(session) = &self.session
+                {
+                    session
+                        .ipc
+                        .call(serde_json::json!(["add", "chapter", value]))
+                        .await
+                        .map(|_| ())
+                } else {
+                    Ok(())
+                }
+            }
+            PlayerCommand::SetChapter(index) => {
+                if self.state.is_loaded()
+                    && let Some(session) = &self.session
+                {
+                    session
+                        .ipc
+                        .call(serde_json::json!(["set_property", "chapter", index]))
+                        .await
+                        .map(|_| ())
+                } else {
+                    Ok(())
+                }
+            }
+            PlayerCommand::SetTrack(kind, id) => {
+                if self.state.is_loaded()
+                    && let Some(session) = &self.session
+                {
+                    let id = id
+                        .parse::<i64>()
+                        .map_or_else(|_| Value::from(id), Value::from);
+
+                    session
+                        .ipc
+                        .call(serde_json::json!(["set_property", kind.property(), id]))
+                        .await
+                        .map(|_| ())
+                } else {
+                    Ok(())
+                }
+            }
+            PlayerCommand::CycleSleepTimer => {
+                if self.state.is_loaded() {
+                    let timer = SleepTimer::next(self.state.sleep_timer, Instant::now());
+                    self.clear_sleep_timer().await;
+                    self.state.sleep_timer = timer;
+                    self.publish_state(PlaybackUpdateCause::Other).await;
+                }
+
+                Ok(())
+            }
             PlayerCommand::Stop => self.stop_playback().await,
             PlayerCommand::ReleaseVideo => {
                 self.release_video().await;
                 Ok(())
             }
+            PlayerCommand::Shutdown(ack) => {
+                self.stop_idling().await;
+                self.session = None;
+                let _ = ack.send(());
+                Ok(())
+            }
         };
 
         if let Err(error) = result {
@@ -404,6 +829,28 @@ impl PlayerController {
                             .map(|seconds| seconds.round() as u64);
                     }
                     Some("mute") => self.state.muted = event.get("data").and_then(Value::as_bool),
+                    Some("chapter-list") if self.notification_is_for_current() => {
+                        self.state.chapters =
+                            event.get("data").map(parse_chapters).unwrap_or_default();
+                    }
+                    Some("track-list") if self.notification_is_for_current() => {
+                        self.state.tracks = event.get("data").map(parse_tracks).unwrap_or_default();
+                    }
+                    // mpv reports -1 before the first chapter.
+                    Some("chapter") if self.notification_is_for_current() => {
+                        self.state.chapter = event
+                            .get("data")
+                            .and_then(Value::as_u64)
+                            .map(|index| index as usize);
+                    }
+                    Some("speed") => {
+                        self.state.speed = event.get("data").and_then(Value::as_f64);
+
+                        if self.speed_requested && self.notification_is_for_current() {
+                            self.speed_requested = false;
+                            cause = PlaybackUpdateCause::SpeedChanged;
+                        }
+                    }
                     _ => return Ok(()),
                 },
                 Some("seek") if self.notification_is_for_current() => {
@@ -461,6 +908,11 @@ impl PlayerController {
 
                     self.publish_state(PlaybackUpdateCause::Ended(reason)).await;
                     self.set_idle();
+
+                    if matches!(self.state.sleep_timer, Some(SleepTimer::EndOfVideo)) {
+                        self.clear_sleep_timer().await;
+                    }
+
                     return Ok(());
                 }
                 _ => return Ok(()),
@@ -508,6 +960,14 @@ impl PlayerHandle {
         (Self { command_tx }, update_rx, task)
     }
 
+    /// Returns a handle whose commands are received by the returned receiver
+    /// instead of a controller.
+    #[cfg(test)]
+    pub(super) fn detached() -> (Self, mpsc::Receiver<PlayerCommand>) {
+        let (command_tx, command_rx) = mpsc::channel(32);
+        (Self { command_tx }, command_rx)
+    }
+
     fn send(&self, command: PlayerCommand) -> Result<()> {
         self.command_tx
             .try_send(command)
@@ -515,29 +975,72 @@ impl PlayerHandle {
     }
 
     pub fn play_audio(&self, spec: PlaybackSpec, source: String) -> Result<()> {
-        self.send(PlayerCommand::Play(PlayRequest::Audio { spec, source }))
+        self.send(PlayerCommand::Play(Box::new(PlayRequest::Audio {
+            spec,
+            source,
+        })))
     }
 
     pub fn play_video(&self, request: VideoRequest) -> Result<()> {
-        self.send(PlayerCommand::Play(PlayRequest::Video(request)))
+        self.send(PlayerCommand::Play(Box::new(PlayRequest::Video(request))))
     }
 
     pub fn toggle(&self) -> Result<()> {
         self.send(PlayerCommand::Toggle)
     }
 
+    pub fn set_pause(&self, paused: bool) -> Result<()> {
+        self.send(PlayerCommand::SetPause(paused))
+    }
+
     pub fn seek_relative(&self, seconds: i32) -> Result<()> {
         self.send(PlayerCommand::Seek(seconds))
     }
 
+    pub fn seek_to(&self, seconds: u64) -> Result<()> {
+        self.send(PlayerCommand::SeekTo(seconds))
+    }
+
     pub fn adjust_volume(&self, value: i8) -> Result<()> {
         self.send(PlayerCommand::AdjustVolume(value))
     }
 
+    pub fn set_volume(&self, volume: f64) -> Result<()> {
+        self.send(PlayerCommand::SetVolume(volume))
+    }
+
     pub fn toggle_mute(&self) -> Result<()> {
         self.send(PlayerCommand::ToggleMute)
     }
 
+    pub fn adjust_speed(&self, value: f64) -> Result<()> {
+        self.send(PlayerCommand::AdjustSpeed(value))
+    }
+
+    pub fn set_speed(&self, speed: f64) -> Result<()> {
+        self.send(PlayerCommand::SetSpeed(speed))
+    }
+
+    pub fn reset_speed(&self) -> Result<()> {
+        self.send(PlayerCommand::ResetSpeed)
+    }
+
+    pub fn adjust_chapter(&self, value: i64) -> Result<()> {
+        self.send(PlayerCommand::AdjustChapter(value))
+    }
+
+    pub fn set_chapter(&self, index: usize) -> Result<()> {
+        self.send(PlayerCommand::SetChapter(index))
+    }
+
+    pub fn set_track(&self, kind: TrackKind, id: String) -> Result<()> {
+        self.send(PlayerCommand::SetTrack(kind, id))
+    }
+
+    pub fn cycle_sleep_timer(&self) -> Result<()> {
+        self.send(PlayerCommand::CycleSleepTimer)
+    }
+
     pub fn stop(&self) -> Result<()> {
         self.send(PlayerCommand::Stop)
     }
@@ -545,4 +1048,331 @@ impl PlayerHandle {
     pub fn release_video(&self) -> Result<()> {
         self.send(PlayerCommand::ReleaseVideo)
     }
+
+    /// Closes the audio session and lets the video session quit when its
+    /// video ends.
+    pub async fn shutdown(&self) -> Result<()> {
+        let (ack_tx, ack_rx) = oneshot::channel();
+        self.send(PlayerCommand::Shutdown(ack_tx))?;
+        ack_rx.await?;
+
+        Ok(())
+    }
+}
+
+/// Adds `value` to the speed, rounded to hundredths so repeated steps don't
+/// accumulate floating point errors.
+fn step_speed(speed: f64, value: f64) -> f64 {
+    ((speed + value) * 100.0)
+        .round()
+        .clamp(MIN_SPEED * 100.0, MAX_SPEED * 100.0)
+        / 100.0
+}
+
+/// Returns the volume for the remaining time of the sleep timer, lowered
+/// linearly over the fade duration.
+fn faded_volume(volume: f64, remaining: Duration) -> f64 {
+    let ratio = remaining.as_secs_f64() / SLEEP_FADE_DURATION.as_secs_f64();
+    (volume * ratio.min(1.0)).round()
+}
+
+/// Returns the options of the file that come from the playback spec.
+fn file_options(spec: &PlaybackSpec) -> serde_json::Map<String, Value> {
+    let mut options = serde_json::Map::new();
+
+    if let Some(position) = spec.start_position {
+        options.insert("start".to_owned(), position.to_string().into());
+    }
+
+    if let Some(speed) = spec.channel_settings.speed {
+        options.insert("speed".to_owned(), speed.to_string().into());
+    }
+
+    match spec.channel_settings.subtitle_language.as_deref() {
+        Some("off") => {
+            options.insert("sid".to_owned(), "no".into());
+        }
+        Some(language) => {
+            options.insert("slang".to_owned(), language.into());
+        }
+        None => (),
+    }
+
+    options
+}
+
+/// Returns the options the file of the request is loaded with. mpv restores
+/// them when the file ends, so the settings of a video don't carry over to the
+/// next one in a reused session.
+fn load_options(request: &PlayRequest) -> serde_json::Map<String, Value> {
+    let mut options = file_options(request.spec());
+
+    // Stream formats are passed on the command line of a new session.
+    if let PlayRequest::Video(request) = request
+        && !matches!(request.source, VideoSource::Direct { .. })
+    {
+        options.extend(source_options(request));
+    }
+
+    options
+}
+
+/// Returns the options of the source. They also clear the streams that a
+/// session launched for stream formats was started with.
+fn source_options(request: &VideoRequest) -> serde_json::Map<String, Value> {
+    let mut options = serde_json::Map::new();
+
+    let (ytdl, title, chapters) = match &request.source {
+        VideoSource::File(path) => {
+            let chapters = path.with_extension("ffmetadata");
+            options.insert("audio-file-auto".to_owned(), "fuzzy".into());
+
+            (
+                "no",
+                request.spec.metadata.title.clone(),
+                chapters
+                    .exists()
+                    .then(|| chapters.to_string_lossy().into_owned()),
+            )
+        }
+        VideoSource::YtDlp(_) | VideoSource::Direct { .. } => ("yes", String::new(), None),
+    };
+
+    options.insert("ytdl".to_owned(), ytdl.into());
+    options.insert("force-media-title".to_owned(), title.into());
+    options.insert("audio-files".to_owned(), "".into());
+    options.insert("sub-files".to_owned(), "".into());
+    options.insert(
+        "chapters-file".to_owned(),
+        chapters.unwrap_or_default().into(),
+    );
+
+    options
+}
+
+/// Returns the audio and subtitle tracks of the track list.
+fn parse_tracks(track_list: &Value) -> Vec<PlaybackTrack> {
+    let text = |track: &Value, key| track.get(key).and_then(Value::as_str).map(str::to_owned);
+
+    track_list
+        .as_array()
+        .map(|tracks| {
+            tracks
+                .iter()
+                .filter_map(|track| {
+                    let kind = match track.get("type").and_then(Value::as_str)? {
+                        "audio" => TrackKind::Audio,
+                        "sub" => TrackKind::Subtitle,
+                        _ => return None,
+                    };
+
+                    Some(PlaybackTrack {
+                        id: track.get("id").and_then(Value::as_i64)?.to_string(),
+                        kind,
+                        title: text(track, "title"),
+                        language: text(track, "lang"),
+                        codec: text(track, "codec"),
+                        selected: track
+                            .get("selected")
+                            .and_then(Value::as_bool)
+                            .unwrap_or_default(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_chapters(chapter_list: &Value) -> Vec<PlaybackChapter> {
+    chapter_list
+        .as_array()
+        .map(|chapters| {
+            chapters
+                .iter()
+                .map(|chapter| PlaybackChapter {
+                    title: chapter
+                        .get("title")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_owned(),
+                    start: chapter
+                        .get("time")
+                        .and_then(Value::as_f64)
+                        .map_or(0, |time| time.max(0.0).round() as u64),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        MpvLaunch, PlayRequest, PlaybackChapter, PlaybackTrack, SleepTimer, TrackKind,
+        VideoRequest, VideoSource, faded_volume, load_options, parse_chapters, parse_tracks,
+        step_speed,
+    };
+    use crate::{channel_settings::ChannelSettings, video::PlaybackSpec};
+    use std::{path::PathBuf, time::Duration};
+    use tokio::time::Instant;
+
+    #[test]
+    fn speed_is_stepped_within_bounds() {
+        assert_eq!(step_speed(1.0, 0.25), 1.25);
+        assert_eq!(step_speed(1.1, 0.1), 1.2);
+        assert_eq!(step_speed(0.3, -0.25), 0.25);
+        assert_eq!(step_speed(3.9, 0.25), 4.0);
+    }
+
+    #[test]
+    fn settings_of_a_video_do_not_carry_over_in_an_idle_session() {
+        let request = |source, speed, subtitle_language: Option<&str>| VideoRequest {
+            spec: PlaybackSpec {
+                channel_settings: ChannelSettings {
+                    speed,
+                    subtitle_language: subtitle_language.map(str::to_owned),
+                    ..ChannelSettings::default()
+                },
+                ..PlaybackSpec::default()
+            },
+            source,
+        };
+
+        let first = request(
+            VideoSource::File(PathBuf::from("/downloads/video.mp4")),
+            Some(1.5),
+            Some("off"),
+        );
+        let second = request(
+            VideoSource::YtDlp("https://youtu.be/id".to_owned()),
+            None,
+            None,
+        );
+
+        // The session is launched for the first video and kept idle for the
+        // second one, so nothing of the first video is set on the command line.
+        assert!(MpvLaunch::from_video(&first).extra_args.is_empty());
+
+        let options = load_options(&PlayRequest::Video(first));
+        assert_eq!(options["speed"], "1.5");
+        assert_eq!(options["sid"], "no");
+        assert_eq!(options["audio-file-auto"], "fuzzy");
+
+        // mpv restores the options of the first file when it ends.
+        let options = load_options(&PlayRequest::Video(second));
+        assert_eq!(options["ytdl"], "yes");
+        for option in ["speed", "sid", "slang", "audio-file-auto"] {
+            assert!(!options.contains_key(option));
+        }
+
+        let third = request(
+            VideoSource::YtDlp("https://youtu.be/id".to_owned()),
+            Some(2.0),
+            Some("de"),
+        );
+        let options = load_options(&PlayRequest::Video(third));
+        assert_eq!(options["speed"], "2");
+        assert_eq!(options["slang"], "de");
+    }
+
+    #[test]
+    fn sleep_timer_presets_are_cycled() {
+        let now = Instant::now();
+        let minutes = |timer: Option<SleepTimer>| match timer {
+            Some(SleepTimer::Minutes(minutes, deadline)) => {
+                assert_eq!(deadline, now + Duration::from_secs(minutes * 60));
+                Some(minutes)
+            }
+            _ => None,
+        };
+
+        let timer = SleepTimer::next(None, now);
+        assert_eq!(minutes(timer), Some(15));
+        let timer = SleepTimer::next(timer, now);
+        assert_eq!(minutes(timer), Some(30));
+        let timer = SleepTimer::next(timer, now);
+        assert_eq!(minutes(timer), Some(60));
+        let timer = SleepTimer::next(timer, now);
+        assert_eq!(timer, Some(SleepTimer::EndOfVideo));
+        assert_eq!(SleepTimer::next(timer, now), None);
+    }
+
+    #[test]
+    fn volume_fades_out_over_the_last_seconds() {
+        assert_eq!(faded_volume(80.0, Duration::from_secs(60)), 80.0);
+        assert_eq!(faded_volume(80.0, Duration::from_secs(15)), 40.0);
+        assert_eq!(faded_volume(80.0, Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn chapter_list_is_parsed() {
+        let chapters = parse_chapters(&serde_json::json!([
+            { "title": "Intro", "time": 0.0 },
+            { "title": "Setup", "time": 94.6 },
+            { "time": 300 }
+        ]));
+
+        assert_eq!(
+            chapters,
+            [
+                PlaybackChapter {
+                    title: "Intro".to_owned(),
+                    start: 0
+                },
+                PlaybackChapter {
+                    title: "Setup".to_owned(),
+                    start: 95
+                },
+                PlaybackChapter {
+                    title: String::new(),
+                    start: 300
+                },
+            ]
+        );
+        assert_eq!(chapters[1].to_string(), "    1:35  Setup");
+    }
+
+    #[test]
+    fn audio_and_subtitle_tracks_are_parsed() {
+        let tracks = parse_tracks(&serde_json::json!([
+            { "id": 1, "type": "video", "codec": "vp9", "selected": true },
+            { "id": 1, "type": "audio", "lang": "en", "codec": "opus", "selected": true },
+            { "id": 1, "type": "sub", "title": "English", "lang": "en", "selected": false },
+            { "id": 2, "type": "sub" }
+        ]));
+
+        assert_eq!(
+            tracks,
+            [
+                PlaybackTrack {
+                    id: "1".to_owned(),
+                    kind: TrackKind::Audio,
+                    title: None,
+                    language: Some("en".to_owned()),
+                    codec: Some("opus".to_owned()),
+                    selected: true,
+                },
+                PlaybackTrack {
+                    id: "1".to_owned(),
+                    kind: TrackKind::Subtitle,
+                    title: Some("English".to_owned()),
+                    language: Some("en".to_owned()),
+                    codec: None,
+                    selected: false,
+                },
+                PlaybackTrack {
+                    id: "2".to_owned(),
+                    kind: TrackKind::Subtitle,
+                    title: None,
+                    language: None,
+                    codec: None,
+                    selected: false,
+                },
+            ]
+        );
+        assert_eq!(
+            tracks.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["en, opus", "English (en)", "Track 2"]
+        );
+    }
 }
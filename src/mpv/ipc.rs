@@ -91,16 +91,22 @@ impl MpvIpc {
         reply_rx.await.context("mpv IPC connection closed")?
     }
 
-    pub async fn load_file(&self, file: &str, start: Option<u64>) -> Result<i64> {
+    /// Loads the file with `options` that only apply to it, so a reused audio
+    /// session returns to the configured options for the next file.
+    pub async fn load_file(
+        &self,
+        file: &str,
+        options: serde_json::Map<String, Value>,
+    ) -> Result<i64> {
         let mut command = serde_json::json!(["loadfile", file, "replace"]);
 
-        if let Some(position) = start {
+        if !options.is_empty() {
             let args = command
                 .as_array_mut()
                 .context("loadfile command was not an array")?;
 
             args.push(serde_json::json!(-1));
-            args.push(serde_json::json!({ "start": position.to_string() }));
+            args.push(Value::Object(options));
         }
 
         let data = self.call(command).await?;
@@ -1,12 +1,16 @@
 use self::ipc::{MpvIpc, MpvNotification};
 use crate::CONFIG;
+use crate::config::AutoplayScope;
 use crate::process::detach_process;
 use crate::video::PlaybackSpec;
 use anyhow::{Context, Result, bail};
 pub use controller::{
-    PlaybackEndReason, PlaybackPhase, PlaybackState, PlaybackUpdate, PlaybackUpdateCause,
-    PlayerHandle,
+    MAX_SPEED, MIN_SPEED, PlaybackChapter, PlaybackEndReason, PlaybackPhase, PlaybackState,
+    PlaybackTrack, PlaybackUpdate, PlaybackUpdateCause, PlayerHandle, SleepTimer, TrackKind,
 };
+#[cfg(target_os = "linux")]
+pub use mpris::Mpris;
+use std::borrow::Cow;
 use std::ffi::OsString;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::{path::PathBuf, time::Duration};
@@ -18,6 +22,8 @@ use tokio::{
 
 mod controller;
 mod ipc;
+#[cfg(target_os = "linux")]
+mod mpris;
 
 static SESSION_ID: AtomicU64 = AtomicU64::new(1);
 
@@ -29,6 +35,7 @@ pub enum PlaybackKind {
 
 pub enum VideoSource {
     YtDlp(String),
+    File(PathBuf),
     Direct {
         video_url: String,
         audio_url: Option<String>,
@@ -43,10 +50,11 @@ pub struct VideoRequest {
 }
 
 impl VideoRequest {
-    fn source(&self) -> &str {
+    fn source(&self) -> Cow<'_, str> {
         match &self.source {
-            VideoSource::YtDlp(source) => source,
-            VideoSource::Direct { video_url, .. } => video_url,
+            VideoSource::YtDlp(source) => source.into(),
+            VideoSource::File(path) => path.to_string_lossy(),
+            VideoSource::Direct { video_url, .. } => video_url.into(),
         }
     }
 }
@@ -58,44 +66,73 @@ struct MpvLaunch {
 }
 
 impl MpvLaunch {
+    /// Only the streams of stream formats are passed on the command line. The
+    /// rest of the options are set when the file is loaded, so that they don't
+    /// carry over to the next video of a session kept idle for autoplay.
     fn from_video(request: &VideoRequest) -> Self {
         let mut args = Vec::<OsString>::new();
 
-        let uses_ytdlp = match &request.source {
-            VideoSource::YtDlp(_) => true,
-            VideoSource::Direct {
-                audio_url,
-                captions,
-                chapters,
-                ..
-            } => {
-                args.push("--no-ytdl".into());
-                args.push(format!("--force-media-title={}", request.spec.metadata.title).into());
-
-                if let Some(url) = audio_url {
-                    args.push(format!("--audio-file={url}").into());
-                }
-
-                for caption in captions {
-                    args.push(format!("--sub-file={caption}").into());
-                }
+        if let VideoSource::Direct {
+            audio_url,
+            captions,
+            chapters,
+            ..
+        } = &request.source
+        {
+            args.push("--no-ytdl".into());
+            args.push(format!("--force-media-title={}", request.spec.metadata.title).into());
+
+            if let Some(url) = audio_url {
+                args.push(format!("--audio-file={url}").into());
+            }
 
-                if let Some(chapters) = chapters {
-                    args.push(format!("--chapters-file={}", chapters.display()).into());
-                }
+            for caption in captions {
+                args.push(format!("--sub-file={caption}").into());
+            }
 
-                false
+            if let Some(chapters) = chapters {
+                args.push(format!("--chapters-file={}", chapters.display()).into());
             }
-        };
+        }
 
         Self {
             kind: PlaybackKind::Video,
-            uses_ytdlp,
+            uses_ytdlp: matches!(request.source, VideoSource::YtDlp(_)),
             extra_args: args,
         }
     }
 }
 
+/// Returns the options that are set when the file is loaded in a session with
+/// IPC, as command line options.
+fn file_args(request: &VideoRequest) -> Vec<OsString> {
+    let mut args = Vec::<OsString>::new();
+
+    if let VideoSource::File(path) = &request.source {
+        args.push(format!("--force-media-title={}", request.spec.metadata.title).into());
+        // Streams downloaded without muxing keep the audio in a separate file
+        // next to the video.
+        args.push("--audio-file-auto=fuzzy".into());
+
+        let chapters = path.with_extension("ffmetadata");
+        if chapters.exists() {
+            args.push(format!("--chapters-file={}", chapters.display()).into());
+        }
+    }
+
+    if let Some(speed) = request.spec.channel_settings.speed {
+        args.push(format!("--speed={speed}").into());
+    }
+
+    match request.spec.channel_settings.subtitle_language.as_deref() {
+        Some("off") => args.push("--sid=no".into()),
+        Some(language) => args.push(format!("--slang={language}").into()),
+        None => (),
+    }
+
+    args
+}
+
 fn configure_proxy(command: &mut Command, uses_ytdlp: bool) {
     let Some(proxy) = CONFIG.mpv_proxy.as_deref() else {
         return;
@@ -108,12 +145,33 @@ fn configure_proxy(command: &mut Command, uses_ytdlp: bool) {
     }
 }
 
+fn configure_cookies(command: &mut Command, uses_ytdlp: bool) {
+    let Some(cookies) = CONFIG.cookies.as_deref() else {
+        return;
+    };
+
+    command
+        .arg("--cookies")
+        .arg(format!("--cookies-file={}", cookies.display()));
+
+    if uses_ytdlp {
+        command.arg(format!(
+            "--ytdl-raw-options-append=cookies={}",
+            cookies.display()
+        ));
+    }
+}
+
 pub fn video_command_without_ipc(request: &VideoRequest) -> Command {
     let launch = MpvLaunch::from_video(request);
     let mut command = Command::new(&CONFIG.mpv_path);
 
     configure_proxy(&mut command, launch.uses_ytdlp);
-    command.args(launch.extra_args).arg(request.source());
+    configure_cookies(&mut command, launch.uses_ytdlp);
+    command
+        .args(launch.extra_args)
+        .args(file_args(request))
+        .arg(request.source().as_ref());
 
     command
 }
@@ -161,13 +219,20 @@ impl MpvSession {
             .arg("--no-terminal")
             .arg(format!("--input-ipc-server={}", endpoint.display()));
         configure_proxy(&mut command, uses_ytdlp);
+        configure_cookies(&mut command, uses_ytdlp);
 
         match kind {
             PlaybackKind::Audio => {
                 command.arg("--idle=yes").arg("--vid=no");
             }
             PlaybackKind::Video => {
-                command.arg("--idle=once");
+                // With autoplay, the window stays open for the next video.
+                if CONFIG.autoplay == AutoplayScope::Off {
+                    command.arg("--idle=once");
+                } else {
+                    command.arg("--idle=yes").arg("--force-window=yes");
+                }
+
                 detach_process(&mut command);
             }
         }
@@ -200,6 +265,10 @@ impl MpvSession {
         ipc.observe_property("duration").await?;
         ipc.observe_property("volume").await?;
         ipc.observe_property("mute").await?;
+        ipc.observe_property("speed").await?;
+        ipc.observe_property("chapter-list").await?;
+        ipc.observe_property("chapter").await?;
+        ipc.observe_property("track-list").await?;
 
         Ok(Self {
             kind,
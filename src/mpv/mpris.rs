@@ -0,0 +1,588 @@
+//! MPRIS interface of the mpv player. There is no track list, so `Next` and
+//! `Previous` jump between the chapters of the current video instead of
+//! changing videos. `CanGoNext` and `CanGoPrevious` are false when the video
+//! has no chapters.
+
+use super::{PlaybackPhase, PlaybackState, PlaybackUpdateCause, PlayerHandle};
+use anyhow::Result;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use zbus::{
+    connection::Builder,
+    fdo,
+    object_server::{InterfaceRef, SignalEmitter},
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value},
+};
+
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.ytsub";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+const MIN_RATE: f64 = 0.25;
+const MAX_RATE: f64 = 4.0;
+
+/// The `org.mpris.MediaPlayer2` interface.
+struct MediaPlayer;
+
+#[zbus::interface(name = "org.mpris.MediaPlayer2")]
+impl MediaPlayer {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> &str {
+        "ytsub"
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// The `org.mpris.MediaPlayer2.Player` interface that forwards the calls to
+/// the player and reports the last published playback state.
+struct MediaPlayerPlayer {
+    player: PlayerHandle,
+    state: PlaybackState,
+}
+
+impl MediaPlayerPlayer {
+    fn track_id(&self) -> OwnedObjectPath {
+        let path = match &self.state.metadata {
+            Some(metadata) if self.state.is_loaded() => {
+                format!(
+                    "/org/ytsub/track/{}",
+                    escape_path_element(&metadata.video_id)
+                )
+            }
+            _ => NO_TRACK.to_owned(),
+        };
+
+        ObjectPath::try_from(path)
+            .map(OwnedObjectPath::from)
+            .unwrap_or_else(|_| {
+                OwnedObjectPath::from(ObjectPath::from_static_str_unchecked(NO_TRACK))
+            })
+    }
+}
+
+fn failed(error: anyhow::Error) -> fdo::Error {
+    fdo::Error::Failed(error.to_string())
+}
+
+#[zbus::interface(name = "org.mpris.MediaPlayer2.Player")]
+impl MediaPlayerPlayer {
+    fn next(&self) -> fdo::Result<()> {
+        self.player.adjust_chapter(1).map_err(failed)
+    }
+
+    fn previous(&self) -> fdo::Result<()> {
+        self.player.adjust_chapter(-1).map_err(failed)
+    }
+
+    fn pause(&self) -> fdo::Result<()> {
+        self.player.set_pause(true).map_err(failed)
+    }
+
+    fn play_pause(&self) -> fdo::Result<()> {
+        self.player.toggle().map_err(failed)
+    }
+
+    fn stop(&self) -> fdo::Result<()> {
+        self.player.stop().map_err(failed)
+    }
+
+    fn play(&self) -> fdo::Result<()> {
+        self.player.set_pause(false).map_err(failed)
+    }
+
+    /// Seeks by `offset` microseconds.
+    fn seek(&self, offset: i64) -> fdo::Result<()> {
+        let seconds = (offset as f64 / 1_000_000.0).round() as i32;
+
+        if seconds == 0 {
+            return Ok(());
+        }
+
+        self.player.seek_relative(seconds).map_err(failed)
+    }
+
+    /// Seeks to `position` microseconds if `track_id` is still the current
+    /// track.
+    fn set_position(&self, track_id: ObjectPath<'_>, position: i64) -> fdo::Result<()> {
+        if track_id.as_str() != self.track_id().as_str() || position < 0 {
+            return Ok(());
+        }
+
+        let seconds = position as u64 / 1_000_000;
+
+        if self
+            .state
+            .duration
+            .is_some_and(|duration| seconds > duration)
+        {
+            return Ok(());
+        }
+
+        self.player.seek_to(seconds).map_err(failed)
+    }
+
+    fn open_uri(&self, _uri: &str) -> fdo::Result<()> {
+        Err(fdo::Error::NotSupported(
+            "ytsub cannot open URIs".to_owned(),
+        ))
+    }
+
+    #[zbus(signal)]
+    async fn seeked(emitter: &SignalEmitter<'_>, position: i64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> &str {
+        playback_status(&self.state)
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        self.state.speed.unwrap_or(1.0)
+    }
+
+    #[zbus(property)]
+    fn set_rate(&mut self, rate: f64) -> fdo::Result<()> {
+        if rate <= 0.0 {
+            return self.player.set_pause(true).map_err(failed);
+        }
+
+        self.player.set_speed(rate).map_err(failed)
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        let mut metadata = HashMap::new();
+        let mut insert = |key: &str, value: Value<'_>| {
+            if let Ok(value) = value.try_to_owned() {
+                metadata.insert(key.to_owned(), value);
+            }
+        };
+
+        insert("mpris:trackid", Value::from(self.track_id()));
+
+        let Some(video) = self
+            .state
+            .metadata
+            .as_ref()
+            .filter(|_| self.state.is_loaded())
+        else {
+            return metadata;
+        };
+
+        if let Some(duration) = self.state.duration {
+            insert("mpris:length", Value::from(duration as i64 * 1_000_000));
+        }
+
+        insert("xesam:title", Value::from(video.title.as_str()));
+        insert("xesam:artist", Value::from(vec![video.channel.as_str()]));
+        insert(
+            "xesam:url",
+            Value::from(format!(
+                "https://www.youtube.com/watch?v={}",
+                video.video_id
+            )),
+        );
+        insert(
+            "mpris:artUrl",
+            Value::from(format!(
+                "https://i.ytimg.com/vi/{}/hqdefault.jpg",
+                video.video_id
+            )),
+        );
+
+        metadata
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        if self.state.muted == Some(true) {
+            return 0.0;
+        }
+
+        self.state
+            .volume
+            .map_or(1.0, |volume| volume as f64 / 100.0)
+    }
+
+    #[zbus(property)]
+    fn set_volume(&mut self, volume: f64) -> fdo::Result<()> {
+        self.player
+            .set_volume((volume.max(0.0) * 100.0).round())
+            .map_err(failed)
+    }
+
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> i64 {
+        self.state.elapsed.unwrap_or_default() as i64 * 1_000_000
+    }
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        MIN_RATE
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        MAX_RATE
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        self.state.is_loaded() && !self.state.chapters.is_empty()
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        self.can_go_next()
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        self.state.is_loaded()
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        self.state.is_loaded()
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        self.state.is_loaded()
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+fn playback_status(state: &PlaybackState) -> &'static str {
+    match state.phase {
+        PlaybackPhase::Loading | PlaybackPhase::Playing => "Playing",
+        PlaybackPhase::Paused => "Paused",
+        PlaybackPhase::Idle | PlaybackPhase::Error(_) => "Stopped",
+    }
+}
+
+/// Escapes `element` so it can be used as an element of an object path,
+/// which only allows ASCII letters, digits and underscores.
+fn escape_path_element(element: &str) -> String {
+    element
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() {
+                (byte as char).to_string()
+            } else {
+                format!("_{byte:02x}")
+            }
+        })
+        .collect()
+}
+
+/// Publishes the playback state to the MPRIS player registered on the session
+/// bus.
+pub struct Mpris {
+    update_tx: mpsc::UnboundedSender<(PlaybackState, bool)>,
+}
+
+impl Mpris {
+    pub async fn start(player: PlayerHandle) -> Result<Self> {
+        Self::start_on(Builder::session()?, player).await
+    }
+
+    async fn start_on(builder: Builder<'_>, player: PlayerHandle) -> Result<Self> {
+        let connection = builder
+            .serve_at(OBJECT_PATH, MediaPlayer)?
+            .serve_at(
+                OBJECT_PATH,
+                MediaPlayerPlayer {
+                    player,
+                    state: PlaybackState::default(),
+                },
+            )?
+            .build()
+            .await?;
+
+        // Another instance may own the name already.
+        if connection.request_name(BUS_NAME).await.is_err() {
+            connection
+                .request_name(format!("{BUS_NAME}.instance{}", std::process::id()))
+                .await?;
+        }
+
+        let interface = connection
+            .object_server()
+            .interface::<_, MediaPlayerPlayer>(OBJECT_PATH)
+            .await?;
+
+        let (update_tx, mut update_rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            // Keep the connection alive as long as the updates are sent.
+            let _connection = connection;
+
+            while let Some((state, seeked)) = update_rx.recv().await {
+                let _ = publish(&interface, state, seeked).await;
+            }
+        });
+
+        Ok(Self { update_tx })
+    }
+
+    pub fn update(&self, state: &PlaybackState, cause: &PlaybackUpdateCause) {
+        let seeked = matches!(cause, PlaybackUpdateCause::Seeked);
+        let _ = self.update_tx.send((state.clone(), seeked));
+    }
+}
+
+/// Replaces the state of the interface and signals the properties that
+/// changed.
+async fn publish(
+    interface: &InterfaceRef<MediaPlayerPlayer>,
+    state: PlaybackState,
+    seeked: bool,
+) -> zbus::Result<()> {
+    let emitter = interface.signal_emitter();
+    let mut player = interface.get_mut().await;
+
+    let previous = std::mem::replace(&mut player.state, state);
+    let current = &player.state;
+
+    if playback_status(&previous) != playback_status(current) {
+        player.playback_status_changed(emitter).await?;
+    }
+
+    if previous.is_loaded() != current.is_loaded() {
+        player.can_play_changed(emitter).await?;
+        player.can_pause_changed(emitter).await?;
+        player.can_seek_changed(emitter).await?;
+    }
+
+    if previous.is_loaded() != current.is_loaded()
+        || previous.duration != current.duration
+        || previous.metadata.as_ref().map(|video| &video.video_id)
+            != current.metadata.as_ref().map(|video| &video.video_id)
+    {
+        player.metadata_changed(emitter).await?;
+    }
+
+    if previous.volume != current.volume || previous.muted != current.muted {
+        player.volume_changed(emitter).await?;
+    }
+
+    if previous.speed != current.speed {
+        player.rate_changed(emitter).await?;
+    }
+
+    if previous.is_loaded() != current.is_loaded()
+        || previous.chapters.is_empty() != current.chapters.is_empty()
+    {
+        player.can_go_next_changed(emitter).await?;
+        player.can_go_previous_changed(emitter).await?;
+    }
+
+    if seeked {
+        MediaPlayerPlayer::seeked(emitter, player.position()).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BUS_NAME, Mpris, OBJECT_PATH, escape_path_element};
+    use crate::mpv::{
+        PlaybackPhase, PlaybackState, PlaybackUpdateCause, PlayerHandle, controller::PlayerCommand,
+    };
+    use crate::video::VideoMetadata;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use std::time::Duration;
+    use zbus::{connection::Builder, fdo::PropertiesProxy, names::InterfaceName, zvariant::Value};
+
+    struct Bus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl Drop for Bus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    /// Starts a private session bus. `dbus-daemon` has to be installed to run
+    /// the tests.
+    fn private_bus() -> Bus {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--print-address", "--nofork"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("couldn't start dbus-daemon, which the MPRIS tests need");
+
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap())
+            .read_line(&mut address)
+            .unwrap();
+
+        Bus {
+            daemon,
+            address: address.trim().to_owned(),
+        }
+    }
+
+    fn playing_state() -> PlaybackState {
+        PlaybackState {
+            metadata: Some(VideoMetadata {
+                video_id: "a-b_c".to_owned(),
+                channel_id: "channel".to_owned(),
+                title: "Title".to_owned(),
+                channel: "Channel".to_owned(),
+            }),
+            phase: PlaybackPhase::Playing,
+            elapsed: Some(10),
+            duration: Some(60),
+            ..PlaybackState::default()
+        }
+    }
+
+    #[test]
+    fn path_elements_are_escaped() {
+        assert_eq!(escape_path_element("a-b_c9"), "a_2db_5fc9");
+    }
+
+    #[tokio::test]
+    async fn player_is_controlled_over_the_session_bus() {
+        let bus = private_bus();
+
+        let (player, mut commands) = PlayerHandle::detached();
+        let mpris = Mpris::start_on(Builder::address(bus.address.as_str()).unwrap(), player)
+            .await
+            .unwrap();
+        mpris.update(&playing_state(), &PlaybackUpdateCause::Loaded);
+
+        let client = Builder::address(bus.address.as_str())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let properties = PropertiesProxy::builder(&client)
+            .destination(BUS_NAME)
+            .unwrap()
+            .path(OBJECT_PATH)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let interface = InterfaceName::from_static_str("org.mpris.MediaPlayer2.Player").unwrap();
+
+        // The update is published asynchronously.
+        let mut status = String::new();
+        for _ in 0..50 {
+            status = properties
+                .get(interface.clone(), "PlaybackStatus")
+                .await
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+            if status == "Playing" {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(status, "Playing");
+
+        let metadata = properties.get(interface.clone(), "Metadata").await.unwrap();
+        let Value::Dict(metadata) = &*metadata else {
+            panic!("metadata is not a dictionary");
+        };
+        let title: Option<String> = metadata.get(&"xesam:title").unwrap();
+        assert_eq!(title.as_deref(), Some("Title"));
+
+        let call = |method: &'static str| {
+            let client = client.clone();
+            async move {
+                client
+                    .call_method(
+                        Some(BUS_NAME),
+                        OBJECT_PATH,
+                        Some("org.mpris.MediaPlayer2.Player"),
+                        method,
+                        &(),
+                    )
+                    .await
+                    .unwrap();
+            }
+        };
+
+        call("PlayPause").await;
+        assert!(matches!(commands.recv().await, Some(PlayerCommand::Toggle)));
+
+        call("Pause").await;
+        assert!(matches!(
+            commands.recv().await,
+            Some(PlayerCommand::SetPause(true))
+        ));
+
+        call("Next").await;
+        assert!(matches!(
+            commands.recv().await,
+            Some(PlayerCommand::AdjustChapter(1))
+        ));
+
+        client
+            .call_method(
+                Some(BUS_NAME),
+                OBJECT_PATH,
+                Some("org.mpris.MediaPlayer2.Player"),
+                "Seek",
+                &(-5_000_000_i64),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            commands.recv().await,
+            Some(PlayerCommand::Seek(-5))
+        ));
+
+        properties
+            .set(interface.clone(), "Volume", Value::from(0.5))
+            .await
+            .unwrap();
+        assert!(
+            matches!(commands.recv().await, Some(PlayerCommand::SetVolume(volume)) if volume == 50.0)
+        );
+    }
+}
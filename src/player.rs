@@ -2,9 +2,12 @@ use crate::TX;
 use crate::api::ApiBackend;
 use crate::client::{Client, ClientRequest};
 use crate::clipboard::{CopyStatus, copy_to_clipboard};
+use crate::config::players::{Player, PlayerInput, PlayerInterface, PlayerProfile};
 use crate::mpv::{PlayerHandle, VideoRequest, VideoSource};
 use crate::process::run_detached;
-use crate::{CONFIG, api::Api, app::VideoPlayer, emit_msg, stream_formats::Formats};
+use crate::video::{PlaybackSpec, VideoMetadata};
+use crate::vlc::{self, VlcInterface};
+use crate::{CONFIG, api::Api, emit_msg, stream_formats::Formats};
 use anyhow::Result;
 use tokio::process::Command;
 
@@ -19,6 +22,11 @@ pub async fn play_from_formats(
     };
     let metadata = &formats.spec.metadata;
 
+    let Some(video_player) = CONFIG.player(&CONFIG.video_player_for_stream_formats) else {
+        emit_msg!(error, "Unknown video player for stream formats");
+        return Ok(());
+    };
+
     let captions = instance.get_caption_paths(&formats).await;
 
     let chapters = formats
@@ -28,8 +36,8 @@ pub async fn play_from_formats(
 
     emit_msg!("Launching video player");
 
-    match CONFIG.video_player_for_stream_formats {
-        VideoPlayer::Mpv => {
+    match video_player {
+        Player::Mpv => {
             let request = VideoRequest {
                 source: VideoSource::Direct {
                     video_url: video_url.to_owned(),
@@ -40,43 +48,129 @@ pub async fn play_from_formats(
                 spec: formats.spec,
             };
 
-            if CONFIG.mpv_video_ipc {
-                match player.play_video(request) {
-                    Ok(()) => emit_msg!(),
-                    Err(error) => emit_msg!(error, error.to_string()),
-                }
-            } else {
-                play_mpv_without_ipc(request).await?;
-            }
+            play_mpv(player, request).await
+        }
+        Player::Profile(profile) => {
+            let input = PlayerInput {
+                video_url,
+                audio_url,
+                captions: &captions,
+                chapters: chapters.as_deref(),
+                title: &metadata.title,
+                start: formats.spec.start_position,
+                speed: formats.spec.channel_settings.speed,
+            };
 
-            Ok(())
+            play_with_profile(&profile, &input, metadata.clone()).await
         }
-        VideoPlayer::Vlc => {
-            let mut command = Command::new(&CONFIG.vlc_path);
-            command
-                .arg("--no-video-title-show")
-                .arg(format!("--input-title-format={}", metadata.title))
-                .arg("--play-and-exit")
-                .arg(video_url);
-
-            if let Some(audio_url) = audio_url {
-                command.arg(format!("--input-slave={audio_url}"));
-            }
+    }
+}
 
-            if !captions.is_empty() {
-                command.arg(format!("--sub-file={}", captions.join(" ")));
-            }
+/// Plays the video from its YouTube URL, or from the local file if it is
+/// downloaded.
+pub async fn play_using_ytdlp(player: PlayerHandle, spec: PlaybackSpec) -> Result<()> {
+    let Some(video_player) = CONFIG.player(&CONFIG.video_player_for_ytdlp) else {
+        emit_msg!(error, "Unknown video player for yt-dlp");
+        return Ok(());
+    };
 
-            play_video(command, &metadata.video_id).await
+    match video_player {
+        Player::Mpv => play_mpv(player, ytdlp_request(spec)).await,
+        Player::Profile(profile) => {
+            let video_url = match &spec.local_file {
+                Some(path) => path.to_string_lossy().into_owned(),
+                None => youtube_watch_url(&spec.metadata.video_id),
+            };
+
+            let input = PlayerInput {
+                video_url: &video_url,
+                audio_url: None,
+                captions: &[],
+                chapters: None,
+                title: &spec.metadata.title,
+                start: spec.start_position,
+                speed: spec.channel_settings.speed,
+            };
+
+            emit_msg!("Launching video player");
+            play_with_profile(&profile, &input, spec.metadata.clone()).await
         }
     }
 }
 
+/// Plays the next video in the mpv session kept idle for autoplay.
+pub fn autoplay_video(player: &PlayerHandle, spec: PlaybackSpec) -> Result<()> {
+    if let Err(error) = player.play_video(ytdlp_request(spec)) {
+        emit_msg!(error, error.to_string());
+    }
+
+    Ok(())
+}
+
+fn ytdlp_request(spec: PlaybackSpec) -> VideoRequest {
+    let source = match &spec.local_file {
+        Some(path) => VideoSource::File(path.clone()),
+        None => VideoSource::YtDlp(youtube_watch_url(&spec.metadata.video_id)),
+    };
+
+    VideoRequest { spec, source }
+}
+
+async fn play_mpv(player: PlayerHandle, request: VideoRequest) -> Result<()> {
+    if CONFIG.mpv_video_ipc {
+        match player.play_video(request) {
+            Ok(()) => emit_msg!(),
+            Err(error) => emit_msg!(error, error.to_string()),
+        }
+
+        Ok(())
+    } else {
+        emit_msg!("Launching video player");
+        play_mpv_without_ipc(request).await
+    }
+}
+
+async fn play_with_profile(
+    profile: &PlayerProfile,
+    input: &PlayerInput<'_>,
+    metadata: VideoMetadata,
+) -> Result<()> {
+    let mut args = profile.build_command(input);
+
+    if args.is_empty() {
+        emit_msg!(error, "The command of the video player is empty");
+        return Ok(());
+    }
+
+    let interface = match profile.interface {
+        Some(PlayerInterface::VlcHttp) => match VlcInterface::new() {
+            Ok(interface) => Some(interface),
+            Err(error) => {
+                emit_msg!(warning, format!("Can't track the progress: {error}"));
+                None
+            }
+        },
+        None => None,
+    };
+
+    if let Some(interface) = &interface {
+        args.splice(1..1, interface.args());
+    }
+
+    let mut command = Command::new(&args[0]);
+    command.args(&args[1..]);
+
+    match interface {
+        Some(interface) => vlc::play_and_track(command, interface, metadata).await,
+        None => play_video(command, &metadata.video_id).await,
+    }
+}
+
 pub fn youtube_watch_url(video_id: &str) -> String {
     format!("https://www.youtube.com/watch?v={video_id}")
 }
 
-pub async fn play_mpv_without_ipc(request: VideoRequest) -> Result<()> {
+async fn play_mpv_without_ipc(request: VideoRequest) -> Result<()> {
     let video_id = request.spec.metadata.video_id.clone();
     let command = crate::mpv::video_command_without_ipc(&request);
 
@@ -0,0 +1,312 @@
+use crate::{database, utils};
+use anyhow::{Result, ensure};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+const STATE_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Videos watched on either side stay watched. Positions follow the
+    /// newer state.
+    WatchedWins,
+    /// The newer state replaces the older one.
+    Newest,
+    /// The imported state replaces the local one.
+    Overwrite,
+}
+
+impl From<&str> for MergeStrategy {
+    fn from(strategy: &str) -> Self {
+        match strategy {
+            "newest" => Self::Newest,
+            "overwrite" => Self::Overwrite,
+            _ => Self::WatchedWins,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VideoState {
+    pub video_id: String,
+    pub watched: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct TagState {
+    name: String,
+    channels: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    version: u32,
+    exported_at: u64,
+    videos: Vec<VideoState>,
+    tags: Vec<TagState>,
+}
+
+pub struct ImportSummary {
+    pub videos: usize,
+    pub tags: usize,
+    /// Tag members that were skipped because the channel isn't subscribed.
+    pub skipped_channels: usize,
+}
+
+/// Writes the watched state, playback positions and tags to a JSON file.
+/// Returns the number of exported videos and tags.
+pub fn export(conn: &Connection, path: &Path) -> Result<(usize, usize)> {
+    let mut tags: BTreeMap<String, Vec<String>> = database::get_tags(conn)?
+        .into_iter()
+        .map(|tag| (tag, Vec::new()))
+        .collect();
+
+    for (tag, channel_id) in database::get_tag_relations(conn)? {
+        tags.entry(tag).or_default().push(channel_id);
+    }
+
+    let state = State {
+        version: STATE_VERSION,
+        exported_at: utils::now()?,
+        videos: database::get_video_states(conn)?,
+        tags: tags
+            .into_iter()
+            .map(|(name, channels)| TagState { name, channels })
+            .collect(),
+    };
+
+    serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), &state)?;
+
+    Ok((state.videos.len(), state.tags.len()))
+}
+
+pub fn import(
+    conn: &mut Connection,
+    path: &Path,
+    strategy: MergeStrategy,
+) -> Result<ImportSummary> {
+    let state: State = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+
+    ensure!(
+        state.version <= STATE_VERSION,
+        "state file version {} is newer than the latest version supported by this ytsub build ({STATE_VERSION})",
+        state.version
+    );
+
+    // a failure part way through leaves the database as it was
+    let tx = conn.transaction()?;
+
+    database::merge_video_states(&tx, &state.videos, strategy)?;
+
+    let existing_tags: HashSet<String> = database::get_tags(&tx)?.into_iter().collect();
+    let subscriptions: HashSet<String> = database::get_channels(&tx, &[])?
+        .into_iter()
+        .map(|channel| channel.channel_id)
+        .collect();
+    let mut skipped_channels = 0;
+
+    for tag in &state.tags {
+        if !existing_tags.contains(&tag.name) {
+            database::create_tag(&tx, &tag.name)?;
+        }
+
+        let (channel_ids, skipped): (Vec<String>, Vec<String>) = tag
+            .channels
+            .iter()
+            .cloned()
+            .partition(|channel_id| subscriptions.contains(channel_id));
+        skipped_channels += skipped.len();
+
+        if strategy == MergeStrategy::Overwrite {
+            database::update_channels_of_tag(&tx, &tag.name, &channel_ids)?;
+        } else {
+            for channel_id in &channel_ids {
+                database::add_channel_to_tag(&tx, &tag.name, channel_id)?;
+            }
+        }
+    }
+
+    tx.commit()?;
+
+    Ok(ImportSummary {
+        videos: state.videos.len(),
+        tags: state.tags.len(),
+        skipped_channels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MergeStrategy, VideoState, export, import};
+    use crate::{channel::Channel, database};
+    use anyhow::Result;
+    use rusqlite::Connection;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    const CHANNEL_ID: &str = "test-channel";
+
+    fn video_state(
+        video_id: &str,
+        watched: bool,
+        position: Option<u64>,
+        updated_at: u64,
+    ) -> VideoState {
+        VideoState {
+            video_id: video_id.to_owned(),
+            watched,
+            position,
+            updated_at: Some(updated_at),
+        }
+    }
+
+    fn create_database(path: &Path, states: &[VideoState]) -> Result<Connection> {
+        let conn = database::open_db(path)?;
+
+        database::create_channel(
+            &conn,
+            &Channel::new(CHANNEL_ID.to_owned(), "Test".to_owned(), None),
+        )?;
+        database::merge_video_states(&conn, states, MergeStrategy::Overwrite)?;
+
+        Ok(conn)
+    }
+
+    fn merge(strategy: MergeStrategy) -> Result<Vec<VideoState>> {
+        let directory = tempdir()?;
+        let state_path = directory.path().join("state.json");
+
+        let source = create_database(
+            &directory.path().join("source.db"),
+            &[
+                video_state("older-locally", false, Some(30), 200),
+                video_state("newer-locally", false, None, 100),
+                video_state("only-in-source", true, None, 100),
+            ],
+        )?;
+        export(&source, &state_path)?;
+
+        let mut target = create_database(
+            &directory.path().join("target.db"),
+            &[
+                video_state("older-locally", true, Some(10), 100),
+                video_state("newer-locally", true, Some(20), 200),
+            ],
+        )?;
+        import(&mut target, &state_path, strategy)?;
+
+        database::get_video_states(&target)
+    }
+
+    #[test]
+    fn watched_wins() -> Result<()> {
+        assert_eq!(
+            merge(MergeStrategy::WatchedWins)?,
+            [
+                video_state("newer-locally", true, Some(20), 200),
+                video_state("older-locally", true, Some(30), 200),
+                video_state("only-in-source", true, None, 100),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn newest_wins() -> Result<()> {
+        assert_eq!(
+            merge(MergeStrategy::Newest)?,
+            [
+                video_state("newer-locally", true, Some(20), 200),
+                video_state("older-locally", false, Some(30), 200),
+                video_state("only-in-source", true, None, 100),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn overwrite() -> Result<()> {
+        assert_eq!(
+            merge(MergeStrategy::Overwrite)?,
+            [
+                video_state("newer-locally", false, None, 100),
+                video_state("older-locally", false, Some(30), 200),
+                video_state("only-in-source", true, None, 100),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn tags_of_unsubscribed_channels_are_skipped() -> Result<()> {
+        let directory = tempdir()?;
+        let state_path = directory.path().join("state.json");
+
+        let source = create_database(&directory.path().join("source.db"), &[])?;
+        database::create_channel(
+            &source,
+            &Channel::new("other-channel".to_owned(), "Other".to_owned(), None),
+        )?;
+        database::create_tag(&source, "music")?;
+        database::create_tag(&source, "empty")?;
+        database::add_channel_to_tag(&source, "music", CHANNEL_ID)?;
+        database::add_channel_to_tag(&source, "music", "other-channel")?;
+        assert_eq!(export(&source, &state_path)?, (0, 2));
+
+        let mut target = create_database(&directory.path().join("target.db"), &[])?;
+        database::create_tag(&target, "music")?;
+        let summary = import(&mut target, &state_path, MergeStrategy::WatchedWins)?;
+
+        assert_eq!(summary.skipped_channels, 1);
+        assert_eq!(database::get_tags(&target)?.len(), 2);
+        assert_eq!(
+            database::get_tag_relations(&target)?,
+            [("music".to_owned(), CHANNEL_ID.to_owned())]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn failed_import_changes_nothing() -> Result<()> {
+        let directory = tempdir()?;
+        let state_path = directory.path().join("state.json");
+
+        // the second tag with the same name fails to be created
+        std::fs::write(
+            &state_path,
+            serde_json::json!({
+                "version": 1,
+                "exported_at": 0,
+                "videos": [{ "video_id": "video", "watched": true }],
+                "tags": [
+                    { "name": "music", "channels": [CHANNEL_ID] },
+                    { "name": "music", "channels": [] }
+                ]
+            })
+            .to_string(),
+        )?;
+
+        let mut target = create_database(&directory.path().join("target.db"), &[])?;
+
+        assert!(import(&mut target, &state_path, MergeStrategy::WatchedWins).is_err());
+        assert!(database::get_video_states(&target)?.is_empty());
+        assert!(database::get_tags(&target)?.is_empty());
+        assert!(database::get_tag_relations(&target)?.is_empty());
+
+        Ok(())
+    }
+}
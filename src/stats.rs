@@ -0,0 +1,187 @@
+use crate::{
+    database,
+    utils::{self, HOUR, MINUTE, MONTH, WEEK},
+};
+use anyhow::Result;
+use rusqlite::Connection;
+use std::cmp::Reverse;
+
+/// Number of weeks covered by the upload and watch activity.
+pub const WEEKS: usize = 12;
+
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelStats {
+    pub channel_id: String,
+    pub channel_name: String,
+    pub videos: u64,
+    pub watched: u64,
+    /// Length of the watched videos and the positions of the partially
+    /// watched ones, in seconds.
+    pub watch_time: u64,
+    pub last_watched: Option<u64>,
+    /// Videos published in the last `WEEKS` weeks.
+    pub recent_uploads: u64,
+}
+
+impl ChannelStats {
+    pub const fn unwatched(&self) -> u64 {
+        self.videos - self.watched
+    }
+
+    pub fn uploads_per_week(&self) -> f64 {
+        self.recent_uploads as f64 / WEEKS as f64
+    }
+}
+
+pub struct Stats {
+    pub channels: Vec<ChannelStats>,
+    /// Videos published in each of the last `WEEKS` weeks, oldest first.
+    pub uploads_per_week: [u64; WEEKS],
+    /// Videos marked as watched in each of the last `WEEKS` weeks, oldest
+    /// first.
+    pub watched_per_week: [u64; WEEKS],
+    pub generated_at: u64,
+}
+
+impl Stats {
+    pub fn collect(conn: &Connection, now: u64) -> Result<Self> {
+        let since = now.saturating_sub(WEEKS as u64 * WEEK);
+
+        Ok(Self {
+            channels: database::get_channel_stats(conn, since)?,
+            uploads_per_week: per_week(database::get_upload_times(conn, since)?, now),
+            watched_per_week: per_week(database::get_watch_times(conn, since)?, now),
+            generated_at: now,
+        })
+    }
+
+    pub fn watched(&self) -> u64 {
+        self.channels.iter().map(|channel| channel.watched).sum()
+    }
+
+    pub fn unwatched(&self) -> u64 {
+        self.channels.iter().map(ChannelStats::unwatched).sum()
+    }
+
+    pub fn watch_time(&self) -> u64 {
+        self.channels.iter().map(|channel| channel.watch_time).sum()
+    }
+
+    /// Channels with any watch time, most watched first.
+    pub fn most_watched(&self) -> Vec<&ChannelStats> {
+        let mut channels: Vec<&ChannelStats> = self
+            .channels
+            .iter()
+            .filter(|channel| channel.watch_time > 0)
+            .collect();
+
+        channels.sort_by_key(|channel| Reverse(channel.watch_time));
+
+        channels
+    }
+
+    /// Channels that had no video marked as watched in the last `months`
+    /// months, the candidates for unsubscribing.
+    pub fn inactive_channels(&self, months: u64) -> Vec<&ChannelStats> {
+        let cutoff = self.generated_at.saturating_sub(months * MONTH);
+
+        self.channels
+            .iter()
+            .filter(|channel| channel.last_watched.is_none_or(|time| time < cutoff))
+            .collect()
+    }
+}
+
+fn per_week(times: Vec<u64>, now: u64) -> [u64; WEEKS] {
+    let mut weeks = [0; WEEKS];
+
+    for time in times {
+        let age = (now.saturating_sub(time) / WEEK) as usize;
+
+        if age < WEEKS {
+            weeks[WEEKS - 1 - age] += 1;
+        }
+    }
+
+    weeks
+}
+
+pub fn watch_time_text(seconds: u64) -> String {
+    format!("{}h {:02}m", seconds / HOUR, seconds % HOUR / MINUTE)
+}
+
+pub fn last_watched_text(last_watched: Option<u64>) -> String {
+    match last_watched {
+        Some(_) => format!("{} ago", utils::refresh_age(last_watched)),
+        None => "never".to_owned(),
+    }
+}
+
+/// Renders the values as a line of block characters for the terminal.
+pub fn sparkline(values: &[u64]) -> String {
+    let max = values.iter().copied().max().unwrap_or_default().max(1);
+
+    values
+        .iter()
+        .map(|value| SPARKLINE_BARS[(value * (SPARKLINE_BARS.len() as u64 - 1) / max) as usize])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChannelStats, Stats, WEEKS, per_week, sparkline};
+    use crate::utils::{MONTH, WEEK};
+
+    fn channel(name: &str, watch_time: u64, last_watched: Option<u64>) -> ChannelStats {
+        ChannelStats {
+            channel_id: name.to_owned(),
+            channel_name: name.to_owned(),
+            videos: 4,
+            watched: 1,
+            watch_time,
+            last_watched,
+            recent_uploads: 6,
+        }
+    }
+
+    #[test]
+    fn channels_are_ranked_and_inactive_ones_found() {
+        let now = 12 * MONTH;
+        let stats = Stats {
+            channels: vec![
+                channel("never", 0, None),
+                channel("old", 60, Some(now - 7 * MONTH)),
+                channel("recent", 600, Some(now - MONTH)),
+            ],
+            uploads_per_week: [0; WEEKS],
+            watched_per_week: [0; WEEKS],
+            generated_at: now,
+        };
+
+        let names = |channels: Vec<&ChannelStats>| -> Vec<String> {
+            channels
+                .into_iter()
+                .map(|channel| channel.channel_name.clone())
+                .collect()
+        };
+
+        assert_eq!(names(stats.most_watched()), ["recent", "old"]);
+        assert_eq!(names(stats.inactive_channels(6)), ["never", "old"]);
+        assert_eq!(stats.watched(), 3);
+        assert_eq!(stats.unwatched(), 9);
+        assert_eq!(stats.channels[0].uploads_per_week(), 0.5);
+    }
+
+    #[test]
+    fn activity_is_grouped_by_week() {
+        let now = 100 * WEEK;
+        let weeks = per_week(vec![now, now - WEEK, now - WEEK - 1, now - 20 * WEEK], now);
+
+        assert_eq!(weeks[WEEKS - 1], 1);
+        assert_eq!(weeks[WEEKS - 2], 2);
+        assert_eq!(weeks.iter().sum::<u64>(), 3);
+        assert_eq!(sparkline(&[0, 1, 7]), "▁▂█");
+    }
+}
@@ -38,11 +38,14 @@ impl Formats {
     }
 
     fn set_preferred(&mut self) {
+        let settings = &self.spec.channel_settings;
+        let video_quality = settings.video_quality.unwrap_or(CONFIG.video_quality);
+        let preferred_video_codec = settings.video_codec.or(CONFIG.preferred_video_codec);
         let mut video_idx = None;
 
         for (idx, format) in self.video_formats.items.iter().enumerate() {
-            if let Some(preferred_codec) = &CONFIG.preferred_video_codec {
-                if CONFIG.video_quality == format.get_quality() {
+            if let Some(preferred_codec) = &preferred_video_codec {
+                if video_quality == format.get_quality() {
                     video_idx = Some(idx);
                 }
 
@@ -53,7 +56,7 @@ impl Formats {
                         _ => (),
                     }
                 }
-            } else if CONFIG.video_quality == format.get_quality() {
+            } else if video_quality == format.get_quality() {
                 video_idx = Some(idx);
                 break;
             }
@@ -169,17 +172,22 @@ impl Formats {
         }
     }
 
-    pub fn get_selected_video_url(&self) -> Option<(&str, Option<&str>)> {
+    pub fn get_selected_video_formats(&self) -> Option<(&Format, Option<&Format>)> {
         Some(if self.use_adaptive_streams {
             (
-                self.video_formats.get_selected_item()?.get_url(),
-                Some(self.audio_formats.get_selected_item()?.get_url()),
+                self.video_formats.get_selected_item()?,
+                Some(self.audio_formats.get_selected_item()?),
             )
         } else {
-            (self.formats.get_selected_item()?.get_url(), None)
+            (self.formats.get_selected_item()?, None)
         })
     }
 
+    pub fn get_selected_video_url(&self) -> Option<(&str, Option<&str>)> {
+        self.get_selected_video_formats()
+            .map(|(video, audio)| (video.get_url(), audio.map(Format::get_url)))
+    }
+
     pub fn get_selected_audio_url(&self) -> Option<&str> {
         self.audio_formats
             .get_selected_item()
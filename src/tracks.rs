@@ -0,0 +1,104 @@
+use crate::{
+    list::SelectionList,
+    mpv::{PlaybackTrack, TrackKind},
+};
+
+/// Audio and subtitle tracks of the playing video in the track selection
+/// window.
+#[derive(Default)]
+pub struct Tracks {
+    pub audio: SelectionList<PlaybackTrack>,
+    pub subtitles: SelectionList<PlaybackTrack>,
+    pub selected_tab: usize,
+}
+
+impl Tracks {
+    pub fn new(tracks: &[PlaybackTrack]) -> Self {
+        let of_kind = |kind| {
+            tracks
+                .iter()
+                .filter(|track| track.kind == kind)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let mut subtitles = vec![PlaybackTrack::off(TrackKind::Subtitle)];
+        subtitles.extend(of_kind(TrackKind::Subtitle));
+
+        let mut tracks = Self {
+            audio: SelectionList::new(of_kind(TrackKind::Audio)),
+            subtitles: SelectionList::new(subtitles),
+            selected_tab: 0,
+        };
+
+        for list in [&mut tracks.audio, &mut tracks.subtitles] {
+            for item in &mut list.items {
+                item.selected = item.item.selected;
+            }
+        }
+
+        let subtitles_off = !tracks.subtitles.items.iter().any(|item| item.selected);
+        if let Some(off) = tracks.subtitles.items.first_mut() {
+            off.selected = subtitles_off;
+        }
+
+        if tracks.audio.items.is_empty() {
+            tracks.selected_tab = 1;
+        }
+
+        tracks
+    }
+
+    pub fn get_mut_selected_tab(&mut self) -> &mut SelectionList<PlaybackTrack> {
+        match self.selected_tab {
+            0 => &mut self.audio,
+            _ => &mut self.subtitles,
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        if !self.audio.items.is_empty() {
+            self.selected_tab = (self.selected_tab + 1) % 2;
+        }
+    }
+
+    pub fn previous_tab(&mut self) {
+        self.next_tab();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tracks;
+    use crate::mpv::{PlaybackTrack, TrackKind};
+
+    fn track(id: &str, kind: TrackKind, selected: bool) -> PlaybackTrack {
+        PlaybackTrack {
+            id: id.to_owned(),
+            kind,
+            title: None,
+            language: None,
+            codec: None,
+            selected,
+        }
+    }
+
+    #[test]
+    fn tracks_are_split_by_kind_with_an_off_entry() {
+        let mut tracks = Tracks::new(&[
+            track("1", TrackKind::Audio, true),
+            track("1", TrackKind::Subtitle, false),
+        ]);
+
+        assert_eq!(tracks.audio.get_selected_item().unwrap().id, "1");
+        assert_eq!(tracks.subtitles.get_selected_item().unwrap().id, "no");
+        assert_eq!(tracks.subtitles.items.len(), 2);
+
+        tracks.next_tab();
+        assert_eq!(tracks.get_mut_selected_tab().items.len(), 2);
+
+        let tracks = Tracks::new(&[track("2", TrackKind::Subtitle, true)]);
+        assert_eq!(tracks.selected_tab, 1);
+        assert_eq!(tracks.subtitles.get_selected_item().unwrap().id, "2");
+    }
+}
@@ -0,0 +1,89 @@
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrashKind {
+    Channel,
+    Video,
+    Tag,
+}
+
+impl TrashKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Channel => "channel",
+            Self::Video => "video",
+            Self::Tag => "tag",
+        }
+    }
+}
+
+impl TryFrom<&str> for TrashKind {
+    type Error = anyhow::Error;
+
+    fn try_from(kind: &str) -> Result<Self, Self::Error> {
+        let kind = match kind {
+            "channel" => Self::Channel,
+            "video" => Self::Video,
+            "tag" => Self::Tag,
+            _ => anyhow::bail!("\"{kind}\" is an invalid trash entry kind"),
+        };
+
+        Ok(kind)
+    }
+}
+
+/// A deleted channel, video or tag that can be restored.
+#[derive(Debug)]
+pub struct TrashEntry {
+    pub id: i64,
+    pub kind: TrashKind,
+    pub name: String,
+    pub deleted_at: u64,
+}
+
+impl Display for TrashEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            TrashKind::Channel => "Channel",
+            TrashKind::Video => "Video",
+            TrashKind::Tag => "Tag",
+        };
+
+        write!(
+            f,
+            "{kind:7} {} ({})",
+            self.name,
+            utils::refresh_age(Some(self.deleted_at))
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashedVideo {
+    pub video_id: String,
+    pub channel_id: String,
+    pub title: Option<String>,
+    pub published: Option<i64>,
+    pub length: Option<i64>,
+    pub tab: Option<i64>,
+    pub members_only: Option<bool>,
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashedChannel {
+    pub channel_id: String,
+    pub channel_name: Option<String>,
+    pub last_refreshed: Option<i64>,
+    pub updated_at: Option<i64>,
+    pub videos: Vec<TrashedVideo>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashedTag {
+    pub tag_name: String,
+    pub channels: Vec<String>,
+}
@@ -1,22 +1,25 @@
 use crate::app::{App, Mode, Selected};
+use crate::autoplay::PendingAutoplay;
 use crate::channel::{HideVideos, tabs_to_be_loaded};
 use crate::config::VideoInfoPosition;
 use crate::help::HelpWindowState;
 use crate::input::InputMode;
 use crate::list::{Scrollable, StatefulList};
 use crate::message::MessageType;
-use crate::mpv::PlaybackPhase;
+use crate::mpv::{PlaybackChapter, PlaybackPhase};
 use crate::search::SearchDirection;
+use crate::stats::{self, WEEKS};
 use crate::stream_formats::Formats;
-use crate::utils::length_as_hhmmss;
+use crate::tracks::Tracks;
+use crate::utils::{length_as_hhmmss, refresh_age};
 use crate::{CONFIG, HELP, THEME};
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Rect};
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{
-    Block, Borders, Cell, Clear, Gauge, List, ListItem, ListState, Paragraph, Row, Table, Tabs,
-    Wrap,
+    Bar, BarChart, BarGroup, Block, Borders, Cell, Clear, Gauge, List, ListItem, ListState,
+    Paragraph, Row, Sparkline, Table, Tabs, Wrap,
 };
 use std::fmt::Display;
 use unicode_width::UnicodeWidthStr;
@@ -25,7 +28,7 @@ use utils::{Column, TitleBuilder, filter_columns};
 mod utils;
 
 pub fn draw(f: &mut Frame, app: &mut App) {
-    let show_player = app.is_player_active();
+    let show_player = app.is_player_active() || app.autoplay.is_some();
     let show_footer = app.is_footer_active();
 
     let mut constraints = vec![Constraint::Min(1)];
@@ -62,7 +65,11 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 
     let input_mode = if matches!(
         app.input_mode,
-        InputMode::Search | InputMode::TagCreation | InputMode::TagRenaming
+        InputMode::Search
+            | InputMode::TagCreation
+            | InputMode::TagRenaming
+            | InputMode::FilterCreation
+            | InputMode::ChannelSettingEditing
     ) {
         &app.prev_input_mode
     } else {
@@ -76,6 +83,27 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             draw_list_with_help(f, "Import".to_string(), &mut app.import_state, &HELP.import)
         }
         InputMode::Tag => draw_list_with_help(f, "Tags".to_string(), &mut app.tags, &HELP.tag),
+        InputMode::Trash => {
+            draw_list_with_help(f, "Trash".to_string(), &mut app.trash, &HELP.trash)
+        }
+        InputMode::Downloads => draw_list_with_help(
+            f,
+            "Downloads".to_string(),
+            &mut app.downloads,
+            &HELP.downloads,
+        ),
+        InputMode::Filter => draw_list_with_help(
+            f,
+            "Filter Rules".to_string(),
+            &mut app.filter_rules,
+            &HELP.filter,
+        ),
+        InputMode::ChannelSettings => draw_list_with_help(
+            f,
+            format!("Settings of {}", app.settings_channel_name),
+            &mut app.channel_settings,
+            &HELP.channel_settings,
+        ),
         InputMode::ChannelSelection => draw_list_with_help(
             f,
             app.tags.get_selected().unwrap().item.clone(),
@@ -83,6 +111,14 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             &HELP.channel_selection,
         ),
         InputMode::FormatSelection => draw_format_selection(f, &mut app.stream_formats),
+        InputMode::Stats => draw_stats(f, app),
+        InputMode::TrackSelection => draw_track_selection(f, &mut app.tracks),
+        InputMode::ChapterSelection => draw_list_with_help(
+            f,
+            "Chapters".to_string(),
+            &mut app.chapters,
+            &HELP.chapter_selection,
+        ),
         _ => {
             if let Some(thumbnail) = &mut app.thumbnail {
                 thumbnail.covered_area.take();
@@ -113,13 +149,27 @@ fn draw_channels(f: &mut Frame, app: &mut App, area: Rect) {
         .channels
         .items
         .iter()
-        .map(Line::from)
-        .map(ListItem::new)
+        .map(|channel| {
+            let mut line = Line::from(channel);
+
+            if app.offline {
+                line.push_span(Span::styled(
+                    format!(" {}", refresh_age(channel.last_refreshed)),
+                    THEME.refresh_age,
+                ));
+            }
+
+            ListItem::new(line)
+        })
         .collect::<Vec<ListItem>>();
 
     let selected_tags = app.tags.get_selected_items();
     let title = TitleBuilder::new(area.width.into())
-        .title("Channels".to_string())
+        .title(if app.offline {
+            "Channels (offline)".to_string()
+        } else {
+            "Channels".to_string()
+        })
         .list(&app.channels)
         .tags(selected_tags)
         .build_title();
@@ -208,7 +258,8 @@ fn draw_videos(f: &mut Frame, app: &mut App, area: Rect) {
         });
 
     let mut title = TitleBuilder::new(video_area.width.into())
-        .hide_flag(app.hide_videos.contains(HideVideos::WATCHED));
+        .hide_flag(app.hide_videos.contains(HideVideos::WATCHED))
+        .filtered(app.tabs.get_selected().map_or(0, |tab| tab.filtered));
 
     if app.mode == Mode::LatestVideos {
         let selected_tags = app.tags.get_selected_items();
@@ -252,6 +303,22 @@ fn draw_videos(f: &mut Frame, app: &mut App, area: Rect) {
                         if video.members_only { " [M]" } else { "" },
                         THEME.members_only_indicator,
                     ),
+                    Span::styled(
+                        if video.dismissed { " [D]" } else { "" },
+                        THEME.dismissed_indicator,
+                    ),
+                    Span::styled(
+                        if video.filtered { " [F]" } else { "" },
+                        THEME.filtered_indicator,
+                    ),
+                    Span::styled(
+                        if video.local_file.is_some() {
+                            " [L]"
+                        } else {
+                            ""
+                        },
+                        THEME.downloaded_indicator,
+                    ),
                     Span::styled(
                         if video.is_new { " [N]" } else { "" },
                         THEME.new_video_indicator,
@@ -370,6 +437,11 @@ fn to_info_line<'a>(field: &'a str, value: &'a str) -> Line<'a> {
 }
 
 fn draw_player(f: &mut Frame, app: &App, area: Rect) {
+    if let Some(autoplay) = &app.autoplay {
+        draw_autoplay_countdown(f, autoplay, area);
+        return;
+    }
+
     let state = &app.playback_state;
 
     let phase = match &state.phase {
@@ -402,10 +474,43 @@ fn draw_player(f: &mut Frame, app: &App, area: Rect) {
         .right_aligned()
     });
 
+    let speed_title = state.speed.map(|speed| {
+        Line::from(vec![
+            Span::styled("Speed:", THEME.title),
+            Span::raw(format!(" {speed}x")),
+        ])
+        .right_aligned()
+    });
+
     let mut block = Block::default().borders(Borders::ALL).title(title);
+    if let Some(speed_title) = speed_title {
+        block = block.title(speed_title);
+    }
     if let Some(volume_title) = volume_title {
         block = block.title(volume_title);
     }
+    if let Some(sleep_timer) = &state.sleep_timer {
+        block = block.title_bottom(
+            Line::from(vec![
+                Span::styled("Sleep:", THEME.title),
+                Span::raw(format!(" {sleep_timer} ")),
+            ])
+            .right_aligned(),
+        );
+    }
+    if let Some(chapter) = state.current_chapter() {
+        block = block.title_bottom(Line::from(vec![
+            Span::styled(
+                format!(
+                    "Chapter {}/{}:",
+                    state.chapter.unwrap_or_default() + 1,
+                    state.chapters.len()
+                ),
+                THEME.title,
+            ),
+            Span::raw(format!(" {} ", chapter.title)),
+        ]));
+    }
 
     let inner_area = block.inner(area);
     f.render_widget(block, area);
@@ -427,14 +532,75 @@ fn draw_player(f: &mut Frame, app: &App, area: Rect) {
         _ => 0.0,
     };
 
+    let label = format!("{elapsed} / {duration}");
+    let label_width = label.width() as u16;
+
     let progress = Gauge::default()
         .style(THEME.progress_bar_unfilled)
         .gauge_style(THEME.progress_bar)
         .ratio(ratio)
-        .label(format!("{elapsed} / {duration}"))
+        .label(label)
         .use_unicode(CONFIG.unicode_progress_bar);
 
     f.render_widget(progress, inner_area);
+
+    if let Some(duration) = state.duration.filter(|duration| *duration > 0) {
+        draw_chapter_ticks(f, &state.chapters, duration, inner_area, label_width);
+    }
+}
+
+fn draw_autoplay_countdown(f: &mut Frame, autoplay: &PendingAutoplay, area: Rect) {
+    let metadata = &autoplay.spec.metadata;
+    let remaining = autoplay.remaining_seconds();
+
+    let title = Line::from(vec![
+        Span::styled("[Up next]", THEME.title),
+        Span::raw(format!(" {} - {} ", metadata.channel, metadata.title)),
+    ]);
+
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let ratio = match CONFIG.autoplay_countdown {
+        0 => 1.0,
+        countdown => (1.0 - remaining as f64 / countdown as f64).clamp(0.0, 1.0),
+    };
+
+    let countdown = Gauge::default()
+        .block(block)
+        .style(THEME.progress_bar_unfilled)
+        .gauge_style(THEME.progress_bar)
+        .ratio(ratio)
+        .label(format!("Playing in {remaining}s, stop playback to cancel"))
+        .use_unicode(CONFIG.unicode_progress_bar);
+
+    f.render_widget(countdown, area);
+}
+
+/// Marks the start of each chapter on the progress bar, leaving the centered
+/// label readable.
+fn draw_chapter_ticks(
+    f: &mut Frame,
+    chapters: &[PlaybackChapter],
+    duration: u64,
+    area: Rect,
+    label_width: u16,
+) {
+    let label_start = area.x + area.width.saturating_sub(label_width) / 2;
+    let label_end = label_start + label_width;
+    let buffer = f.buffer_mut();
+
+    for chapter in chapters.iter().filter(|chapter| chapter.start > 0) {
+        let x = area.x + (chapter.start.min(duration) * u64::from(area.width) / duration) as u16;
+
+        if x >= area.right() || (label_start..label_end).contains(&x) {
+            continue;
+        }
+
+        for y in area.top()..area.bottom() {
+            if let Some(cell) = buffer.cell_mut((x, y)) {
+                cell.set_symbol("│");
+            }
+        }
+    }
 }
 
 fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
@@ -457,6 +623,14 @@ fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
             Span::raw("Tag name: "),
             Span::raw(&app.input),
         ])),
+        InputMode::FilterCreation => Paragraph::new(Line::from(vec![
+            Span::raw("Filter rule: "),
+            Span::raw(&app.input),
+        ])),
+        InputMode::ChannelSettingEditing => Paragraph::new(Line::from(vec![
+            Span::raw("Value: "),
+            Span::raw(&app.input),
+        ])),
         InputMode::Subscribe => Paragraph::new(Line::from(vec![
             Span::raw("Enter channel id or url: "),
             Span::raw(&app.input),
@@ -595,6 +769,190 @@ fn draw_format_selection(f: &mut Frame, stream_formats: &mut Formats) -> Rect {
     )
 }
 
+fn draw_track_selection(f: &mut Frame, tracks: &mut Tracks) -> Rect {
+    let tabs = Tabs::new(vec![
+        Line::from(Span::styled(
+            "Audio",
+            if tracks.audio.items.is_empty() {
+                THEME.watched
+            } else {
+                Style::default()
+            },
+        )),
+        Line::from("Subtitles"),
+    ])
+    .select(tracks.selected_tab)
+    .highlight_style(THEME.selected);
+
+    draw_list_with_help_tabs(
+        f,
+        "Tracks".to_string(),
+        Some(tabs),
+        tracks.get_mut_selected_tab(),
+        &HELP.track_selection,
+    )
+}
+
+fn draw_stats(f: &mut Frame, app: &mut App) -> Rect {
+    const MOST_WATCHED_LIMIT: usize = 10;
+
+    let window = popup_window_from_percentage(90, 90, f.area());
+    f.render_widget(Clear, window);
+
+    let Some(stats) = &app.stats else {
+        return window;
+    };
+
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled("Statistics", THEME.title)),
+        window,
+    );
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(6),
+            Constraint::Length(MOST_WATCHED_LIMIT as u16 + 2),
+            Constraint::Min(4),
+            Constraint::Length(1),
+        ])
+        .split(window);
+
+    let summary = format!(
+        "{} watched, {} unwatched, {} watched in total",
+        stats.watched(),
+        stats.unwatched(),
+        stats::watch_time_text(stats.watch_time())
+    );
+    f.render_widget(Paragraph::new(summary), chunks[0]);
+
+    let activity_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
+
+    for (area, title, values) in [
+        (activity_chunks[0], "Uploads", &stats.uploads_per_week),
+        (activity_chunks[1], "Watched", &stats.watched_per_week),
+    ] {
+        let sparkline = Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "{title} per week ({} in {WEEKS} weeks)",
+                values.iter().sum::<u64>()
+            )))
+            .data(values)
+            .style(THEME.header);
+        f.render_widget(sparkline, area);
+    }
+
+    let channel_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[2]);
+
+    let bars: Vec<Bar> = stats
+        .most_watched()
+        .into_iter()
+        .take(MOST_WATCHED_LIMIT)
+        .map(|channel| {
+            Bar::default()
+                .label(channel.channel_name.as_str())
+                .value(channel.watch_time)
+                .text_value(stats::watch_time_text(channel.watch_time))
+        })
+        .collect();
+    let most_watched = BarChart::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Most watched channels"),
+        )
+        .direction(Direction::Horizontal)
+        .bar_width(1)
+        .bar_gap(0)
+        .bar_style(THEME.progress_bar)
+        .data(BarGroup::default().bars(&bars));
+    f.render_widget(most_watched, channel_chunks[0]);
+
+    let inactive_channels: Vec<ListItem> = stats
+        .inactive_channels(CONFIG.inactive_channel_months)
+        .into_iter()
+        .map(|channel| {
+            ListItem::new(format!(
+                "{} ({})",
+                channel.channel_name,
+                stats::last_watched_text(channel.last_watched)
+            ))
+        })
+        .collect();
+    f.render_widget(
+        List::new(inactive_channels).block(Block::default().borders(Borders::ALL).title(format!(
+            "No watched videos in {} months",
+            CONFIG.inactive_channel_months
+        ))),
+        channel_chunks[1],
+    );
+
+    let rows = app.stats_channels.items.iter().map(|channel| {
+        Row::new([
+            Cell::from(channel.channel_name.as_str()),
+            Cell::from(channel.watched.to_string()),
+            Cell::from(channel.unwatched().to_string()),
+            Cell::from(stats::watch_time_text(channel.watch_time)),
+            Cell::from(format!("{:.1}", channel.uploads_per_week())),
+            Cell::from(stats::last_watched_text(channel.last_watched)),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(20),
+            Constraint::Length(7),
+            Constraint::Length(9),
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Length(12),
+        ],
+    )
+    .block(Block::default().borders(Borders::ALL))
+    .header(
+        Row::new([
+            "Channel",
+            "Watched",
+            "Unwatched",
+            "Watch time",
+            "Uploads/week",
+            "Last watched",
+        ])
+        .style(THEME.header),
+    )
+    .column_spacing(2)
+    .highlight_symbol(&*CONFIG.highlight_symbol)
+    .row_highlight_style(THEME.focused);
+
+    app.stats_channels.visible_lines = chunks[3].height.saturating_sub(3);
+    f.render_stateful_widget(table, chunks[3], &mut app.stats_channels.state);
+
+    let help_text = Line::from(
+        HELP.stats
+            .iter()
+            .flat_map(|entry| {
+                [
+                    Span::styled(entry.0.clone(), THEME.help),
+                    Span::raw(entry.1),
+                ]
+            })
+            .collect::<Vec<Span>>(),
+    );
+    f.render_widget(Paragraph::new(help_text), chunks[4]);
+
+    window
+}
+
 fn draw_list_with_help<T: Display>(
     f: &mut Frame,
     title: String,
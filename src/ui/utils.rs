@@ -15,6 +15,7 @@ use unicode_width::UnicodeWidthStr;
 pub struct TitleBuilder<'a, T, S: State> {
     title: String,
     hide_flag: bool,
+    filtered: usize,
     list: Option<&'a StatefulList<T, S>>,
     tabs: Option<&'a StatefulList<Tab, ListState>>,
     tags: Option<Vec<&'a String>>,
@@ -26,6 +27,7 @@ impl<'a, T, S: State> TitleBuilder<'a, T, S> {
         Self {
             title: String::new(),
             hide_flag: false,
+            filtered: 0,
             list: None,
             tabs: None,
             tags: None,
@@ -43,6 +45,11 @@ impl<'a, T, S: State> TitleBuilder<'a, T, S> {
         self
     }
 
+    pub const fn filtered(mut self, count: usize) -> Self {
+        self.filtered = count;
+        self
+    }
+
     pub const fn list(mut self, list: &'a StatefulList<T, S>) -> Self {
         self.list = Some(list);
         self
@@ -119,6 +126,14 @@ impl<'a, T, S: State> TitleBuilder<'a, T, S> {
             self.available_width = self.available_width.saturating_sub(4);
         }
 
+        let filtered_text = format!("[{} filtered]", self.filtered);
+
+        if self.filtered > 0 {
+            self.available_width = self
+                .available_width
+                .saturating_sub(filtered_text.width() + 1);
+        }
+
         let position = self.list.map_or_else(
             || Span::raw(""),
             |list| {
@@ -172,6 +187,11 @@ impl<'a, T, S: State> TitleBuilder<'a, T, S> {
             title_sections.push(Span::styled("[H]", THEME.title));
         }
 
+        if self.filtered > 0 {
+            title_sections.push(Span::raw(border_symbol_top));
+            title_sections.push(Span::styled(filtered_text, THEME.title));
+        }
+
         if let Some(p_gap_width) = self
             .available_width
             .checked_sub(required_width_for_position)
@@ -50,6 +50,15 @@ pub fn get_cache_dir() -> Result<PathBuf> {
     Ok(path)
 }
 
+/// Returns the directory that downloads are saved to when `download_dir` is
+/// not set.
+pub fn get_default_download_dir() -> Result<PathBuf> {
+    match dirs::download_dir() {
+        Some(path) => Ok(path.join(PACKAGE_NAME)),
+        None => Ok(get_data_dir()?.join("downloads")),
+    }
+}
+
 fn hyperlink(text: &str, link: &str) -> String {
     format!("\x1b]8;;{link}\x1b\\{text}\x1b]8;;\x1b\\")
 }
@@ -186,11 +195,11 @@ pub fn length_as_hhmmss(length: u32) -> String {
     }
 }
 
-const MINUTE: u64 = 60;
-const HOUR: u64 = 3600;
+pub const MINUTE: u64 = 60;
+pub const HOUR: u64 = 3600;
 const DAY: u64 = 86400;
-const WEEK: u64 = 604800;
-const MONTH: u64 = 2592000;
+pub const WEEK: u64 = 604800;
+pub const MONTH: u64 = 2592000;
 const YEAR: u64 = 31536000;
 
 pub fn published_text_as_timestamp(published_text: &str) -> Result<u64> {
@@ -286,6 +295,46 @@ pub fn published_text(published: u64, stream: bool) -> Option<String> {
     Some(text)
 }
 
+pub fn refresh_age(last_refreshed: Option<u64>) -> String {
+    let Some(time_diff) = last_refreshed.and_then(|time| time_passed(time).ok()) else {
+        return String::from("never");
+    };
+
+    if time_diff < MINUTE {
+        String::from("now")
+    } else if time_diff < HOUR {
+        format!("{}m", time_diff / MINUTE)
+    } else if time_diff < DAY {
+        format!("{}h", time_diff / HOUR)
+    } else if time_diff < WEEK * 2 {
+        format!("{}d", time_diff / DAY)
+    } else if time_diff < MONTH {
+        format!("{}w", time_diff / WEEK)
+    } else if time_diff < YEAR {
+        format!("{}mo", time_diff / MONTH)
+    } else {
+        format!("{}y", time_diff / YEAR)
+    }
+}
+
+pub fn file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
 pub fn now() -> Result<u64> {
     Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
 }
@@ -305,8 +354,9 @@ pub fn env_var_is_set(name: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::{
-        length_as_hhmmss, length_as_seconds, length_from_accessibility_label, now, params_from_url,
-        premiere_text_as_timestamp, published_text, published_text_as_timestamp,
+        file_size, length_as_hhmmss, length_as_seconds, length_from_accessibility_label, now,
+        params_from_url, premiere_text_as_timestamp, published_text, published_text_as_timestamp,
+        refresh_age,
     };
 
     #[test]
@@ -351,6 +401,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn refresh_age_conversion() {
+        let now = now().unwrap();
+
+        assert_eq!(refresh_age(None), "never");
+        assert_eq!(refresh_age(Some(now)), "now");
+        assert_eq!(refresh_age(Some(now - 300)), "5m");
+        assert_eq!(refresh_age(Some(now - 7200)), "2h");
+        assert_eq!(refresh_age(Some(now - 432000)), "5d");
+        assert_eq!(refresh_age(Some(now - 63072000)), "2y");
+    }
+
+    #[test]
+    fn file_size_conversion() {
+        assert_eq!(file_size(512), "512 B");
+        assert_eq!(file_size(1536), "1.5 KiB");
+        assert_eq!(file_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+
     #[test]
     fn premiere_conversion() {
         let mut text = "Premieres 5/27/26, 4:00 PM";
@@ -1,7 +1,8 @@
-use crate::list::ListItem;
+use crate::{channel_settings::ChannelSettings, list::ListItem};
 use std::{
     fmt::Display,
     ops::{Deref, DerefMut},
+    path::PathBuf,
 };
 
 pub struct Video {
@@ -44,6 +45,11 @@ pub struct VideoListItem {
     pub published_text: String,
     pub watched: bool,
     pub position: Option<u64>,
+    pub dismissed: bool,
+    /// The downloaded file of the video.
+    pub local_file: Option<PathBuf>,
+    /// Whether a filter rule hides the video.
+    pub filtered: bool,
     pub is_new: bool,
 }
 
@@ -87,6 +93,7 @@ impl Display for VideoListItem {
 #[derive(Default, Clone)]
 pub struct VideoMetadata {
     pub video_id: String,
+    pub channel_id: String,
     pub title: String,
     pub channel: String,
 }
@@ -95,4 +102,7 @@ pub struct VideoMetadata {
 pub struct PlaybackSpec {
     pub metadata: VideoMetadata,
     pub start_position: Option<u64>,
+    pub channel_settings: ChannelSettings,
+    /// The downloaded file that is played instead of streaming the video.
+    pub local_file: Option<PathBuf>,
 }
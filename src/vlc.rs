@@ -0,0 +1,292 @@
+use crate::client::ClientRequest;
+use crate::mpv::{
+    PlaybackEndReason, PlaybackPhase, PlaybackState, PlaybackUpdate, PlaybackUpdateCause,
+};
+use crate::process::detach_process;
+use crate::video::VideoMetadata;
+use crate::{TX, emit_msg};
+use anyhow::{Context, Result};
+use rand::prelude::*;
+use reqwest::Client;
+use serde_json::Value;
+use std::net::{Ipv4Addr, TcpListener};
+use std::time::Duration;
+use tokio::{
+    process::Command,
+    time::{Instant, MissedTickBehavior},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// Position changes that differ from the elapsed time by more than this are
+/// seeks.
+const SEEK_TOLERANCE: u64 = 3;
+/// Playback that stops this close to the end has reached the end of the file.
+const END_TOLERANCE: u64 = 3;
+
+/// The HTTP interface of VLC, enabled on a free local port with a random
+/// password.
+pub struct VlcInterface {
+    port: u16,
+    password: String,
+}
+
+impl VlcInterface {
+    pub fn new() -> Result<Self> {
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+            .local_addr()?
+            .port();
+
+        Ok(Self {
+            port,
+            password: format!("{:016x}", rand::rng().random::<u64>()),
+        })
+    }
+
+    pub fn args(&self) -> [String; 4] {
+        [
+            "--extraintf=http".to_owned(),
+            format!("--http-host={}", Ipv4Addr::LOCALHOST),
+            format!("--http-port={}", self.port),
+            format!("--http-password={}", self.password),
+        ]
+    }
+
+    async fn status(&self, client: &Client) -> Result<VlcStatus> {
+        let status = client
+            .get(format!(
+                "http://{}:{}/requests/status.json",
+                Ipv4Addr::LOCALHOST,
+                self.port
+            ))
+            .basic_auth("", Some(&self.password))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Value>()
+            .await?;
+
+        VlcStatus::parse(&status).context("invalid VLC status")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum VlcState {
+    Playing,
+    Paused,
+    Stopped,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct VlcStatus {
+    state: VlcState,
+    time: u64,
+    length: Option<u64>,
+}
+
+impl VlcStatus {
+    fn parse(status: &Value) -> Option<Self> {
+        let state = match status.get("state")?.as_str()? {
+            "playing" => VlcState::Playing,
+            "paused" => VlcState::Paused,
+            _ => VlcState::Stopped,
+        };
+
+        Some(Self {
+            state,
+            time: status.get("time")?.as_u64()?,
+            length: status
+                .get("length")
+                .and_then(Value::as_u64)
+                .filter(|length| *length > 0),
+        })
+    }
+
+    /// Returns why the status changed since the previous poll.
+    fn cause(&self, previous: Option<&Self>, interval: Duration) -> Option<PlaybackUpdateCause> {
+        let Some(previous) = previous else {
+            return Some(PlaybackUpdateCause::Loaded);
+        };
+
+        let expected = match previous.state {
+            VlcState::Playing => previous.time + interval.as_secs(),
+            _ => previous.time,
+        };
+
+        let cause = match (previous.state, self.state) {
+            (VlcState::Playing, VlcState::Paused) => PlaybackUpdateCause::Paused,
+            (VlcState::Paused, VlcState::Playing) => PlaybackUpdateCause::Resumed,
+            _ if self.time.abs_diff(expected) > SEEK_TOLERANCE => PlaybackUpdateCause::Seeked,
+            _ if self.time != previous.time => PlaybackUpdateCause::Progress,
+            _ => return None,
+        };
+
+        Some(cause)
+    }
+}
+
+fn publish(state: &PlaybackState, cause: PlaybackUpdateCause) -> Result<()> {
+    TX.send(ClientRequest::ExternalPlaybackUpdate(PlaybackUpdate {
+        state: state.clone(),
+        cause,
+    }))?;
+
+    Ok(())
+}
+
+/// Launches VLC and polls its HTTP interface until it exits, reporting the
+/// position so that progress is saved and the video is marked as watched
+/// like with mpv.
+pub async fn play_and_track(
+    mut command: Command,
+    interface: VlcInterface,
+    metadata: VideoMetadata,
+) -> Result<()> {
+    let client = Client::builder()
+        .no_proxy()
+        .timeout(POLL_INTERVAL)
+        .build()?;
+
+    detach_process(&mut command);
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            emit_msg!(error, error.to_string());
+            return Ok(());
+        }
+    };
+
+    let mut state = PlaybackState {
+        metadata: Some(metadata),
+        phase: PlaybackPhase::Loading,
+        ..PlaybackState::default()
+    };
+    publish(&state, PlaybackUpdateCause::Loading)?;
+
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+    poll.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    let started = Instant::now();
+    let mut previous = None::<VlcStatus>;
+    let mut polling = true;
+
+    let exit_status = loop {
+        tokio::select! {
+            exit_status = child.wait() => break exit_status?,
+            _ = poll.tick(), if polling => match interface.status(&client).await {
+                Ok(status) if status.state != VlcState::Stopped => {
+                    if let Some(cause) = status.cause(previous.as_ref(), POLL_INTERVAL) {
+                        state.phase = match status.state {
+                            VlcState::Paused => PlaybackPhase::Paused,
+                            _ => PlaybackPhase::Playing,
+                        };
+                        state.elapsed = Some(status.time);
+                        state.duration = status.length;
+                        publish(&state, cause)?;
+                    }
+
+                    previous = Some(status);
+                }
+                Ok(_) => (),
+                Err(_) if previous.is_none() && started.elapsed() >= CONNECT_TIMEOUT => {
+                    polling = false;
+                    emit_msg!(warning, "Couldn't connect to VLC to track the progress");
+                }
+                Err(_) => (),
+            },
+        }
+    };
+
+    let video_id = state
+        .metadata
+        .as_ref()
+        .map(|metadata| metadata.video_id.clone())
+        .unwrap_or_default();
+
+    if previous.is_none() {
+        // Without the interface, the video is marked as watched when VLC
+        // exits successfully.
+        if exit_status.success() {
+            TX.send(ClientRequest::SetWatched(video_id, true))?;
+        } else {
+            emit_msg!(error, format!("VLC exited with {exit_status}"));
+        }
+
+        return Ok(());
+    }
+
+    let reason = if !exit_status.success() {
+        PlaybackEndReason::Error
+    } else if state
+        .elapsed
+        .zip(state.duration)
+        .is_some_and(|(elapsed, duration)| elapsed + END_TOLERANCE >= duration)
+    {
+        PlaybackEndReason::Eof
+    } else {
+        PlaybackEndReason::Quit
+    };
+
+    state.phase = PlaybackPhase::Idle;
+    publish(&state, PlaybackUpdateCause::Ended(reason))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VlcState, VlcStatus};
+    use crate::mpv::PlaybackUpdateCause;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn status_changes_are_detected() {
+        let interval = Duration::from_secs(1);
+        let status = |state, time| VlcStatus {
+            state,
+            time,
+            length: Some(600),
+        };
+
+        assert_eq!(
+            VlcStatus::parse(&json!({"state": "playing", "time": 42, "length": 600})),
+            Some(status(VlcState::Playing, 42))
+        );
+        assert_eq!(
+            VlcStatus::parse(&json!({"state": "stopped", "time": 0, "length": 0}))
+                .map(|status| status.length),
+            Some(None)
+        );
+        assert_eq!(VlcStatus::parse(&json!({"state": "playing"})), None);
+
+        let playing = status(VlcState::Playing, 42);
+        let cause = |current: VlcStatus| current.cause(Some(&playing), interval);
+
+        assert!(matches!(
+            status(VlcState::Playing, 0).cause(None, interval),
+            Some(PlaybackUpdateCause::Loaded)
+        ));
+        assert!(matches!(
+            cause(status(VlcState::Playing, 43)),
+            Some(PlaybackUpdateCause::Progress)
+        ));
+        assert!(matches!(
+            cause(status(VlcState::Playing, 120)),
+            Some(PlaybackUpdateCause::Seeked)
+        ));
+        assert!(matches!(
+            cause(status(VlcState::Paused, 43)),
+            Some(PlaybackUpdateCause::Paused)
+        ));
+        assert!(
+            status(VlcState::Paused, 43)
+                .cause(Some(&status(VlcState::Paused, 43)), interval)
+                .is_none()
+        );
+        assert!(matches!(
+            status(VlcState::Playing, 43).cause(Some(&status(VlcState::Paused, 43)), interval),
+            Some(PlaybackUpdateCause::Resumed)
+        ));
+    }
+}